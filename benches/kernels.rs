@@ -0,0 +1,81 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use grasp::grasp::constructive_phase;
+use grasp::instance::Instance;
+use grasp::local_search::{local_search_2opt, TwoOpt};
+use grasp::solution::{PrLocalSearch, PrSelection, PrStop};
+
+/// Bundled instances the kernels are measured on.
+const INSTANCES: [&str; 3] = ["bays29", "berlin52", "brg180"];
+
+fn load(name: &str) -> Instance {
+    Instance::load(&format!("instances/{}.tsp", name)).expect("Failed to load the instance")
+}
+
+fn eval(c: &mut Criterion) {
+    for name in INSTANCES {
+        let instance = load(name);
+        let mut solution = constructive_phase(&instance);
+        c.bench_function(&format!("eval/{}", name), |b| {
+            b.iter(|| black_box(&mut solution).eval(&instance))
+        });
+    }
+}
+
+fn construction(c: &mut Criterion) {
+    for name in INSTANCES {
+        let instance = load(name);
+        c.bench_function(&format!("constructive_phase/{}", name), |b| {
+            b.iter(|| constructive_phase(black_box(&instance)))
+        });
+    }
+}
+
+fn two_opt(c: &mut Criterion) {
+    for name in INSTANCES {
+        let instance = load(name);
+        c.bench_function(&format!("local_search_2opt/{}", name), |b| {
+            b.iter_batched(
+                || {
+                    let mut solution = constructive_phase(&instance);
+                    solution.eval(&instance);
+                    solution
+                },
+                |mut solution| local_search_2opt(&mut solution, &instance, &|| false),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+}
+
+fn path_relinking(c: &mut Criterion) {
+    for name in INSTANCES {
+        let instance = load(name);
+        let local_optimum = || {
+            let mut solution = constructive_phase(&instance);
+            local_search_2opt(&mut solution, &instance, &|| false);
+            solution.eval(&instance);
+            solution
+        };
+        let target = local_optimum();
+        c.bench_function(&format!("path_relinking/{}", name), |b| {
+            b.iter_batched(
+                local_optimum,
+                |mut solution| {
+                    solution.path_relinking(
+                        &target,
+                        &instance,
+                        &TwoOpt,
+                        1.0,
+                        PrLocalSearch::BestOnly,
+                        PrStop::FirstImprove,
+                        PrSelection::Sequential,
+                    )
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+}
+
+criterion_group!(kernels, eval, construction, two_opt, path_relinking);
+criterion_main!(kernels);
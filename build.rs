@@ -0,0 +1,31 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/capi.rs");
+
+    // Regenerates the C header of the `capi` functions. Only functions are exported, so that
+    // public constants elsewhere in the crate stay out of the header; cbindgen leaves the file
+    // untouched when its contents do not change.
+    #[cfg(feature = "capi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config {
+            usize_is_size_t: true,
+            export: cbindgen::ExportConfig {
+                item_types: vec![cbindgen::ItemType::Functions],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_crate(&crate_dir)
+            .with_language(cbindgen::Language::C)
+            .with_include_guard("GRASP_H")
+            .with_no_includes()
+            .with_sys_include("stddef.h")
+            .with_sys_include("stdint.h")
+            .generate()
+            .expect("Failed to generate the C header")
+            .write_to_file(format!("{}/include/grasp.h", crate_dir));
+    }
+}
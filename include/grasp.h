@@ -0,0 +1,36 @@
+#ifndef GRASP_H
+#define GRASP_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Solves the TSP over the row-major `n * n` distance matrix `dist_matrix` for `time_ms`
+ * milliseconds with the basic GRASP. Writes the tour as 0-based city indices to `out_tour`
+ * and returns its length, or -1 if the arguments are invalid or the solver failed.
+ *
+ * # Safety
+ *
+ * `dist_matrix` must be valid for reads of `n * n` elements and `out_tour` for writes of `n`
+ * elements.
+ */
+int64_t grasp_solve(const int64_t *dist_matrix, size_t n, uint64_t time_ms, size_t *out_tour);
+
+/**
+ * Solves the EUC_2D TSP over the `n` cities `(xs[i], ys[i])` for `time_ms` milliseconds with
+ * the basic GRASP. Distances are rounded to integers as in TSPLIB. Writes the tour as 0-based
+ * city indices to `out_tour` and returns its length, or -1 if the arguments are invalid or the
+ * solver failed.
+ *
+ * # Safety
+ *
+ * `xs` and `ys` must be valid for reads of `n` elements and `out_tour` for writes of `n`
+ * elements.
+ */
+int64_t grasp_solve_euclidean(const double *xs,
+                              const double *ys,
+                              size_t n,
+                              uint64_t time_ms,
+                              size_t *out_tour);
+
+#endif  /* GRASP_H */
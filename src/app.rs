@@ -0,0 +1,855 @@
+//! The command-line tool. Binaries of downstream crates can register their own variants with
+//! `metaheuristic::register` and then hand over to `main`, which selects them with
+//! `--variant custom --metaheuristic <name>`.
+
+use crate::benchmark::{benchmark, expand_instance_paths, Method};
+use crate::cli::{
+    list_available_instances, Cli, Command, ConstructionKind, GraspVariant, SolveArgs, SubsetKind,
+};
+use crate::constraints::EdgeConstraints;
+use crate::error::{self, Error, ErrorKind};
+use crate::experiment::{batch_solve, multi_run, time_to_target};
+use crate::instance::Instance;
+use crate::local_search::LocalSearch;
+use crate::mtsp::mtsp;
+use crate::objective::Objective;
+use crate::optimum::{gap, known_optimum};
+use crate::pareto::{pareto_grasp, write_front};
+use crate::pctsp::{pctsp, Prizes};
+use crate::qap::Qap;
+use crate::solution::Solution;
+use crate::termination::Termination;
+use crate::trace::Trace;
+use crate::{generate, info, island, problem, svg, verify};
+use clap::{CommandFactory, Parser, ValueEnum};
+use rand::seq::IteratorRandom;
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(feature = "tui")]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tracing::{debug, info, warn, Level};
+
+/// Iteration limit of `--deterministic` runs without `--max-iterations`.
+const DETERMINISTIC_ITERATIONS: usize = 1000;
+
+/// Parses the command line and runs it, exiting the process with the code of any error.
+pub fn main() {
+    let cli = Cli::parse();
+
+    if std::env::args().len() == 1 {
+        let mut cmd = Cli::command();
+        cmd.print_help().unwrap();
+
+        let bold = "\x1b[1m";
+        let reset = "\x1b[0m";
+        let underline = "\x1b[4m";
+
+        println!(
+            "\n{}{}Available instances:{}\n{}",
+            bold,
+            underline,
+            reset,
+            list_available_instances(&cli.solve_args().instances_dir)
+        );
+        std::process::exit(0);
+    }
+
+    #[cfg(feature = "tui")]
+    let quiet = cli.quiet || cli.solve_args().tui;
+    #[cfg(not(feature = "tui"))]
+    let quiet = cli.quiet;
+    let level = match (quiet, cli.verbose) {
+        (true, _) => Level::WARN,
+        (false, 0) => Level::INFO,
+        (false, 1) => Level::DEBUG,
+        (false, _) => Level::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_target(false)
+        .with_writer(std::io::stderr)
+        .init();
+
+    let json_errors = cli.json_errors;
+    error::install_hook(json_errors);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let threads = if cli.solve_args().deterministic {
+            1
+        } else {
+            cli.threads.unwrap_or_else(|| {
+                std::thread::available_parallelism().map_or(1, |threads| threads.get())
+            })
+        };
+        if threads == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidArguments,
+                "The number of threads must be at least 1",
+            ));
+        }
+        // The solver runs on its own pool so that it never occupies the global rayon pool.
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .expect("Failed to build the thread pool");
+        pool.install(|| run(cli))
+    }))
+    .unwrap_or_else(|payload| Err(Error::from_panic(payload)));
+
+    if let Err(error) = result {
+        if json_errors {
+            eprintln!("{}", error.to_json());
+        } else if error.kind != ErrorKind::Internal {
+            // Panics were already reported by the panic hook.
+            eprintln!("Error: {}", error);
+        }
+        std::process::exit(error.kind.exit_code());
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Error> {
+    let mut args = cli.solve_args().clone();
+    if args.default {
+        args.instance_file = "bier127.tsp".to_string();
+        args.time_limit = Duration::from_secs(120);
+        args.variant = GraspVariant::Basic;
+    }
+    if args.deterministic {
+        if !matches!(cli.command, None | Some(Command::Solve(_))) {
+            return Err(Error::new(
+                ErrorKind::InvalidArguments,
+                "--deterministic only applies to single solves",
+            ));
+        }
+        args.seed.get_or_insert(0);
+        args.max_iterations.get_or_insert(DETERMINISTIC_ITERATIONS);
+        args.time_limit = Duration::MAX;
+    }
+    if let Some(seed) = args.seed {
+        crate::rng::set_seed(seed);
+    }
+    if let Some(bytes) = args.max_memory {
+        crate::memory::set_max_memory(bytes);
+    }
+    #[cfg(feature = "fetch")]
+    if let Some(name) = &args.fetch {
+        args.instance_file =
+            crate::fetch::fetch_instance(name, &args.fetch_url, &args.instances_dir)?;
+    }
+    let args = &args;
+
+    if let Some(Command::Generate {
+        cities,
+        size,
+        seed,
+        output,
+    }) = &cli.command
+    {
+        generate::write_random_instance(output, *cities, *size, *seed)?;
+        info!("Instance with {} cities saved to {}", cities, output);
+        return Ok(());
+    }
+
+    #[cfg(feature = "serve")]
+    if let Some(Command::Serve { addr }) = &cli.command {
+        crate::serve::serve(addr, args.clone());
+        return Ok(());
+    }
+
+    if let Some(Command::Info { instance }) = &cli.command {
+        return info::print_info(&args.resolve_instance(instance), args.precision);
+    }
+
+    if let Some(Command::Qap { instance }) = &cli.command {
+        let qap = Qap::load(&args.resolve_instance(instance))?;
+        debug!("Loaded {} with {} facilities", instance, qap.size);
+        let termination = args.termination();
+        let best = problem::solve(&qap, &termination, &args.engine_config());
+        println!("\nBest assignment found: {:?}", best.order);
+        println!("Cost: {}", best.cost);
+        if let Some(optimum) = args.optimum {
+            println!(
+                "Best known: {} (gap = {:.2}%)",
+                optimum,
+                gap(best.cost, optimum)
+            );
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Verify { instance, tour }) = &cli.command {
+        return verify::verify(&args.resolve_instance(instance), tour, args.optimum);
+    }
+
+    if let Some(Command::Benchmark {
+        instances,
+        local_searches,
+        variants,
+        runs,
+        time_limit,
+        output,
+        report,
+    }) = &cli.command
+    {
+        let construction = args.construction();
+        let local_searches: Vec<Box<dyn LocalSearch>> = local_searches
+            .iter()
+            .map(|kind| args.build_local_search(kind))
+            .collect();
+
+        let mut methods: Vec<Method> = local_searches
+            .iter()
+            .map(|local_search| Method {
+                name: local_search.name().to_string(),
+                run: Box::new(|instance: &Instance, _, trace: &Trace| {
+                    let mut solution = construction.construct(instance);
+                    solution.eval(instance);
+                    local_search.improve(&mut solution, instance);
+                    trace.record(1, 0, solution.total_distance);
+                    Ok(solution)
+                }),
+            })
+            .collect();
+        for variant in variants {
+            if let GraspVariant::Mtsp | GraspVariant::Pctsp | GraspVariant::Pareto = variant {
+                continue;
+            }
+            methods.push(Method {
+                name: variant.to_string(),
+                run: Box::new(|instance: &Instance, time_limit, trace: &Trace| {
+                    let termination =
+                        Termination::new(time_limit).with_max_iterations(args.max_iterations);
+                    args.solve(variant, instance, &termination, trace)
+                }),
+            });
+        }
+
+        let instances: Vec<String> = instances
+            .iter()
+            .map(|instance| args.resolve_instance(instance))
+            .collect();
+        let instance_files = expand_instance_paths(&instances);
+        let reports = benchmark(
+            &instance_files,
+            &methods,
+            *runs,
+            *time_limit,
+            args.precision,
+            output,
+        )?;
+        if let Some(report) = report {
+            let method_names: Vec<&str> = methods.iter().map(|m| m.name.as_str()).collect();
+            let parameters = [
+                ("Instances", instance_files.join(", ")),
+                ("Methods", method_names.join(", ")),
+                ("Runs", runs.to_string()),
+                ("Time limit", format!("{:?}", time_limit)),
+                ("Construction", format!("{:?}", args.construction)),
+                (
+                    "RCL",
+                    format!("{:?} (alpha = {})", args.rcl, args.rcl_alpha),
+                ),
+                ("Elite size", args.elite_size.to_string()),
+                ("Cooling rate", args.cooling_rate.to_string()),
+                (
+                    "Seed",
+                    args.seed.map_or("random".into(), |seed| seed.to_string()),
+                ),
+                ("Threads", rayon::current_num_threads().to_string()),
+            ];
+            crate::report::write_report(report, &parameters, &reports)?;
+            info!("HTML report saved to {}/index.html", report);
+        }
+        return Ok(());
+    }
+
+    if let Some(instance_dir) = &args.instance_dir {
+        if let GraspVariant::Mtsp | GraspVariant::Pctsp | GraspVariant::Pareto = args.variant {
+            return Err(Error::new(
+                ErrorKind::InvalidArguments,
+                "--instance-dir only supports the single-tour variants",
+            ));
+        }
+        return batch_solve(
+            &expand_instance_paths(&[args.resolve_instance(instance_dir)]),
+            args.parallel_instances,
+            || args.termination(),
+            args.batch_output.as_deref(),
+            |instance_file| load_instance(args, instance_file),
+            |instance, termination, trace| args.solve(&args.variant, instance, termination, trace),
+        );
+    }
+
+    let instance = load_instance(args, &args.instance_file)?;
+
+    if let GraspVariant::Mtsp = args.variant {
+        let termination = args.termination();
+        let best_solution = mtsp(
+            &instance,
+            &termination,
+            args.start_city.unwrap_or(0),
+            args.salesmen,
+            args.mtsp_objective,
+            &Trace::new(),
+        )?;
+
+        println!();
+        for (route, length) in best_solution
+            .routes
+            .iter()
+            .zip(&best_solution.route_lengths)
+        {
+            println!(
+                "Route {:?}: length = {}",
+                route,
+                instance.format_cost(*length)
+            );
+        }
+        println!(
+            "Total distance: {}",
+            instance.format_cost(best_solution.total_distance())
+        );
+        println!(
+            "Longest route: {}",
+            instance.format_cost(best_solution.longest_route())
+        );
+        return Ok(());
+    }
+
+    if let GraspVariant::Pctsp = args.variant {
+        let prizes = Prizes::load(args.prizes.as_deref().unwrap(), &instance)?;
+        let termination = args.termination();
+        let best_solution = pctsp(
+            &instance,
+            &termination,
+            &prizes,
+            args.start_city.unwrap_or(0),
+            args.min_prize,
+            &Trace::new(),
+        )?;
+
+        println!("\nBest solution found: {:?}", best_solution.path);
+        print_original_cities(&instance, &best_solution.path);
+        println!(
+            "Visited cities: {} of {}",
+            best_solution.path.len(),
+            instance.num_cities
+        );
+        println!(
+            "Total distance: {}",
+            instance.format_cost(best_solution.total_distance)
+        );
+        println!(
+            "Total penalty: {}",
+            instance.format_cost(best_solution.total_penalty)
+        );
+        println!("Collected prize: {}", best_solution.collected_prize);
+        println!("Cost: {}", instance.format_cost(best_solution.cost()));
+        return Ok(());
+    }
+
+    if let GraspVariant::Pareto = args.variant {
+        let second_file = args.resolve_instance(args.second_costs.as_deref().unwrap());
+        let mut second = Instance::load_with_precision(&second_file, args.precision)?;
+        if let Some(cities) = &instance.original_cities {
+            second = second.subset(cities)?;
+        }
+        if second.num_cities != instance.num_cities {
+            return Err(Error::new(
+                ErrorKind::Instance,
+                format!(
+                    "The second cost matrix has {} cities but the instance has {}",
+                    second.num_cities, instance.num_cities
+                ),
+            ));
+        }
+        second.open_tour = instance.open_tour;
+
+        let termination = args.termination();
+        let archive = pareto_grasp(
+            &instance,
+            &second,
+            &termination,
+            &*args.construction(),
+            &*args.local_search(),
+            &args.pareto_config(),
+            &Trace::new(),
+        )?;
+
+        println!("\nPareto front: {} tours", archive.len());
+        for point in archive.points() {
+            println!(
+                "Distance: {}  Second cost: {}",
+                instance.format_cost(point.solution.total_distance),
+                second.format_cost(point.second_cost)
+            );
+        }
+        if let Some(output) = &args.pareto_output {
+            write_front(output, &archive, &instance, &second)?;
+            println!("Pareto front saved to {}", output);
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Ttt {
+        target,
+        runs,
+        output,
+    }) = &cli.command
+    {
+        let hits = time_to_target(
+            *runs,
+            *target,
+            || args.termination(),
+            output,
+            |termination, trace| args.solve(&args.variant, &instance, termination, trace),
+        )?;
+        if hits == 0 {
+            return Err(Error::new(
+                ErrorKind::TargetNotReached,
+                format!("No run reached the target {}", target),
+            ));
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Island {
+        listen,
+        connect,
+        islands,
+        migration_interval,
+    }) = &cli.command
+    {
+        let termination = args.termination();
+        let interval = Duration::from_secs(*migration_interval);
+        let trace = args.configure_trace(Trace::new());
+        let best_solution = match (listen, connect) {
+            (Some(listen), _) => island::coordinator(
+                listen,
+                *islands,
+                interval,
+                &instance,
+                &termination,
+                &*args.construction(),
+                &*args.local_search(),
+                &args.static_pr_config(),
+                &trace,
+            ),
+            (None, Some(connect)) => island::island(
+                connect,
+                interval,
+                &instance,
+                &termination,
+                &*args.construction(),
+                &*args.local_search(),
+                &args.static_pr_config(),
+                &trace,
+            ),
+            (None, None) => unreachable!("clap requires --listen or --connect"),
+        };
+        return report(args, &instance, &best_solution, &trace);
+    }
+
+    if args.runs > 1 {
+        // The report, including the trace, is that of the best run.
+        let (best_solution, trace) = multi_run(
+            args.runs,
+            || args.termination(),
+            || args.configure_trace(Trace::new()),
+            instance.objective,
+            args.runs_output.as_deref(),
+            |termination, trace| args.solve(&args.variant, &instance, termination, trace),
+        )?;
+        return report(args, &instance, &best_solution, &trace);
+    }
+
+    let termination = args.termination();
+
+    #[cfg(feature = "tui")]
+    if args.tui {
+        let trace = args.configure_trace(Trace::live());
+        let done = AtomicBool::new(false);
+        let best_solution = std::thread::scope(|scope| {
+            scope.spawn(|| crate::tui::dashboard(&instance, &termination, &trace, &done));
+            let solution = args.solve(&args.variant, &instance, &termination, &trace);
+            done.store(true, Ordering::Relaxed);
+            solution
+        })?;
+        report(args, &instance, &best_solution, &trace)?;
+        if termination.interrupted() {
+            return Err(Error::new(
+                ErrorKind::Interrupted,
+                "The run was interrupted",
+            ));
+        }
+        return Ok(());
+    }
+
+    let trace = args.configure_trace(Trace::new());
+    let best_solution = args.solve(&args.variant, &instance, &termination, &trace)?;
+    report(args, &instance, &best_solution, &trace)
+}
+
+/// Loads `instance_file`, looked up in `--instances-dir` when it is not found as given, and
+/// applies the tour options and constraints of `args` to it.
+fn load_instance(args: &SolveArgs, instance_file: &str) -> Result<Instance, Error> {
+    let instance_file = &args.resolve_instance(instance_file);
+    #[cfg(feature = "osrm")]
+    let mut instance = match &args.osrm_url {
+        Some(url) => load_osrm(instance_file, url, args.precision)?,
+        None => read_instance(args, instance_file)?,
+    };
+    #[cfg(not(feature = "osrm"))]
+    let mut instance = read_instance(args, instance_file)?;
+    debug!(
+        "Loaded {} with {} cities",
+        instance_file, instance.num_cities
+    );
+    if let Some(max_cities) = args.max_cities.filter(|&max| max < instance.num_cities) {
+        let mut cities: Vec<usize> = match args.subset {
+            SubsetKind::Prefix => (0..max_cities).collect(),
+            SubsetKind::Random => {
+                (0..instance.num_cities).choose_multiple(&mut crate::rng::thread_rng(), max_cities)
+            }
+        };
+        cities.sort_unstable();
+        info!(
+            "Solving {} of the {} cities ({:?} subset)",
+            max_cities, instance.num_cities, args.subset
+        );
+        instance = instance.subset(&cities)?;
+    }
+    instance.open_tour = args.open_tour;
+    #[cfg(not(feature = "json"))]
+    if args.elite_json.is_some() {
+        return Err(Error::new(
+            ErrorKind::InvalidArguments,
+            "--elite-json requires the json feature",
+        ));
+    }
+    if !args.supports(args.objective) {
+        return Err(Error::new(
+            ErrorKind::InvalidArguments,
+            format!(
+                "The {} objective is not supported by the {} variant",
+                args.objective.to_possible_value().unwrap().get_name(),
+                args.variant
+            ),
+        ));
+    }
+    instance.objective = args.objective;
+    if let Some(file) = &args.variances {
+        let mut variances = Instance::load_with_precision(&args.resolve_instance(file), 0)?;
+        if let Some(cities) = &instance.original_cities {
+            variances = variances.subset(cities)?;
+        }
+        if variances.num_cities != instance.num_cities {
+            return Err(Error::new(
+                ErrorKind::Instance,
+                format!(
+                    "The variance matrix has {} cities but the instance has {}",
+                    variances.num_cities, instance.num_cities
+                ),
+            ));
+        }
+        instance.set_variances(&variances, args.risk_aversion)?;
+    }
+    instance.start_city = args.start_city;
+    instance.end_city = args.end_city;
+    for city in [args.start_city, args.end_city].into_iter().flatten() {
+        if city >= instance.num_cities {
+            return Err(Error::new(
+                ErrorKind::InvalidArguments,
+                format!("City {} does not exist in the instance", city),
+            ));
+        }
+    }
+    if args.start_city.is_some() && args.start_city == args.end_city {
+        return Err(Error::new(
+            ErrorKind::InvalidArguments,
+            "Start and end cities must differ",
+        ));
+    }
+    if let Some(constraints_file) = &args.constraints {
+        EdgeConstraints::load(constraints_file)?.apply(&mut instance)?;
+    }
+    if let Some(tour_file) = &args.freeze_tour {
+        freeze_prefix(&mut instance, args, tour_file)?;
+    }
+    if instance.coords().is_none() {
+        check_without_coordinates(args)?;
+    }
+    Ok(instance)
+}
+
+/// Rejects the options of `args` that need node coordinates, for an instance that has none.
+fn check_without_coordinates(args: &SolveArgs) -> Result<(), Error> {
+    if matches!(args.construction, ConstructionKind::SpaceFillingCurve) {
+        return Err(Error::new(
+            ErrorKind::InvalidArguments,
+            "The space-filling-curve construction requires node coordinates",
+        ));
+    }
+    if args.plot_svg.is_some() {
+        return Err(Error::new(
+            ErrorKind::InvalidArguments,
+            "--plot-svg requires node coordinates",
+        ));
+    }
+    #[cfg(feature = "png")]
+    for (option, set) in [
+        ("--plot-png", args.plot_png.is_some()),
+        ("--png-frames", args.png_frames.is_some()),
+        ("--animate", args.animate.is_some()),
+    ] {
+        if set {
+            return Err(Error::new(
+                ErrorKind::InvalidArguments,
+                format!("{} requires node coordinates", option),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Keeps the first `--freeze-prefix` cities of the tour in `tour_file` (all of them by
+/// default) at the start of every tour, in order.
+fn freeze_prefix(instance: &mut Instance, args: &SolveArgs, tour_file: &str) -> Result<(), Error> {
+    let tour = Solution::load_tour(tour_file)?;
+    let length = args.freeze_prefix.unwrap_or(tour.path.len());
+    if length > tour.path.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidArguments,
+            format!(
+                "Cannot freeze {} cities of a tour of {}",
+                length,
+                tour.path.len()
+            ),
+        ));
+    }
+    let prefix = &tour.path[..length];
+    let mut seen = vec![false; instance.num_cities];
+    for &city in prefix {
+        if city >= instance.num_cities {
+            return Err(Error::new(
+                ErrorKind::Instance,
+                format!("City {} of the frozen tour does not exist", city),
+            ));
+        }
+        if std::mem::replace(&mut seen[city], true) {
+            return Err(Error::new(
+                ErrorKind::Instance,
+                format!("The frozen tour visits city {} twice", city),
+            ));
+        }
+    }
+    let Some(&first) = prefix.first() else {
+        return Ok(());
+    };
+    if args.start_city.is_some_and(|start| start != first) {
+        return Err(Error::new(
+            ErrorKind::InvalidArguments,
+            "The frozen prefix must begin at --start-city",
+        ));
+    }
+    instance.start_city = Some(first);
+    // The end city can only be frozen as the last city of a tour frozen whole.
+    let frozen_end = length == instance.num_cities && args.end_city == prefix.last().copied();
+    if !frozen_end && args.end_city.is_some_and(|end| prefix[1..].contains(&end)) {
+        return Err(Error::new(
+            ErrorKind::Infeasible,
+            "The end city is part of the frozen prefix",
+        ));
+    }
+    if length > 1 {
+        EdgeConstraints::freezing(prefix).apply(instance)?;
+    }
+    info!("Freezing the first {} cities of {}", length, tour_file);
+    Ok(())
+}
+
+/// Loads the `lat` and `lon` points of the CSV file `instance_file` with the road distances
+/// of the OSRM table service at `url`.
+#[cfg(feature = "osrm")]
+fn load_osrm(instance_file: &str, url: &str, precision: u32) -> Result<Instance, Error> {
+    let content = crate::tsplib::read_file(instance_file)?;
+    let points = crate::geo::read_lat_lon(&content)?.ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidArguments,
+            "--osrm-url requires a CSV instance with lat and lon columns",
+        )
+    })?;
+    let mut instance = crate::geo::osrm_instance(&points, url, precision)?;
+    instance.name = Some(crate::instance::name_from_path(instance_file).to_string());
+    Ok(instance)
+}
+
+#[cfg(feature = "cache")]
+fn read_instance(args: &SolveArgs, instance_file: &str) -> Result<Instance, Error> {
+    if args.cache {
+        crate::cache::load_cached(instance_file, args.precision)
+    } else {
+        Instance::load_with_precision(instance_file, args.precision)
+    }
+}
+
+#[cfg(not(feature = "cache"))]
+fn read_instance(args: &SolveArgs, instance_file: &str) -> Result<Instance, Error> {
+    Instance::load_with_precision(instance_file, args.precision)
+}
+
+/// Prints the tour of a subset instance (see `--max-cities`) in the original city indices.
+fn print_original_cities(instance: &Instance, path: &[usize]) {
+    if let Some(original_cities) = &instance.original_cities {
+        let original: Vec<usize> = path.iter().map(|&city| original_cities[city]).collect();
+        println!("In original city indices: {:?}", original);
+    }
+}
+
+fn report(
+    args: &SolveArgs,
+    instance: &Instance,
+    best_solution: &Solution,
+    trace: &Trace,
+) -> Result<(), Error> {
+    if let Some(name) = &instance.name {
+        println!("\nInstance: {}", name);
+    }
+    println!("\nBest solution found: {:?}", best_solution.path);
+    print_original_cities(instance, &best_solution.path);
+    println!(
+        "Total distance: {}",
+        instance.format_cost(best_solution.total_distance)
+    );
+    if instance.objective == Objective::Bottleneck {
+        println!(
+            "Longest edge: {}",
+            instance.format_cost(best_solution.longest_edge)
+        );
+    }
+    if instance.objective == Objective::Robust {
+        let variance = instance.tour_variance(&best_solution.path);
+        println!("Standard deviation: {:.2}", (variance as f64).sqrt());
+        println!(
+            "Robust cost: {}",
+            instance.format_cost(instance.objective.value(best_solution))
+        );
+    }
+    // Logged rather than printed, since it varies between otherwise identical runs.
+    if let Some(peak) = crate::memory::peak_rss() {
+        info!("Peak memory: {}", crate::memory::format_bytes(peak));
+    }
+
+    let violations = instance.constraints.as_ref().map_or(0, |constraints| {
+        constraints.violations(best_solution, instance)
+    });
+    if instance.constraints.is_some() {
+        println!("Constraint violations: {}", violations);
+    }
+
+    let optimum = args
+        .optimum
+        .or_else(|| {
+            // The best known distance is that of the whole instance, not of a subset.
+            let name = instance
+                .name
+                .as_deref()
+                .filter(|_| instance.original_cities.is_none());
+            name.and_then(known_optimum)
+        })
+        .filter(|_| !instance.open_tour);
+    if let Some(optimum) = optimum {
+        println!(
+            "Best known: {} (gap = {:.2}%)",
+            optimum,
+            gap(best_solution.total_distance, optimum * instance.scale())
+        );
+    }
+
+    if let Some(svg_file) = &args.plot_svg {
+        let reference = args
+            .plot_reference
+            .as_deref()
+            .map(|tour_file| Solution::load_valid_tour(tour_file, instance))
+            .transpose()?;
+        svg::write_svg(svg_file, instance, best_solution, reference.as_ref())?;
+        info!("Tour plot saved to {}", svg_file);
+    }
+
+    if args.plot_terminal {
+        if instance.coords().is_some() {
+            let (columns, rows) = crate::ascii::terminal_size();
+            // Leave room for the prompt and the summary printed above.
+            let tour =
+                crate::ascii::render_tour(instance, best_solution, columns, rows.saturating_sub(2));
+            println!("\n{}", tour);
+        } else {
+            warn!("--plot-terminal requires node coordinates");
+        }
+    }
+
+    #[cfg(feature = "png")]
+    {
+        let options = crate::png::PngOptions {
+            width: args.png_width,
+            height: args.png_height,
+            labels: args.png_labels,
+        };
+        if let Some(png_file) = &args.plot_png {
+            crate::png::write_png(png_file, instance, best_solution, &options)?;
+            info!("Tour plot saved to {}", png_file);
+        }
+        if let Some(frames_dir) = &args.png_frames {
+            let tours = trace.tours();
+            crate::png::write_frames(frames_dir, instance, &tours, &options)?;
+            info!("{} improvement frames saved to {}", tours.len(), frames_dir);
+        }
+        if let Some(animation) = &args.animate {
+            let tours = trace.tours();
+            if animation.ends_with(".gif") {
+                crate::png::write_gif(animation, instance, &tours, &options, args.animate_delay)?;
+            } else {
+                crate::png::write_frames(animation, instance, &tours, &options)?;
+            }
+            info!(
+                "Animation of {} improvements saved to {}",
+                tours.len(),
+                animation
+            );
+        }
+    }
+
+    if let Some(dir) = &args.export_plot_data {
+        crate::export::export_plot_data(dir, instance, best_solution, trace)?;
+        info!("Plot data saved to {}", dir);
+    }
+
+    let truncated = trace.truncated_searches();
+    if truncated > 0 {
+        info!(
+            "{} local searches were cut short by the time limit",
+            truncated
+        );
+    }
+
+    if let Some(trace_file) = &args.trace {
+        trace.write_csv(trace_file)?;
+        info!("Improvement trace saved to {}", trace_file);
+
+        if args.trace_iterations.is_some() {
+            let iterations_file = format!(
+                "{}_iterations.csv",
+                trace_file.strip_suffix(".csv").unwrap_or(trace_file)
+            );
+            trace.write_iterations_csv(&iterations_file)?;
+            info!("Iteration records saved to {}", iterations_file);
+        }
+    }
+
+    if violations > 0 {
+        return Err(Error::new(
+            ErrorKind::Infeasible,
+            format!("The best tour violates {} constraints", violations),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use crate::instance::Instance;
+use crate::solution::Solution;
+
+/// Terminal size used when it cannot be determined.
+const DEFAULT_SIZE: (usize, usize) = (80, 24);
+
+/// Bit of each dot of a braille character, indexed by row (0 to 3) and column (0 or 1).
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Columns and rows of the terminal (with the `tui` feature), or else from the `COLUMNS` and
+/// `LINES` environment variables, falling back to 80x24.
+pub fn terminal_size() -> (usize, usize) {
+    #[cfg(feature = "tui")]
+    if let Ok((columns, rows)) = ratatui::crossterm::terminal::size() {
+        return (columns as usize, rows as usize);
+    }
+    let size = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .and_then(|value| value.parse().ok())
+    };
+    (
+        size("COLUMNS").unwrap_or(DEFAULT_SIZE.0),
+        size("LINES").unwrap_or(DEFAULT_SIZE.1),
+    )
+}
+
+/// Renders the tour of `solution` with braille characters in at most `columns` x `rows`
+/// characters, each of them a 2x4 grid of dots. Terminal cells are about twice as tall as
+/// wide, so the dots are close to square and the coordinates keep their aspect ratio. Requires
+/// node coordinates.
+pub fn render_tour(
+    instance: &Instance,
+    solution: &Solution,
+    columns: usize,
+    rows: usize,
+) -> String {
+    let coords = instance
+        .coords()
+        .expect("Terminal plotting requires node coordinates");
+
+    let (min_x, max_x) = coords
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &(x, _)| {
+            (lo.min(x), hi.max(x))
+        });
+    let (min_y, max_y) = coords
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &(_, y)| {
+            (lo.min(y), hi.max(y))
+        });
+    let (dots_x, dots_y) = (columns.max(1) * 2, rows.max(1) * 4);
+    let scale = ((dots_x - 1) as f64 / (max_x - min_x).max(f64::EPSILON))
+        .min((dots_y - 1) as f64 / (max_y - min_y).max(f64::EPSILON));
+    let width = ((max_x - min_x) * scale) as usize + 1;
+    let height = ((max_y - min_y) * scale) as usize + 1;
+
+    let project = |city: usize| {
+        let (x, y) = coords[city];
+        (
+            ((x - min_x) * scale).round() as i64,
+            (height as f64 - 1.0 - (y - min_y) * scale).round() as i64,
+        )
+    };
+
+    let cell_columns = width.div_ceil(2);
+    let cell_rows = height.div_ceil(4);
+    let mut cells = vec![0u8; cell_columns * cell_rows];
+    let mut set = |x: i64, y: i64| {
+        let (x, y) = (x.clamp(0, width as i64 - 1), y.clamp(0, height as i64 - 1));
+        let (x, y) = (x as usize, y as usize);
+        cells[(y / 4) * cell_columns + x / 2] |= BRAILLE_DOTS[y % 4][x % 2];
+    };
+
+    let path = &solution.path;
+    let edges = if instance.open_tour {
+        path.len().saturating_sub(1)
+    } else {
+        path.len()
+    };
+    for i in 0..edges {
+        let (x1, y1) = project(path[i]);
+        let (x2, y2) = project(path[(i + 1) % path.len()]);
+        // Bresenham's line between the two cities.
+        let (dx, dy) = ((x2 - x1).abs(), -(y2 - y1).abs());
+        let (sx, sy) = ((x2 - x1).signum(), (y2 - y1).signum());
+        let (mut x, mut y, mut error) = (x1, y1, dx + dy);
+        loop {
+            set(x, y);
+            if x == x2 && y == y2 {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+
+    let mut output = String::with_capacity(cells.len() * 3 + cell_rows);
+    for row in cells.chunks(cell_columns) {
+        output.extend(
+            row.iter()
+                .map(|&dots| char::from_u32(0x2800 + dots as u32).unwrap()),
+        );
+        output.push('\n');
+    }
+    output
+}
@@ -0,0 +1,224 @@
+use crate::error::Error;
+use crate::instance::Instance;
+use crate::optimum::{gap, known_optimum};
+use crate::report::{InstanceReport, MethodReport};
+use crate::solution::Solution;
+use crate::stats::wilcoxon_signed_rank;
+use crate::svg::render_svg;
+use crate::time::Instant;
+use crate::trace::Trace;
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+
+/// Runs a method once on an instance under a time limit, recording its improvements in the
+/// trace.
+pub type MethodRun<'a> = Box<dyn Fn(&Instance, Duration, &Trace) -> Result<Solution, Error> + 'a>;
+
+/// A named method compared by the benchmark: a local search applied to a constructed solution
+/// or a full solver run under the per-run time limit.
+pub struct Method<'a> {
+    pub name: String,
+    pub run: MethodRun<'a>,
+}
+
+/// Name endings of the files that discovery treats as instances.
+const INSTANCE_EXTENSIONS: [&str; 2] = [".tsp", ".tsp.gz"];
+
+/// Instance files (`.tsp` or `.tsp.gz`) under `dir` and its subdirectories, in path order.
+pub fn find_instance_files(dir: &str) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::from(dir)];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let path = path.to_string_lossy().into_owned();
+            if INSTANCE_EXTENSIONS.iter().any(|ext| path.ends_with(ext)) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Expands directories into the instance files they contain, recursively and in path order.
+pub fn expand_instance_paths(paths: &[String]) -> Vec<String> {
+    let mut files = Vec::new();
+    for path in paths {
+        if Path::new(path).is_dir() {
+            files.extend(find_instance_files(path));
+        } else {
+            files.push(path.clone());
+        }
+    }
+    files
+}
+
+/// Runs every method `num_runs` times on every instance, loaded with `precision` decimal digits,
+/// and writes one row per run to a single CSV file. Returns the results of each instance for
+/// the HTML report.
+pub fn benchmark(
+    instance_files: &[String],
+    methods: &[Method],
+    num_runs: usize,
+    time_limit: Duration,
+    precision: u32,
+    output: &str,
+) -> Result<Vec<InstanceReport>, Error> {
+    let output_error = |error: io::Error| Error::output(output, error);
+    let file = File::create(output).map_err(output_error)?;
+    let mut writer = BufWriter::new(file);
+    writeln!(writer, "Instance,Method,Run,Distance,Gap (%),Time (µs)").map_err(output_error)?;
+    let comparisons_output = comparisons_path(output);
+    let comparisons_error = |error: io::Error| Error::output(comparisons_output.display(), error);
+    let mut comparisons = comparisons_writer(&comparisons_output).map_err(comparisons_error)?;
+    let mut reports = Vec::with_capacity(instance_files.len());
+
+    for instance_file in instance_files {
+        info!("Running benchmark for instance {}", instance_file);
+        let instance = Instance::load_with_precision(instance_file, precision)?;
+        let instance_name = instance.name.as_deref().unwrap_or_default();
+        // Distances are in the units of the instance, scaled by its precision.
+        let optimum = known_optimum(instance_name).map(|optimum| optimum * instance.scale());
+        let mut method_reports = Vec::with_capacity(methods.len());
+
+        for method in methods {
+            let mut method_distances = Vec::with_capacity(num_runs);
+            let mut times = Vec::with_capacity(num_runs);
+            let mut best: Option<(Solution, Trace)> = None;
+            for run in 1..=num_runs {
+                let trace = Trace::new();
+                let start = Instant::now();
+                let solution = (method.run)(&instance, time_limit, &trace)?;
+                let duration = start.elapsed();
+                let gap = optimum
+                    .map(|optimum| format!("{:.2}", gap(solution.total_distance, optimum)))
+                    .unwrap_or_default();
+
+                info!(
+                    "{} run {}: Distance = {}, Time = {:.2?}",
+                    method.name, run, solution.total_distance, duration
+                );
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{}",
+                    instance_name,
+                    method.name,
+                    run,
+                    solution.total_distance,
+                    gap,
+                    duration.as_micros()
+                )
+                .map_err(output_error)?;
+                method_distances.push(solution.total_distance as f64);
+                times.push(duration.as_secs_f64());
+                if best
+                    .as_ref()
+                    .is_none_or(|(best, _)| instance.objective.better(&solution, best))
+                {
+                    best = Some((solution, trace));
+                }
+            }
+
+            let (best, trace) = best.expect("There should be at least one run");
+            method_reports.push(MethodReport {
+                name: method.name.clone(),
+                distances: method_distances,
+                times,
+                convergence: trace
+                    .events()
+                    .iter()
+                    .map(|event| (event.elapsed.as_secs_f64(), event.distance))
+                    .collect(),
+                best,
+            });
+        }
+
+        println!("\n=== {} ===", instance_name);
+        let distances: Vec<(&str, Vec<f64>)> = method_reports
+            .iter()
+            .map(|method| (method.name.as_str(), method.distances.clone()))
+            .collect();
+        write_comparisons(&mut comparisons, instance_name, &distances)
+            .map_err(comparisons_error)?;
+
+        let tour_svg = method_reports
+            .iter()
+            .map(|method| &method.best)
+            .min_by_key(|best| instance.objective.key(best))
+            .and_then(|best| render_svg(&instance, best, None));
+        reports.push(InstanceReport {
+            name: instance_name.to_string(),
+            num_cities: instance.num_cities,
+            optimum,
+            methods: method_reports,
+            tour_svg,
+        });
+    }
+
+    writer.flush().map_err(output_error)?;
+    comparisons.flush().map_err(comparisons_error)?;
+    info!("Benchmark results saved to {}", output);
+    info!(
+        "Pairwise comparisons saved to {}",
+        comparisons_output.display()
+    );
+    Ok(reports)
+}
+
+/// `<stem>_wilcoxon.csv` next to `output`.
+fn comparisons_path(output: &str) -> PathBuf {
+    let path = Path::new(output);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}_wilcoxon.csv", stem))
+}
+
+/// Creates the CSV file of `write_comparisons` and writes its header.
+pub fn comparisons_writer(path: &Path) -> io::Result<BufWriter<File>> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(
+        writer,
+        "Instance,Method A,Method B,Pairs,W+,Mean Difference,p-value"
+    )?;
+    Ok(writer)
+}
+
+/// Compares the distances of every pair of methods, paired by run, with the Wilcoxon
+/// signed-rank test. Prints each p-value and writes one row per pair to `writer`.
+pub fn write_comparisons(
+    writer: &mut impl Write,
+    instance_name: &str,
+    distances: &[(&str, Vec<f64>)],
+) -> io::Result<()> {
+    for (i, (a, a_distances)) in distances.iter().enumerate() {
+        for (b, b_distances) in &distances[i + 1..] {
+            let test = wilcoxon_signed_rank(a_distances, b_distances);
+            let mean_difference = a_distances
+                .iter()
+                .zip(b_distances)
+                .map(|(x, y)| x - y)
+                .sum::<f64>()
+                / a_distances.len().max(1) as f64;
+            println!(
+                "{} vs {}: mean difference = {:.2}, Wilcoxon p-value = {:.4}",
+                a, b, mean_difference, test.p_value
+            );
+            writeln!(
+                writer,
+                "{},{},{},{},{},{:.2},{:.6}",
+                instance_name, a, b, test.pairs, test.w_plus, mean_difference, test.p_value
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use crate::instance::Cost;
+use crate::objective::Objective;
+use crate::solution::Solution;
+
+use std::sync::atomic::{AtomicI64, Ordering};
+
+#[cfg(not(target_family = "wasm"))]
+use std::sync::mpsc::{self, Sender};
+#[cfg(target_family = "wasm")]
+use std::sync::Mutex;
+#[cfg(not(target_family = "wasm"))]
+use std::thread::{self, JoinHandle};
+
+/// Keeps the best under an objective of the solutions sent by the worker threads. Workers send
+/// their improvements over a channel to a collector thread instead of storing them behind a
+/// lock, and since the collector compares every solution it receives, a worse solution sent
+/// late never replaces a better one. WebAssembly cannot spawn the collector thread, so there the
+/// best solution is kept behind a lock instead.
+#[cfg(not(target_family = "wasm"))]
+pub struct BestCollector {
+    objective: Objective,
+    score: AtomicI64,
+    sender: Sender<Solution>,
+    collector: JoinHandle<Option<Solution>>,
+}
+
+#[cfg(target_family = "wasm")]
+pub struct BestCollector {
+    objective: Objective,
+    score: AtomicI64,
+    best: Mutex<Option<Solution>>,
+}
+
+impl BestCollector {
+    /// Sends `solution` if its value under the objective is lower than that of every solution
+    /// sent through `improve` so far, and returns whether it was. The comparison and the update
+    /// of the best score are a single atomic step, so a concurrent worker can never overwrite a
+    /// better score and only genuine improvements are reported.
+    pub fn improve(&self, solution: &Solution) -> bool {
+        let value = self.objective.value(solution);
+        if value >= self.score() || self.score.fetch_min(value, Ordering::Relaxed) <= value {
+            return false;
+        }
+        self.send(solution.copy());
+        true
+    }
+
+    /// Best value under the objective sent through `improve` so far.
+    pub fn score(&self) -> Cost {
+        self.score.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl BestCollector {
+    pub fn new(objective: Objective) -> Self {
+        let (sender, receiver) = mpsc::channel::<Solution>();
+        let collector = thread::spawn(move || {
+            receiver
+                .into_iter()
+                .fold(None, |best: Option<Solution>, solution| match best {
+                    Some(best) if !objective.better(&solution, &best) => Some(best),
+                    _ => Some(solution),
+                })
+        });
+
+        BestCollector {
+            objective,
+            score: AtomicI64::new(Cost::MAX),
+            sender,
+            collector,
+        }
+    }
+
+    pub fn send(&self, solution: Solution) {
+        self.sender
+            .send(solution)
+            .expect("The best-solution collector should be running");
+    }
+
+    /// Closes the channel and returns the best solution received, if any.
+    pub fn finish(self) -> Option<Solution> {
+        drop(self.sender);
+        self.collector
+            .join()
+            .expect("The best-solution collector panicked")
+    }
+}
+
+#[cfg(target_family = "wasm")]
+impl BestCollector {
+    pub fn new(objective: Objective) -> Self {
+        BestCollector {
+            objective,
+            score: AtomicI64::new(Cost::MAX),
+            best: Mutex::new(None),
+        }
+    }
+
+    pub fn send(&self, solution: Solution) {
+        let mut best = self.best.lock().unwrap();
+        if best
+            .as_ref()
+            .is_none_or(|best| self.objective.better(&solution, best))
+        {
+            *best = Some(solution);
+        }
+    }
+
+    /// Returns the best solution received, if any.
+    pub fn finish(self) -> Option<Solution> {
+        self.best.into_inner().unwrap()
+    }
+}
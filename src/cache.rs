@@ -0,0 +1,130 @@
+use crate::constraints::EdgeConstraints;
+use crate::distance::{Distances, MatrixDistances};
+use crate::error::{Error, ErrorKind};
+use crate::instance::{Cost, Instance};
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{BufReader, BufWriter};
+use tracing::warn;
+
+/// Bumped whenever the layout of `CachedInstance` changes.
+const CACHE_VERSION: u32 = 4;
+
+/// Parsed instance data stored in the sidecar cache file.
+#[derive(Serialize, Deserialize)]
+struct CachedInstance {
+    version: u32,
+    key: u64,
+    name: Option<String>,
+    problem_type: Option<String>,
+    comment: Option<String>,
+    num_cities: usize,
+    distances: Vec<Cost>,
+    coords: Option<Vec<(f64, f64)>>,
+    geometric: bool,
+    precision: u32,
+    /// Edges of the FIXED_EDGES_SECTION; `distances` are stored without their penalties.
+    fixed_edges: Vec<(usize, usize)>,
+}
+
+/// Hash of the instance file contents and the precision it is loaded with.
+fn cache_key(content: &[u8], precision: u32) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    precision.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Loads an instance through a binary sidecar cache (`<filename>.cache`). The cache is used
+/// when it was built from the same file contents and precision; otherwise the instance is
+/// parsed and the cache is rewritten.
+pub fn load_cached(filename: &str, precision: u32) -> Result<Instance, Error> {
+    let content = std::fs::read(filename).map_err(|error| {
+        Error::new(
+            ErrorKind::Instance,
+            format!("Failed to read {}: {}", filename, error),
+        )
+    })?;
+    let key = cache_key(&content, precision);
+    let cache_file = format!("{}.cache", filename);
+
+    let cached = File::open(&cache_file)
+        .ok()
+        .and_then(|file| bincode::deserialize_from::<_, CachedInstance>(BufReader::new(file)).ok())
+        .filter(|cached| cached.version == CACHE_VERSION && cached.key == key);
+    if let Some(cached) = cached {
+        let mut instance = Instance::with_distances(
+            cached.num_cities,
+            Distances::Matrix(MatrixDistances::new(cached.num_cities, cached.distances)),
+            cached.coords,
+            cached.precision,
+        );
+        if cached.geometric {
+            instance.build_kd_tree();
+        }
+        instance.name = cached.name;
+        instance.problem_type = cached.problem_type;
+        instance.comment = cached.comment;
+        if !cached.fixed_edges.is_empty() {
+            EdgeConstraints::forcing(cached.fixed_edges).apply(&mut instance)?;
+        }
+        return Ok(instance);
+    }
+
+    let mut instance = Instance::load_with_precision(filename, precision)?;
+    let constraints = EdgeConstraints::lift(&mut instance);
+    if let Some(matrix) = instance.matrix() {
+        let fixed_edges = constraints
+            .iter()
+            .flat_map(|constraints| constraints.forced.iter().copied())
+            .collect();
+        write_cache(
+            &cache_file,
+            key,
+            precision,
+            &instance,
+            matrix.as_slice(),
+            fixed_edges,
+        );
+    }
+    if let Some(constraints) = constraints {
+        constraints.apply(&mut instance)?;
+    }
+    Ok(instance)
+}
+
+/// Writes the cache file of `instance` loaded with `precision`, whose distances without the
+/// fixed-edge penalties are `distances`.
+fn write_cache(
+    cache_file: &str,
+    key: u64,
+    precision: u32,
+    instance: &Instance,
+    distances: &[Cost],
+    fixed_edges: Vec<(usize, usize)>,
+) {
+    let cached = CachedInstance {
+        version: CACHE_VERSION,
+        key,
+        name: instance.name.clone(),
+        problem_type: instance.problem_type.clone(),
+        comment: instance.comment.clone(),
+        num_cities: instance.num_cities,
+        distances: distances.to_vec(),
+        coords: instance.coords().map(|coords| coords.to_vec()),
+        geometric: instance.kd_tree().is_some(),
+        precision,
+        fixed_edges,
+    };
+    match File::create(cache_file) {
+        Ok(file) => {
+            if let Err(error) = bincode::serialize_into(BufWriter::new(file), &cached) {
+                warn!("Failed to write instance cache {}: {}", cache_file, error);
+            }
+        }
+        Err(error) => warn!("Failed to create instance cache {}: {}", cache_file, error),
+    }
+}
@@ -0,0 +1,304 @@
+use crate::instance::Instance;
+
+use rayon::prelude::*;
+
+/// Nearest neighbours kept as candidates of each city, on top of its Delaunay neighbours.
+const NEAREST_CANDIDATES: usize = 5;
+
+/// Candidate neighbours of each city, sorted by distance. Local searches only consider moves
+/// that create an edge between a city and one of its candidates.
+#[derive(Debug, Clone)]
+pub struct CandidateLists {
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl CandidateLists {
+    /// The `NEAREST_CANDIDATES` nearest neighbours of every city, plus its neighbours in the
+    /// Delaunay triangulation when the distances are Euclidean over the node coordinates.
+    pub fn new(instance: &Instance) -> Self {
+        let mut neighbors = nearest_neighbors(instance, NEAREST_CANDIDATES);
+        if let (Some(coords), Some(_)) = (instance.coords(), instance.kd_tree()) {
+            for (a, b) in delaunay_edges(coords) {
+                neighbors[a].push(b);
+                neighbors[b].push(a);
+            }
+        }
+
+        neighbors
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(city, candidates)| {
+                candidates.sort_unstable();
+                candidates.dedup();
+                candidates.sort_by_key(|&other| instance.dist(city, other));
+            });
+        CandidateLists { neighbors }
+    }
+
+    pub fn neighbors(&self, city: usize) -> &[usize] {
+        &self.neighbors[city]
+    }
+}
+
+/// The `k` cities closest to each city, using the k-d tree when there is one.
+fn nearest_neighbors(instance: &Instance, k: usize) -> Vec<Vec<usize>> {
+    let n = instance.num_cities;
+    if let Some(tree) = instance.kd_tree() {
+        let state = tree.full_state();
+        return (0..n)
+            .into_par_iter()
+            .map(|city| tree.k_nearest(city, k, &state))
+            .collect();
+    }
+
+    (0..n)
+        .into_par_iter()
+        .map(|city| {
+            let mut others: Vec<usize> = (0..n).filter(|&other| other != city).collect();
+            if k < others.len() {
+                others.select_nth_unstable_by_key(k, |&other| instance.dist(city, other));
+                others.truncate(k);
+            }
+            others
+        })
+        .collect()
+}
+
+/// Triangle of the triangulation with its circumcircle.
+struct Triangle {
+    vertices: [usize; 3],
+    center: (f64, f64),
+    squared_radius: f64,
+}
+
+impl Triangle {
+    fn new(vertices: [usize; 3], points: &[(f64, f64)]) -> Self {
+        let [(ax, ay), (bx, by), (cx, cy)] = vertices.map(|v| points[v]);
+        let d = 2.0 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
+        if d.abs() < f64::EPSILON {
+            // Degenerate triangles are replaced by the next point inserted.
+            return Triangle {
+                vertices,
+                center: (0.0, 0.0),
+                squared_radius: f64::INFINITY,
+            };
+        }
+        let (a2, b2, c2) = (ax * ax + ay * ay, bx * bx + by * by, cx * cx + cy * cy);
+        let ux = (a2 * (by - cy) + b2 * (cy - ay) + c2 * (ay - by)) / d;
+        let uy = (a2 * (cx - bx) + b2 * (ax - cx) + c2 * (bx - ax)) / d;
+        Triangle {
+            vertices,
+            center: (ux, uy),
+            squared_radius: (ax - ux).powi(2) + (ay - uy).powi(2),
+        }
+    }
+
+    fn edges(&self) -> [(usize, usize); 3] {
+        let [a, b, c] = self.vertices;
+        [
+            (a.min(b), a.max(b)),
+            (b.min(c), b.max(c)),
+            (a.min(c), a.max(c)),
+        ]
+    }
+}
+
+/// Edges of the Delaunay triangulation of `coords`, computed with the Bowyer-Watson algorithm
+/// sweeping the points by increasing x: triangles whose circumcircle lies entirely to the left
+/// of the sweep can no longer change and are set aside.
+fn delaunay_edges(coords: &[(f64, f64)]) -> Vec<(usize, usize)> {
+    let n = coords.len();
+    if n < 3 {
+        return Vec::new();
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| coords[a].partial_cmp(&coords[b]).unwrap());
+    order.dedup_by(|a, b| coords[*a] == coords[*b]);
+
+    // Super triangle enclosing every point, with vertices n, n + 1 and n + 2.
+    let (min_x, min_y, max_x, max_y) = coords.iter().fold(
+        (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+        |(x0, y0, x1, y1), &(x, y)| (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+    );
+    let size = (max_x - min_x).max(max_y - min_y).max(1.0) * 20.0;
+    let (mid_x, mid_y) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+    let mut points = coords.to_vec();
+    points.extend([
+        (mid_x - size, mid_y - size),
+        (mid_x + size, mid_y - size),
+        (mid_x, mid_y + size),
+    ]);
+
+    let mut open = vec![Triangle::new([n, n + 1, n + 2], &points)];
+    let mut done = Vec::new();
+    let mut boundary = Vec::new();
+
+    for &point in &order {
+        let (x, y) = points[point];
+        boundary.clear();
+        open.retain(|triangle| {
+            let (cx, cy) = triangle.center;
+            let dx = x - cx;
+            if dx > 0.0 && dx * dx > triangle.squared_radius {
+                done.push(triangle.edges());
+                return false;
+            }
+            if dx * dx + (y - cy).powi(2) <= triangle.squared_radius {
+                boundary.extend(triangle.edges());
+                return false;
+            }
+            true
+        });
+
+        // Edges shared by two removed triangles are inside the cavity.
+        boundary.sort_unstable();
+        let mut i = 0;
+        while i < boundary.len() {
+            if i + 1 < boundary.len() && boundary[i] == boundary[i + 1] {
+                i += 2;
+                continue;
+            }
+            let (a, b) = boundary[i];
+            open.push(Triangle::new([a, b, point], &points));
+            i += 1;
+        }
+    }
+    done.extend(open.iter().map(Triangle::edges));
+
+    let mut edges: Vec<(usize, usize)> = done
+        .into_iter()
+        .flatten()
+        .filter(|&(a, b)| a < n && b < n)
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `n` points spread over a 1000 by 1000 square by a linear congruential generator.
+    fn points(n: usize) -> Vec<(f64, f64)> {
+        let mut state: u64 = 12345;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 11) as f64 / (1u64 << 53) as f64 * 1000.0
+        };
+        (0..n).map(|_| (next(), next())).collect()
+    }
+
+    /// Delaunay edges by definition: the edges of every triangle whose circumcircle has no
+    /// other point inside, assuming no four points are cocircular.
+    fn brute_force_delaunay(coords: &[(f64, f64)]) -> Vec<(usize, usize)> {
+        let n = coords.len();
+        let mut edges = Vec::new();
+        for a in 0..n {
+            for b in a + 1..n {
+                for c in b + 1..n {
+                    let triangle = Triangle::new([a, b, c], coords);
+                    let (cx, cy) = triangle.center;
+                    let empty = (0..n).filter(|&p| p != a && p != b && p != c).all(|p| {
+                        let (x, y) = coords[p];
+                        (x - cx).powi(2) + (y - cy).powi(2) > triangle.squared_radius
+                    });
+                    if triangle.squared_radius.is_finite() && empty {
+                        edges.extend(triangle.edges());
+                    }
+                }
+            }
+        }
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    #[test]
+    fn square_with_center() {
+        let coords = [(0.0, 0.0), (2.0, 0.0), (0.0, 2.0), (2.0, 2.0), (1.0, 1.0)];
+        let expected = vec![
+            (0, 1),
+            (0, 2),
+            (0, 4),
+            (1, 3),
+            (1, 4),
+            (2, 3),
+            (2, 4),
+            (3, 4),
+        ];
+        assert_eq!(delaunay_edges(&coords), expected);
+    }
+
+    #[test]
+    fn too_few_points() {
+        assert!(delaunay_edges(&[]).is_empty());
+        assert!(delaunay_edges(&[(0.0, 0.0), (1.0, 1.0)]).is_empty());
+        assert_eq!(
+            delaunay_edges(&[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]),
+            vec![(0, 1), (0, 2), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn matches_the_empty_circle_definition() {
+        for n in [10, 40, 100] {
+            let coords = points(n);
+            assert_eq!(
+                delaunay_edges(&coords),
+                brute_force_delaunay(&coords),
+                "{n} points"
+            );
+        }
+    }
+
+    #[test]
+    fn duplicate_points_are_triangulated_once() {
+        let mut coords = points(20);
+        coords.push(coords[3]);
+        let edges = delaunay_edges(&coords);
+        assert!(edges.iter().all(|&(a, b)| a < b && b < 20));
+        assert_eq!(edges, brute_force_delaunay(&coords[..20]));
+    }
+
+    #[test]
+    fn candidates_include_nearest_and_delaunay_neighbours() {
+        let coords = points(60);
+        let instance = Instance::from_coords(coords.clone(), 0);
+        let candidates = CandidateLists::new(&instance);
+        let edges = delaunay_edges(&coords);
+        for city in 0..instance.num_cities {
+            let neighbors = candidates.neighbors(city);
+            assert!(!neighbors.contains(&city));
+            assert!(neighbors.is_sorted_by_key(|&other| instance.dist(city, other)));
+
+            // Ties at the distance of the last nearest neighbour may be broken either way.
+            let mut distances: Vec<_> = (0..instance.num_cities)
+                .filter(|&other| other != city)
+                .map(|other| instance.dist(city, other))
+                .collect();
+            distances.sort_unstable();
+            let radius = distances[NEAREST_CANDIDATES - 1];
+            for other in (0..instance.num_cities).filter(|&other| other != city) {
+                if instance.dist(city, other) < radius {
+                    assert!(neighbors.contains(&other), "{city} lacks {other}");
+                }
+            }
+            let within = neighbors
+                .iter()
+                .filter(|&&other| instance.dist(city, other) <= radius)
+                .count();
+            assert!(within >= NEAREST_CANDIDATES);
+            for &(a, b) in &edges {
+                if a == city {
+                    assert!(neighbors.contains(&b));
+                } else if b == city {
+                    assert!(neighbors.contains(&a));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::construction::NearestNeighbor;
+use crate::distance::{Distances, MatrixDistances};
+use crate::grasp::grasp;
+use crate::instance::Instance;
+use crate::local_search::TwoOpt;
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+use std::time::Duration;
+
+/// Runs the basic GRASP on the instance built by `build` for `time_ms` milliseconds, writes the
+/// tour to `out_tour` and returns its length, or -1 if building the instance or the solver
+/// panicked.
+///
+/// # Safety
+///
+/// `out_tour` must be valid for writes of as many elements as the instance has cities.
+unsafe fn solve_into(build: impl FnOnce() -> Instance, time_ms: u64, out_tour: *mut usize) -> i64 {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let instance = build();
+        let termination = Termination::new(Duration::from_millis(time_ms));
+        grasp(
+            &instance,
+            &termination,
+            &NearestNeighbor::default(),
+            &TwoOpt,
+            &Trace::new(),
+        )
+    }));
+    match result {
+        Ok(solution) => {
+            slice::from_raw_parts_mut(out_tour, solution.path.len())
+                .copy_from_slice(&solution.path);
+            solution.total_distance
+        }
+        Err(_) => -1,
+    }
+}
+
+/// Solves the TSP over the row-major `n * n` distance matrix `dist_matrix` for `time_ms`
+/// milliseconds with the basic GRASP. Writes the tour as 0-based city indices to `out_tour`
+/// and returns its length, or -1 if the arguments are invalid or the solver failed.
+///
+/// # Safety
+///
+/// `dist_matrix` must be valid for reads of `n * n` elements and `out_tour` for writes of `n`
+/// elements.
+#[no_mangle]
+pub unsafe extern "C" fn grasp_solve(
+    dist_matrix: *const i64,
+    n: usize,
+    time_ms: u64,
+    out_tour: *mut usize,
+) -> i64 {
+    if dist_matrix.is_null() || out_tour.is_null() || n < 3 {
+        return -1;
+    }
+    // A slice may span at most `isize::MAX` bytes.
+    let Some(len) = n
+        .checked_mul(n)
+        .filter(|&len| len <= isize::MAX as usize / std::mem::size_of::<i64>())
+    else {
+        return -1;
+    };
+    let distances = slice::from_raw_parts(dist_matrix, len);
+    solve_into(
+        || {
+            let distances = Distances::Matrix(MatrixDistances::new(n, distances.to_vec()));
+            Instance::with_distances(n, distances, None, 0)
+        },
+        time_ms,
+        out_tour,
+    )
+}
+
+/// Solves the EUC_2D TSP over the `n` cities `(xs[i], ys[i])` for `time_ms` milliseconds with
+/// the basic GRASP. Distances are rounded to integers as in TSPLIB. Writes the tour as 0-based
+/// city indices to `out_tour` and returns its length, or -1 if the arguments are invalid or the
+/// solver failed.
+///
+/// # Safety
+///
+/// `xs` and `ys` must be valid for reads of `n` elements and `out_tour` for writes of `n`
+/// elements.
+#[no_mangle]
+pub unsafe extern "C" fn grasp_solve_euclidean(
+    xs: *const f64,
+    ys: *const f64,
+    n: usize,
+    time_ms: u64,
+    out_tour: *mut usize,
+) -> i64 {
+    if xs.is_null() || ys.is_null() || out_tour.is_null() || n < 3 {
+        return -1;
+    }
+    let (xs, ys) = (slice::from_raw_parts(xs, n), slice::from_raw_parts(ys, n));
+    solve_into(
+        || Instance::from_coords(xs.iter().copied().zip(ys.iter().copied()).collect(), 0),
+        time_ms,
+        out_tour,
+    )
+}
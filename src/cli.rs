@@ -1,28 +1,74 @@
-use clap::{ArgAction, Parser, ValueEnum};
+use crate::benchmark::find_instance_files;
+use crate::construction::{
+    CheapestInsertion, Constructive, FarthestInsertion, GreedyEdge, NearestNeighbor,
+    PerturbedCosts, SampleGreedy, SpaceFillingCurve,
+};
+use crate::decompose;
+use crate::error::{Error, ErrorKind, EXIT_CODES_HELP};
+#[cfg(feature = "gpu")]
+use crate::gpu::GpuTwoOpt;
+use crate::grasp::{
+    grasp, grasp_cooperative, grasp_static_pr, CooperativeConfig, Rcl, ReplacementPolicy,
+    StaticPrConfig,
+};
+use crate::ils::ils;
+use crate::instance::{Cost, Instance};
+use crate::learning::LearningConstruction;
+use crate::lns::{lns, LnsConfig, LnsDestroy};
+use crate::local_search::{
+    BottleneckTwoOpt, CandidateTwoOpt, LocalSearch, OrOpt, ParallelTwoOpt, RobustTwoOpt, Swap,
+    ThreeOpt, TwoHOpt, TwoOpt, Vnd,
+};
+use crate::memetic::{memetic, MemeticConfig};
+use crate::memory::parse_size;
+use crate::metaheuristic::{self, DriverConfig};
+use crate::mtsp::MtspObjective;
+use crate::objective::Objective;
+use crate::pareto::ParetoConfig;
+use crate::perturbation::Perturbation;
+use crate::problem::EngineConfig;
+use crate::simulated_annealing::simulated_annealing;
+#[cfg(feature = "json")]
+use crate::solution::save_elite_json;
+use crate::solution::{
+    load_solutions, save_solutions, PrLocalSearch, PrSelection, PrStop, Solution,
+};
+use crate::tabu_search::{tabu_search, Tabu};
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
 use std::fmt;
-use std::fs;
-
-pub fn list_available_instances() -> String {
-    let mut instances = String::new();
-    if let Ok(entries) = fs::read_dir("instances") {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                if let Some(file_name) = entry.path().file_name() {
-                    if let Some(file_name_str) = file_name.to_str() {
-                        instances.push_str(&format!("  instances/{}\n", file_name_str));
-                    }
-                }
-            }
-        }
-    }
-    instances
+use std::path::Path;
+use std::time::Duration;
+
+/// The instance files under `dir`, one per line.
+pub fn list_available_instances(dir: &str) -> String {
+    find_instance_files(dir)
+        .iter()
+        .map(|file| format!("  {}\n", file))
+        .collect()
 }
 
 #[derive(Debug, Clone, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GraspVariant {
     Basic,
     StaticPR,
-    Benchmark,
+    Cooperative,
+    Ils,
+    SimulatedAnnealing,
+    Lns,
+    Tabu,
+    Memetic,
+    Mtsp,
+    Pctsp,
+    /// GRASP on k-means clusters of the cities, joined into one tour.
+    Decompose,
+    /// Bi-objective GRASP over the distances and the `--second-costs` matrix.
+    Pareto,
+    /// The `--metaheuristic` run by the generic parallel driver.
+    Custom,
 }
 
 impl fmt::Display for GraspVariant {
@@ -30,35 +76,986 @@ impl fmt::Display for GraspVariant {
         match self {
             GraspVariant::Basic => write!(f, "Basic"),
             GraspVariant::StaticPR => write!(f, "StaticPR"),
-            GraspVariant::Benchmark => write!(f, "Benchmark"),
+            GraspVariant::Cooperative => write!(f, "Cooperative"),
+            GraspVariant::Ils => write!(f, "ILS"),
+            GraspVariant::SimulatedAnnealing => write!(f, "SimulatedAnnealing"),
+            GraspVariant::Lns => write!(f, "LNS"),
+            GraspVariant::Tabu => write!(f, "Tabu"),
+            GraspVariant::Memetic => write!(f, "Memetic"),
+            GraspVariant::Mtsp => write!(f, "mTSP"),
+            GraspVariant::Pctsp => write!(f, "PCTSP"),
+            GraspVariant::Decompose => write!(f, "Decompose"),
+            GraspVariant::Pareto => write!(f, "Pareto"),
+            GraspVariant::Custom => write!(f, "Custom"),
         }
     }
 }
 
+#[derive(Debug, Clone, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LocalSearchKind {
+    Swap,
+    TwoOpt,
+    /// Best-improvement 2-opt evaluating all moves of the tour in parallel.
+    ParallelTwoOpt,
+    /// 2-opt over Delaunay and nearest-neighbour candidate edges.
+    CandidateTwoOpt,
+    TwoHOpt,
+    OrOpt,
+    ThreeOpt,
+    Vnd,
+    Tabu,
+    #[cfg(feature = "gpu")]
+    Gpu2opt,
+}
+
+/// When path relinking runs the local search on its intermediate solutions.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PrLocalSearchKind {
+    /// Never.
+    None,
+    /// Only on the best intermediate solution.
+    BestOnly,
+    /// Every `--pr-ls-interval` steps.
+    Every,
+}
+
+/// Restricted candidate list of the nearest-neighbor construction.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RclKind {
+    /// The `--rcl-size` closest cities, or the closest `--rcl-fraction` of them.
+    Cardinality,
+    /// The cities within `--rcl-alpha` of the closest one.
+    Value,
+    /// Value-based, with an alpha drawn uniformly from [0, `--rcl-alpha`] for each construction.
+    RandomValue,
+}
+
+/// How path relinking picks the next position to restore.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PrSelectionKind {
+    /// The next differing position.
+    Sequential,
+    /// The cheapest restoring swap.
+    Greedy,
+    /// A random swap among the cheapest ones, as set by `--pr-alpha`.
+    GreedyRandomized,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConstructionKind {
+    NearestNeighbor,
+    CheapestInsertion,
+    FarthestInsertion,
+    GreedyEdge,
+    SpaceFillingCurve,
+    /// Greedy nearest neighbor over randomly perturbed distances (see `--cost-noise`).
+    PerturbedCosts,
+    /// Closest of a random sample of the remaining cities (see `--sample-size`).
+    SampleGreedy,
+}
+
+/// Cities kept by `--max-cities`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SubsetKind {
+    /// A random sample, reproducible with `--seed`.
+    Random,
+    /// The first cities of the instance file.
+    Prefix,
+}
+
 /// Command-line interface (CLI) options.
 #[derive(Parser)]
 #[command(name = "GRASP TSP Solver")]
 #[command(about = "A simple CLI to solve TSP using GRASP algorithm", long_about = None)]
 #[command(long_about = None, help_template =
     "{before-help}{name} {version}\n\n{about}\n\n{usage-heading} {usage}\n\n{all-args}{after-help}")]
+#[command(after_help = EXIT_CODES_HELP)]
 pub struct Cli {
-    /// Path to the instance file.
-    #[arg(short = 'f', long, default_value_t = String::from("instances/bays29.txt"))]
+    #[command(flatten)]
+    pub solve: SolveArgs,
+
+    /// Only print warnings and errors.
+    #[arg(short = 'q', long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Print more diagnostics; repeat for more detail (`--verbose --verbose`). There is no
+    /// `-v` short form: it has always selected the variant.
+    #[arg(long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Print fatal errors to stderr as a single-line JSON object with the error kind, exit
+    /// code and message.
+    #[arg(long, global = true)]
+    pub json_errors: bool,
+
+    /// Number of worker threads used by the solver (default: all cores).
+    #[arg(long, global = true)]
+    pub threads: Option<usize>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Options of a solver run. They are accepted both by the `solve` subcommand and, for
+/// compatibility, without any subcommand.
+#[derive(Args, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolveArgs {
+    /// Path to the instance file (TSPLIB, or `.json` / `.csv`), or its path within
+    /// `--instances-dir`.
+    #[arg(short = 'f', long, default_value_t = String::from("bays29.tsp"))]
     pub instance_file: String,
 
-    /// Time limit for the GRASP algorithm in seconds.
-    #[arg(short = 't', long, default_value_t = 120)]
-    pub time_limit: u64,
+    /// Directory of the instance library: instance files not found as given are looked up in
+    /// it, and `--fetch` downloads into it.
+    #[arg(long, env = "GRASP_INSTANCES_DIR", default_value_t = String::from("instances"))]
+    pub instances_dir: String,
+
+    /// Time limit for the GRASP algorithm, such as `90s`, `2m30s` or `500ms` (a bare number
+    /// is in seconds).
+    #[arg(short = 't', long, default_value = "120s", value_parser = parse_time_limit)]
+    pub time_limit: Duration,
+
+    /// Largest distance matrix to build, such as `512M` or `4GiB`. Loading an instance whose
+    /// matrix would be larger fails, except for EUC_2D instances, whose distances are then
+    /// computed on the fly.
+    #[arg(long, value_parser = parse_size)]
+    pub max_memory: Option<u64>,
+
+    /// Also stop after this many iterations, summed over all threads (for SimulatedAnnealing,
+    /// an iteration is 1024 moves).
+    #[arg(long)]
+    pub max_iterations: Option<usize>,
+
+    /// Seed of the random generators. Runs are only reproducible on a single thread.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Reproducible run: a single thread, the seed of `--seed` (0 by default) and no time
+    /// limit, stopping after `--max-iterations` iterations (1000 by default).
+    #[arg(long, conflicts_with_all = ["runs", "instance_dir"])]
+    pub deterministic: bool,
 
     /// Variant of the GRASP to be used.
     #[arg(short = 'v', long, default_value = "basic")]
     pub variant: GraspVariant,
 
-    /// Size of the elite set for StaticPR (ignored for Basic).
+    /// Constructive heuristic used to build each initial solution.
+    #[arg(short = 'c', long, default_value = "nearest-neighbor")]
+    pub construction: ConstructionKind,
+
+    /// Bias the nearest-neighbor construction towards the edges that appear often in elite
+    /// solutions (learning GRASP).
+    #[arg(long, conflicts_with = "construction")]
+    pub learning: bool,
+
+    /// Factor applied to the learned edge frequencies after every batch of iterations.
+    #[arg(long, default_value_t = 0.95)]
+    pub learning_decay: f32,
+
+    /// Maximum relative increase of each distance seen by the perturbed-costs construction.
+    #[arg(long, default_value_t = 0.2)]
+    pub cost_noise: f64,
+
+    /// Number of remaining cities sampled at each step of the sample-greedy construction.
+    #[arg(long, default_value_t = 10)]
+    pub sample_size: usize,
+
+    /// Restricted candidate list of the nearest-neighbor construction.
+    #[arg(long, value_enum, default_value_t = RclKind::Cardinality)]
+    pub rcl: RclKind,
+
+    /// Number of cities in a cardinality-based list (overrides `--rcl-fraction`).
+    #[arg(long)]
+    pub rcl_size: Option<usize>,
+
+    /// Fraction of the remaining cities in a cardinality-based list.
+    #[arg(long, default_value_t = 1.0 / 3.0)]
+    pub rcl_fraction: f64,
+
+    /// Alpha of a value-based list: 0 is greedy and 1 uniformly random.
+    #[arg(long, default_value_t = 0.3)]
+    pub rcl_alpha: f64,
+
+    /// Local search used to improve each constructed solution.
+    #[arg(short = 'l', long, default_value = "two-opt")]
+    pub local_search: LocalSearchKind,
+
+    /// Number of iterations a removed edge stays tabu.
+    #[arg(long, default_value_t = 10)]
+    pub tabu_tenure: usize,
+
+    /// Number of iterations without improvement before tabu search stops.
+    #[arg(long, default_value_t = 100)]
+    pub tabu_iterations: usize,
+
+    /// Size of the elite set for StaticPR and Cooperative (ignored for Basic).
     #[arg(short = 'e', long, default_value_t = 10)]
     pub elite_size: usize,
 
+    /// Seed the StaticPR elite set with the solutions saved by `--elite-out` in a previous run.
+    #[arg(long)]
+    pub elite_in: Option<String>,
+
+    /// Save the final StaticPR elite set to this file.
+    #[arg(long)]
+    pub elite_out: Option<String>,
+
+    /// Write the final StaticPR elite set to this JSON file, with the distance and tour of each
+    /// solution and the edge differences between every pair of them. Requires the json feature.
+    #[arg(long)]
+    pub elite_json: Option<String>,
+
+    /// Size of the population for Memetic.
+    #[arg(long, default_value_t = 30)]
+    pub population_size: usize,
+
+    /// Minimum edge difference between elite solutions, as a fraction of the number of cities.
+    #[arg(long, default_value_t = 0.1)]
+    pub min_diversity: f64,
+
+    /// Policy used to replace elite solutions once the elite set is full.
+    #[arg(long, default_value = "worst")]
+    pub replacement_policy: ReplacementPolicy,
+
+    /// Fraction, in (0, 1], of the way to the guiding solution that path relinking walks: below
+    /// 1 it stops short of the guide.
+    #[arg(long, default_value_t = 1.0, value_parser = parse_fraction)]
+    pub pr_depth: f64,
+
+    /// When path relinking runs the local search on its intermediate solutions.
+    #[arg(long, value_enum, default_value_t = PrLocalSearchKind::Every)]
+    pub pr_local_search: PrLocalSearchKind,
+
+    /// Number of path relinking steps between local searches, with `--pr-local-search every`.
+    #[arg(long, default_value_t = 1)]
+    pub pr_ls_interval: usize,
+
+    /// Whether path relinking stops at the first improving intermediate solution or walks as far
+    /// towards the guiding solution as `--pr-depth` allows.
+    #[arg(long, value_enum, default_value_t = PrStop::FirstImprove)]
+    pub pr_stop: PrStop,
+
+    /// How path relinking picks the position restored at each step.
+    #[arg(long, value_enum, default_value_t = PrSelectionKind::Sequential)]
+    pub pr_selection: PrSelectionKind,
+
+    /// Greediness of `--pr-selection greedy-randomized`: 0 always takes the cheapest swap, 1
+    /// takes any swap.
+    #[arg(long, default_value_t = 0.2)]
+    pub pr_alpha: f64,
+
+    /// Every this many iterations, the Cooperative threads restart from a perturbed elite
+    /// solution instead of a fresh construction.
+    #[arg(long, default_value_t = 5)]
+    pub restart_interval: usize,
+
+    /// A Cooperative thread that has gone this many iterations without improving its own best
+    /// solution restarts from a perturbed elite solution.
+    #[arg(long)]
+    pub stagnation_threshold: Option<usize>,
+
+    /// Perturbation used by ILS and by the Cooperative restarts.
+    #[arg(long, value_enum, default_value_t = Perturbation::DoubleBridge)]
+    pub perturbation: Perturbation,
+
+    /// Number of times the perturbation move is applied.
+    #[arg(long, default_value_t = 1)]
+    pub perturbation_strength: usize,
+
+    /// Geometric cooling rate for SimulatedAnnealing and LNS.
+    #[arg(long, default_value_t = 0.9995)]
+    pub cooling_rate: f64,
+
+    /// Number of cities removed and reinserted by each LNS iteration.
+    #[arg(long, default_value_t = 10)]
+    pub lns_removal: usize,
+
+    /// How LNS chooses the cities to remove.
+    #[arg(long, value_enum, default_value_t = LnsDestroy::Related)]
+    pub lns_destroy: LnsDestroy,
+
+    /// Number of k-means clusters of the decompose variant [default: one per 500 cities].
+    #[arg(long)]
+    pub clusters: Option<usize>,
+
+    /// Metaheuristic run by the custom variant: `grasp`, `grasp-pr` (GRASP relinking elite
+    /// tours after every batch), or one registered through the library API.
+    #[arg(long, default_value = "grasp")]
+    pub metaheuristic: String,
+
+    /// Write the final tour as an SVG image (coordinate-based instances only).
+    #[arg(long)]
+    pub plot_svg: Option<String>,
+
+    /// TSPLIB tour file overlaid on the SVG plot for comparison (e.g. the best-known tour).
+    #[arg(long, requires = "plot_svg")]
+    pub plot_reference: Option<String>,
+
+    /// Print the final tour in the terminal with braille characters, scaled to the terminal
+    /// size (coordinate-based instances only).
+    #[arg(long)]
+    pub plot_terminal: bool,
+
+    /// Write the final tour as a PNG image (coordinate-based instances only).
+    #[cfg(feature = "png")]
+    #[arg(long)]
+    pub plot_png: Option<String>,
+
+    /// Write a PNG of the best tour after every improvement into this directory, numbered in
+    /// order, for assembling into an animation of the search.
+    #[cfg(feature = "png")]
+    #[arg(long)]
+    pub png_frames: Option<String>,
+
+    /// Animate the evolution of the best tour: an animated GIF with one frame per improvement
+    /// if the path ends in `.gif`, otherwise a directory of numbered PNG frames.
+    #[cfg(feature = "png")]
+    #[arg(long)]
+    pub animate: Option<String>,
+
+    /// Milliseconds each frame of the `--animate` GIF is shown.
+    #[cfg(feature = "png")]
+    #[arg(long, default_value_t = 200)]
+    pub animate_delay: u32,
+
+    /// Width in pixels of the PNG and GIF images.
+    #[cfg(feature = "png")]
+    #[arg(long, default_value_t = 800)]
+    pub png_width: u32,
+
+    /// Height in pixels of the PNG and GIF images.
+    #[cfg(feature = "png")]
+    #[arg(long, default_value_t = 800)]
+    pub png_height: u32,
+
+    /// Label every city of the PNG and GIF images with its id.
+    #[cfg(feature = "png")]
+    #[arg(long)]
+    pub png_labels: bool,
+
+    /// Write ready-to-plot whitespace-separated files into this directory: the best tour's
+    /// coordinates in order, the improvement trace and the final elite pool's distances, with
+    /// a gnuplot script plotting them.
+    #[arg(long)]
+    pub export_plot_data: Option<String>,
+
+    /// Write every improvement event (elapsed time, iteration, thread, distance) to a CSV file.
+    #[arg(long)]
+    pub trace: Option<String>,
+
+    /// Also record the cost of every Nth GRASP iteration before and after local search, written
+    /// next to the trace as `<trace>_iterations.csv`.
+    #[arg(long, requires = "trace")]
+    pub trace_iterations: Option<usize>,
+
+    /// Criterion that tours are ranked by. The bottleneck objective, minimizing the longest
+    /// edge, and the robust objective are supported by the GRASP variants (basic, static-pr,
+    /// cooperative, memetic and the grasp metaheuristics of custom), whose local search they
+    /// replace by a 2-opt that ranks moves by the objective.
+    #[arg(long, value_enum, default_value_t = Objective::Total)]
+    pub objective: Objective,
+
+    /// Instance file with the variance of each edge cost, over the same cities as the instance,
+    /// for the robust objective. Its distances are read as integers.
+    #[arg(long, required_if_eq("objective", "robust"))]
+    pub variances: Option<String>,
+
+    /// Weight of the standard deviation of the tour length in the robust objective.
+    #[arg(long, default_value_t = 1.0)]
+    pub risk_aversion: f64,
+
+    /// Solve the open-path variant: the tour does not return to its first city.
+    #[arg(long)]
+    pub open_tour: bool,
+
+    /// Solve only this many cities of the instance, chosen by `--subset`, for quick trials on
+    /// a large instance. The best tour is also printed with the original city indices;
+    /// `--start-city`, `--end-city` and `--constraints` refer to the cities of the subset.
+    #[arg(long)]
+    pub max_cities: Option<usize>,
+
+    /// How `--max-cities` chooses the cities.
+    #[arg(long, value_enum, default_value_t = SubsetKind::Random, requires = "max_cities")]
+    pub subset: SubsetKind,
+
+    /// City (0-based index) that the tour must start at.
+    #[arg(long)]
+    pub start_city: Option<usize>,
+
+    /// City (0-based index) that the tour must end at.
+    #[arg(long, requires = "start_city")]
+    pub end_city: Option<usize>,
+
+    /// Constraints file listing `forced <a> <b>` and `forbidden <a> <b>` edges (0-based cities).
+    #[arg(long)]
+    pub constraints: Option<String>,
+
+    /// Tour file (TSPLIB `.tour` format, possibly listing only some of the cities) whose cities
+    /// stay at the start of the tour in the same order, e.g. the stops of a route already
+    /// dispatched; only the rest of the tour is optimized. Other edges can be fixed with
+    /// `--constraints`.
+    #[arg(long)]
+    pub freeze_tour: Option<String>,
+
+    /// Freeze only the first N cities of `--freeze-tour`.
+    #[arg(long, requires = "freeze_tour")]
+    pub freeze_prefix: Option<usize>,
+
+    /// Number of salesmen of the mtsp variant, all leaving from the depot (--start-city or 0).
+    #[arg(long, default_value_t = 2)]
+    pub salesmen: usize,
+
+    /// Objective of the mtsp variant.
+    #[arg(long, value_enum, default_value_t = MtspObjective::Total)]
+    pub mtsp_objective: MtspObjective,
+
+    /// Prizes file of the pctsp variant, with `<city> <prize> <penalty>` lines (0-based cities).
+    #[arg(long, required_if_eq("variant", "pctsp"))]
+    pub prizes: Option<String>,
+
+    /// Minimum prize the pctsp tour must collect.
+    #[arg(long, default_value_t = 0)]
+    pub min_prize: i64,
+
+    /// Instance file with the second cost matrix of the pareto variant (e.g. tolls), over the
+    /// same cities as the instance.
+    #[arg(long, required_if_eq("variant", "pareto"))]
+    pub second_costs: Option<String>,
+
+    /// Number of weightings of the two objectives used by the pareto variant.
+    #[arg(long, default_value_t = 11)]
+    pub pareto_weights: usize,
+
+    /// Write the Pareto front of the pareto variant to a file (JSON if it ends in .json, CSV
+    /// otherwise).
+    #[arg(long)]
+    pub pareto_output: Option<String>,
+
+    /// Decimal digits kept in EUC_2D distances (0 rounds them to integers as TSPLIB does).
+    #[arg(long, default_value_t = 0)]
+    pub precision: u32,
+
+    /// Load the instance through a binary cache stored next to it (`<instance>.cache`),
+    /// rebuilding it when the instance file changes.
+    #[cfg(feature = "cache")]
+    #[arg(long)]
+    pub cache: bool,
+
+    /// Download the instance with this name (e.g. `berlin52`) into `--instances-dir`, unless it was
+    /// downloaded before, and solve it instead of `--instance-file`.
+    #[cfg(feature = "fetch")]
+    #[arg(long)]
+    pub fetch: Option<String>,
+
+    /// Download URL of `--fetch`, where `{name}` stands for the instance name.
+    #[cfg(feature = "fetch")]
+    #[arg(
+        long,
+        default_value_t = String::from(
+            "http://comopt.ifi.uni-heidelberg.de/software/TSPLIB95/tsp/{name}.tsp.gz"
+        )
+    )]
+    pub fetch_url: String,
+
+    /// OSRM table service used for the distances of a CSV instance of `lat` and `lon` points,
+    /// e.g. `http://localhost:5000/table/v1/driving`, instead of the haversine distances.
+    #[cfg(feature = "osrm")]
+    #[arg(long)]
+    pub osrm_url: Option<String>,
+
+    /// Best-known tour length used for the optimality gap, overriding the built-in TSPLIB table.
+    #[arg(long)]
+    pub optimum: Option<Cost>,
+
+    /// Show a live dashboard of the run in the terminal.
+    #[cfg(feature = "tui")]
+    #[arg(long)]
+    pub tui: bool,
+
+    /// Execute the variant this many independent times and report aggregate statistics.
+    #[arg(long, default_value_t = 1)]
+    pub runs: usize,
+
+    /// Write the per-run results of `--runs` to a file (JSON if it ends in .json, CSV otherwise).
+    #[arg(long)]
+    pub runs_output: Option<String>,
+
+    /// Solve every instance (`.tsp` or `.tsp.gz` file) under this directory in turn instead of
+    /// `--instance-file`.
+    #[arg(long)]
+    pub instance_dir: Option<String>,
+
+    /// Number of instances of `--instance-dir` solved at the same time, sharing the threads.
+    #[arg(long, default_value_t = 1)]
+    pub parallel_instances: usize,
+
+    /// Write the per-instance results of `--instance-dir` to a file (JSON if it ends in .json,
+    /// CSV otherwise).
+    #[arg(long)]
+    pub batch_output: Option<String>,
+
     /// Execute with default settings.
     #[arg(short = 'd', long, default_value_t = false, action = ArgAction::SetTrue)]
     pub default: bool,
 }
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Solve an instance (the default when no subcommand is given).
+    Solve(Box<SolveArgs>),
+
+    /// Time-to-target experiment: runs the chosen variant repeatedly until it reaches a target
+    /// distance and writes the empirical distribution of hitting times to a CSV file.
+    Ttt {
+        /// Target distance; a run stops as soon as it finds a solution this good.
+        #[arg(long)]
+        target: Cost,
+
+        /// Number of independent runs.
+        #[arg(short = 'n', long, default_value_t = 100)]
+        runs: usize,
+
+        /// Output CSV file.
+        #[arg(short = 'o', long, default_value_t = String::from("ttt.csv"))]
+        output: String,
+    },
+
+    /// Validate a tour file against an instance and report its length and optimality gap.
+    Verify {
+        /// Instance file.
+        instance: String,
+
+        /// Tour file in TSPLIB .tour format.
+        tour: String,
+    },
+
+    /// Benchmark local searches and variants over a set of instances, writing one consolidated
+    /// CSV file.
+    Benchmark {
+        /// Instance files, or directories whose .tsp files are all benchmarked.
+        #[arg(required = true, num_args = 1..)]
+        instances: Vec<String>,
+
+        /// Local searches applied to a constructed solution (comma-separated).
+        #[arg(short = 'l', long, value_delimiter = ',')]
+        local_searches: Vec<LocalSearchKind>,
+
+        /// Variants run for the per-run time limit (comma-separated).
+        #[arg(short = 'v', long, value_delimiter = ',')]
+        variants: Vec<GraspVariant>,
+
+        /// Number of runs per instance and method.
+        #[arg(short = 'n', long, default_value_t = 10, value_parser = parse_at_least_one)]
+        runs: usize,
+
+        /// Time limit for each variant run, such as `10s` or `500ms` (a bare number is in
+        /// seconds).
+        #[arg(short = 't', long, default_value = "10s", value_parser = parse_time_limit)]
+        time_limit: Duration,
+
+        /// Output CSV file.
+        #[arg(short = 'o', long, default_value_t = String::from("benchmark_results.csv"))]
+        output: String,
+
+        /// Also render an HTML report of the results into this directory: per-instance tables,
+        /// convergence plots, the parameters and the best tours.
+        #[arg(long)]
+        report: Option<String>,
+    },
+
+    /// Generate a random EUC_2D instance in TSPLIB format.
+    Generate {
+        /// Number of cities.
+        #[arg(short = 'n', long)]
+        cities: usize,
+
+        /// Side of the square the cities are placed in.
+        #[arg(long, default_value_t = 1000.0)]
+        size: f64,
+
+        /// Seed of the random generator, for reproducible instances.
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Output instance file.
+        #[arg(short = 'o', long)]
+        output: String,
+    },
+
+    /// Serve the solver over HTTP. Solves use the top-level solver options unless the request
+    /// overrides them.
+    #[cfg(feature = "serve")]
+    Serve {
+        /// Address to listen on.
+        #[arg(long, default_value_t = String::from("127.0.0.1:8080"))]
+        addr: String,
+    },
+
+    /// Island model across processes: every process runs GRASP on the same instance with the
+    /// top-level solver options, and they periodically exchange their elite sets through a
+    /// coordinator over TCP.
+    Island {
+        /// Run as the coordinator, listening for the islands on this address.
+        #[arg(long, conflicts_with = "connect", required_unless_present = "connect")]
+        listen: Option<String>,
+
+        /// Run as an island, connecting to the coordinator at this address.
+        #[arg(long)]
+        connect: Option<String>,
+
+        /// Number of islands the coordinator waits for before starting.
+        #[arg(long, default_value_t = 1)]
+        islands: usize,
+
+        /// Seconds between elite set exchanges.
+        #[arg(long, default_value_t = 5)]
+        migration_interval: u64,
+    },
+
+    /// Print the metadata of an instance file and statistics of its distances: symmetry,
+    /// range, triangle-inequality violations and coordinate bounding box.
+    Info {
+        /// Instance file.
+        instance: String,
+    },
+
+    /// Solve a quadratic assignment problem (QAPLIB format) by GRASP with path relinking, with
+    /// the top-level time limit, RCL, elite set and path relinking options.
+    Qap {
+        /// Instance file.
+        instance: String,
+    },
+}
+
+/// Parses a time limit with units, such as `90s`, `2m30s` or `500ms`, or a bare number of
+/// seconds (possibly fractional).
+fn parse_time_limit(text: &str) -> Result<Duration, String> {
+    if let Ok(seconds) = text.parse::<f64>() {
+        return Duration::try_from_secs_f64(seconds).map_err(|error| error.to_string());
+    }
+    humantime::parse_duration(text).map_err(|error| error.to_string())
+}
+
+/// Parses a count that must be at least 1.
+fn parse_at_least_one(text: &str) -> Result<usize, String> {
+    match text.parse::<usize>() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        Ok(count) => Ok(count),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+/// Parses a fraction in (0, 1].
+fn parse_fraction(text: &str) -> Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(fraction) if fraction > 0.0 && fraction <= 1.0 => Ok(fraction),
+        Ok(_) => Err("must be greater than 0 and at most 1".to_string()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+impl Cli {
+    /// Solver options, taken from the `solve` subcommand when it is used.
+    pub fn solve_args(&self) -> &SolveArgs {
+        match &self.command {
+            Some(Command::Solve(args)) => args,
+            _ => &self.solve,
+        }
+    }
+}
+
+impl SolveArgs {
+    /// `file` as given when it exists, otherwise its path within `--instances-dir`.
+    pub fn resolve_instance(&self, file: &str) -> String {
+        let in_dir = Path::new(&self.instances_dir).join(file);
+        if Path::new(file).exists() || !in_dir.exists() {
+            return file.to_string();
+        }
+        in_dir.to_string_lossy().into_owned()
+    }
+
+    /// `trace` keeping the records these options write out.
+    pub fn configure_trace(&self, trace: Trace) -> Trace {
+        #[cfg(feature = "png")]
+        let trace = trace.with_tours(self.png_frames.is_some() || self.animate.is_some());
+        trace.with_iteration_records(self.trace_iterations.unwrap_or(0))
+    }
+
+    /// Runs `variant`, which must produce a single tour, configured by these options.
+    pub fn solve(
+        &self,
+        variant: &GraspVariant,
+        instance: &Instance,
+        termination: &Termination,
+        trace: &Trace,
+    ) -> Result<Solution, Error> {
+        let solution = match variant {
+            GraspVariant::Basic => grasp(
+                instance,
+                termination,
+                &*self.construction(),
+                &*self.local_search(),
+                trace,
+            ),
+            GraspVariant::StaticPR => {
+                let mut elite_set = match &self.elite_in {
+                    Some(filename) => load_solutions(filename, instance)?,
+                    None => Vec::new(),
+                };
+                let best = grasp_static_pr(
+                    instance,
+                    termination,
+                    &*self.construction(),
+                    &*self.local_search(),
+                    &self.static_pr_config(),
+                    &mut elite_set,
+                    trace,
+                );
+                if let Some(filename) = &self.elite_out {
+                    save_solutions(filename, &elite_set)?;
+                }
+                #[cfg(feature = "json")]
+                if let Some(filename) = &self.elite_json {
+                    save_elite_json(filename, &elite_set, instance)?;
+                }
+                best
+            }
+            GraspVariant::Cooperative => grasp_cooperative(
+                instance,
+                termination,
+                &*self.construction(),
+                &*self.local_search(),
+                &self.cooperative_config(),
+                trace,
+            ),
+            GraspVariant::Ils => ils(
+                instance,
+                termination,
+                self.perturbation,
+                self.perturbation_strength,
+                trace,
+            ),
+            GraspVariant::SimulatedAnnealing => {
+                simulated_annealing(instance, termination, self.cooling_rate, trace)
+            }
+            GraspVariant::Lns => lns(instance, termination, &self.lns_config(), trace),
+            GraspVariant::Tabu => tabu_search(
+                instance,
+                termination,
+                self.tabu_tenure,
+                self.tabu_iterations,
+                trace,
+            ),
+            GraspVariant::Memetic => memetic(
+                instance,
+                termination,
+                &*self.construction(),
+                &*self.local_search(),
+                &self.memetic_config(),
+                trace,
+            ),
+            GraspVariant::Decompose => {
+                let clusters = self.clusters.unwrap_or_else(|| {
+                    instance
+                        .num_cities
+                        .div_ceil(decompose::DEFAULT_CLUSTER_SIZE)
+                });
+                decompose::decompose(
+                    instance,
+                    termination,
+                    clusters,
+                    trace,
+                    &|cluster, termination| {
+                        self.solve(&GraspVariant::Basic, cluster, termination, &Trace::new())
+                    },
+                )?
+            }
+            GraspVariant::Custom => {
+                let metaheuristic =
+                    metaheuristic::create(&self.metaheuristic, self).ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::InvalidArguments,
+                            format!(
+                                "Unknown metaheuristic {} (available: {})",
+                                self.metaheuristic,
+                                metaheuristic::names().join(", ")
+                            ),
+                        )
+                    })?;
+                metaheuristic::run(
+                    instance,
+                    termination,
+                    &*metaheuristic,
+                    &self.driver_config(),
+                    trace,
+                )
+            }
+            GraspVariant::Mtsp => unreachable!("mTSP produces several routes, not a single tour"),
+            GraspVariant::Pctsp => unreachable!("PCTSP tours visit a subset of the cities"),
+            GraspVariant::Pareto => {
+                unreachable!("Pareto produces a front of tours, not a single one")
+            }
+        };
+        Ok(solution)
+    }
+
+    /// Stop criteria of one run: the time limit and the iteration limit.
+    pub fn termination(&self) -> Termination {
+        Termination::new(self.time_limit).with_max_iterations(self.max_iterations)
+    }
+
+    /// Whether the selected variant ranks tours by `objective`; the others only minimize the
+    /// total length.
+    pub fn supports(&self, objective: Objective) -> bool {
+        match self.variant {
+            GraspVariant::Basic
+            | GraspVariant::StaticPR
+            | GraspVariant::Cooperative
+            | GraspVariant::Memetic => true,
+            // Unknown metaheuristics are reported when the run starts.
+            GraspVariant::Custom => metaheuristic::create(&self.metaheuristic, self)
+                .is_none_or(|metaheuristic| metaheuristic.supports(objective)),
+            _ => objective == Objective::Total,
+        }
+    }
+
+    pub fn rcl(&self) -> Rcl {
+        match self.rcl {
+            RclKind::Cardinality => match self.rcl_size {
+                Some(k) => Rcl::Cardinality(k),
+                None => Rcl::Fraction(self.rcl_fraction),
+            },
+            RclKind::Value => Rcl::Value(self.rcl_alpha),
+            RclKind::RandomValue => Rcl::RandomValue(self.rcl_alpha),
+        }
+    }
+
+    pub fn construction(&self) -> Box<dyn Constructive> {
+        if self.learning {
+            return Box::new(LearningConstruction::new(self.learning_decay, self.rcl()));
+        }
+        match self.construction {
+            ConstructionKind::NearestNeighbor => Box::new(NearestNeighbor { rcl: self.rcl() }),
+            ConstructionKind::CheapestInsertion => Box::new(CheapestInsertion),
+            ConstructionKind::FarthestInsertion => Box::new(FarthestInsertion),
+            ConstructionKind::GreedyEdge => Box::new(GreedyEdge),
+            ConstructionKind::SpaceFillingCurve => Box::new(SpaceFillingCurve),
+            ConstructionKind::PerturbedCosts => Box::new(PerturbedCosts {
+                noise: self.cost_noise,
+            }),
+            ConstructionKind::SampleGreedy => Box::new(SampleGreedy {
+                sample_size: self.sample_size,
+            }),
+        }
+    }
+
+    pub fn local_search(&self) -> Box<dyn LocalSearch> {
+        match self.objective {
+            Objective::Total => self.build_local_search(&self.local_search),
+            Objective::Bottleneck => Box::new(BottleneckTwoOpt),
+            Objective::Robust => Box::new(RobustTwoOpt),
+        }
+    }
+
+    pub fn build_local_search(&self, kind: &LocalSearchKind) -> Box<dyn LocalSearch> {
+        match kind {
+            LocalSearchKind::Swap => Box::new(Swap),
+            LocalSearchKind::TwoOpt => Box::new(TwoOpt),
+            LocalSearchKind::ParallelTwoOpt => Box::new(ParallelTwoOpt),
+            LocalSearchKind::CandidateTwoOpt => Box::new(CandidateTwoOpt),
+            LocalSearchKind::TwoHOpt => Box::new(TwoHOpt),
+            LocalSearchKind::OrOpt => Box::new(OrOpt),
+            LocalSearchKind::ThreeOpt => Box::new(ThreeOpt),
+            LocalSearchKind::Vnd => Box::new(Vnd::default()),
+            LocalSearchKind::Tabu => Box::new(Tabu {
+                tenure: self.tabu_tenure,
+                max_iterations: self.tabu_iterations,
+            }),
+            #[cfg(feature = "gpu")]
+            LocalSearchKind::Gpu2opt => Box::new(GpuTwoOpt::new()),
+        }
+    }
+
+    pub fn engine_config(&self) -> EngineConfig {
+        EngineConfig {
+            rcl: self.rcl(),
+            elite_size: self.elite_size,
+            min_diversity: self.min_diversity,
+            pr_depth: self.pr_depth,
+        }
+    }
+
+    pub fn driver_config(&self) -> DriverConfig {
+        DriverConfig {
+            elite_size: self.elite_size,
+            min_diversity: self.min_diversity,
+            replacement_policy: self.replacement_policy,
+        }
+    }
+
+    pub fn memetic_config(&self) -> MemeticConfig {
+        MemeticConfig {
+            population_size: self.population_size,
+            min_diversity: self.min_diversity,
+            replacement_policy: self.replacement_policy,
+        }
+    }
+
+    pub fn cooperative_config(&self) -> CooperativeConfig {
+        CooperativeConfig {
+            elite_size: self.elite_size,
+            min_diversity: self.min_diversity,
+            replacement_policy: self.replacement_policy,
+            restart_interval: self.restart_interval,
+            perturbation: self.perturbation,
+            perturbation_strength: self.perturbation_strength,
+            stagnation_threshold: self.stagnation_threshold,
+        }
+    }
+
+    pub fn lns_config(&self) -> LnsConfig {
+        LnsConfig {
+            removal: self.lns_removal,
+            destroy: self.lns_destroy,
+            cooling_rate: self.cooling_rate,
+        }
+    }
+
+    pub fn pareto_config(&self) -> ParetoConfig {
+        let pr = self.static_pr_config();
+        ParetoConfig {
+            weights: self.pareto_weights,
+            pr_depth: pr.pr_depth,
+            pr_local_search: pr.pr_local_search,
+            pr_stop: pr.pr_stop,
+            pr_selection: pr.pr_selection,
+        }
+    }
+
+    pub fn static_pr_config(&self) -> StaticPrConfig {
+        StaticPrConfig {
+            elite_size: self.elite_size,
+            min_diversity: self.min_diversity,
+            replacement_policy: self.replacement_policy,
+            pr_depth: self.pr_depth,
+            pr_local_search: match self.pr_local_search {
+                PrLocalSearchKind::None => PrLocalSearch::None,
+                PrLocalSearchKind::BestOnly => PrLocalSearch::BestOnly,
+                PrLocalSearchKind::Every => PrLocalSearch::Every(self.pr_ls_interval),
+            },
+            pr_stop: self.pr_stop,
+            pr_selection: match self.pr_selection {
+                PrSelectionKind::Sequential => PrSelection::Sequential,
+                PrSelectionKind::Greedy => PrSelection::Greedy,
+                PrSelectionKind::GreedyRandomized => PrSelection::GreedyRandomized(self.pr_alpha),
+            },
+        }
+    }
+}
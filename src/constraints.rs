@@ -0,0 +1,154 @@
+use crate::error::{Error, ErrorKind};
+use crate::instance::{Cost, Instance};
+use crate::solution::Solution;
+
+use std::collections::HashSet;
+
+/// Edges that must appear in the tour and edges that may not, as unordered city pairs.
+#[derive(Debug, Clone, Default)]
+pub struct EdgeConstraints {
+    pub forced: HashSet<(usize, usize)>,
+    pub forbidden: HashSet<(usize, usize)>,
+    /// Penalty folded into the distances by `apply`.
+    penalty: Cost,
+}
+
+fn edge(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+impl EdgeConstraints {
+    /// Constraints forcing every edge of `edges`.
+    pub fn forcing(edges: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        EdgeConstraints {
+            forced: edges.into_iter().map(|(a, b)| edge(a, b)).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Constraints keeping the cities of `prefix` together and in order: every edge between
+    /// two consecutive cities of it is forced.
+    pub fn freezing(prefix: &[usize]) -> Self {
+        Self::forcing(prefix.windows(2).map(|pair| (pair[0], pair[1])))
+    }
+
+    /// Loads a constraints file with one `forced <a> <b>` or `forbidden <a> <b>` line per edge
+    /// (0-based city indices). Blank lines and lines starting with `#` are ignored.
+    pub fn load(filename: &str) -> Result<Self, Error> {
+        let invalid = |message: String| Error::new(ErrorKind::Instance, message);
+        let content = std::fs::read_to_string(filename)
+            .map_err(|error| invalid(format!("Failed to read {}: {}", filename, error)))?;
+        let mut constraints = EdgeConstraints::default();
+
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let invalid_line = || invalid(format!("Invalid constraint line: {}", line));
+            let [kind, a, b] = fields[..] else {
+                return Err(invalid_line());
+            };
+            let a: usize = a.parse().map_err(|_| invalid_line())?;
+            let b: usize = b.parse().map_err(|_| invalid_line())?;
+            match kind {
+                "forced" => constraints.forced.insert(edge(a, b)),
+                "forbidden" => constraints.forbidden.insert(edge(a, b)),
+                _ => return Err(invalid(format!("Unknown constraint kind: {}", kind))),
+            };
+        }
+
+        Ok(constraints)
+    }
+
+    pub fn is_forced(&self, a: usize, b: usize) -> bool {
+        self.forced.contains(&edge(a, b))
+    }
+
+    pub fn is_forbidden(&self, a: usize, b: usize) -> bool {
+        self.forbidden.contains(&edge(a, b))
+    }
+
+    /// Number of forced edges missing from `solution` plus forbidden edges it uses.
+    pub fn violations(&self, solution: &Solution, instance: &Instance) -> usize {
+        let n = solution.path.len();
+        let last = if instance.open_tour { n - 1 } else { n };
+        let used: HashSet<(usize, usize)> = (0..last)
+            .map(|i| edge(solution.path[i], solution.path[(i + 1) % n]))
+            .collect();
+
+        self.forced.difference(&used).count() + self.forbidden.intersection(&used).count()
+    }
+
+    /// Folds the constraints into the instance's distances: forbidden edges cost a penalty
+    /// larger than any tour more and forced edges the same penalty less, so every move already
+    /// evaluates them. `Solution::eval` adds the penalties of the forced edges back, which keeps
+    /// the distance of a feasible tour unchanged and adds one penalty per violation. Constraints
+    /// already applied to the instance are kept alongside these.
+    pub fn apply(mut self, instance: &mut Instance) -> Result<(), Error> {
+        if let Some(applied) = Self::lift(instance) {
+            self.forced.extend(applied.forced);
+            self.forbidden.extend(applied.forbidden);
+        }
+
+        let n = instance.num_cities;
+        for &(a, b) in self.forced.iter().chain(&self.forbidden) {
+            if a >= n || b >= n || a == b {
+                return Err(Error::new(
+                    ErrorKind::Instance,
+                    format!("Invalid constraint edge ({}, {})", a, b),
+                ));
+            }
+        }
+        let mut degree = vec![0; n];
+        for &(a, b) in &self.forced {
+            degree[a] += 1;
+            degree[b] += 1;
+        }
+        if degree.iter().any(|&d| d > 2) {
+            return Err(Error::new(
+                ErrorKind::Infeasible,
+                "A city cannot have more than two forced edges",
+            ));
+        }
+
+        let matrix = instance.matrix_mut().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidArguments,
+                "Edge constraints require a precomputed distance matrix",
+            )
+        })?;
+        let max_distance = matrix.as_slice().iter().max().copied().unwrap_or(0);
+        let constraints = (self.forced.len() + self.forbidden.len()) as Cost;
+        let penalty = (max_distance * n as Cost + 1).min(Cost::MAX / 4 / (constraints + 1));
+
+        for &(a, b) in &self.forbidden {
+            matrix.add_to_edge(a, b, penalty);
+        }
+        for &(a, b) in &self.forced {
+            matrix.add_to_edge(a, b, -penalty);
+        }
+
+        instance.distance_offset = penalty * self.forced.len() as Cost;
+        self.penalty = penalty;
+        instance.constraints = Some(self);
+        Ok(())
+    }
+
+    /// Removes the constraints applied to `instance`, restoring its original distances, and
+    /// returns them.
+    pub fn lift(instance: &mut Instance) -> Option<Self> {
+        let constraints = instance.constraints.take()?;
+        let matrix = instance
+            .matrix_mut()
+            .expect("Edge constraints require a precomputed distance matrix");
+        for &(a, b) in &constraints.forbidden {
+            matrix.add_to_edge(a, b, -constraints.penalty);
+        }
+        for &(a, b) in &constraints.forced {
+            matrix.add_to_edge(a, b, constraints.penalty);
+        }
+        instance.distance_offset = 0;
+        Some(constraints)
+    }
+}
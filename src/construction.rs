@@ -0,0 +1,458 @@
+use crate::grasp::{biased_constructive_phase, Rcl};
+use crate::instance::{Cost, Instance};
+use crate::solution::Solution;
+
+use rand::seq::index;
+
+/// Constructive heuristic producing an initial tour, pluggable into the GRASP loops.
+pub trait Constructive: Sync {
+    fn construct(&self, instance: &Instance) -> Solution;
+
+    /// Called by the GRASP loops after every batch of iterations with their current elite
+    /// solutions, for heuristics that learn from them.
+    fn learn(&self, _elite: &[Solution]) {}
+}
+
+/// Randomized nearest neighbor: picks the next city from the restricted candidate list, by
+/// default the closest third of the candidates.
+#[derive(Default)]
+pub struct NearestNeighbor {
+    pub rcl: Rcl,
+}
+
+impl Constructive for NearestNeighbor {
+    fn construct(&self, instance: &Instance) -> Solution {
+        biased_constructive_phase(instance, self.rcl, None)
+    }
+}
+
+/// Cheapest insertion from a random start city.
+pub struct CheapestInsertion;
+
+impl Constructive for CheapestInsertion {
+    fn construct(&self, instance: &Instance) -> Solution {
+        cheapest_insertion(instance)
+    }
+}
+
+/// Farthest insertion from a random start city.
+pub struct FarthestInsertion;
+
+impl Constructive for FarthestInsertion {
+    fn construct(&self, instance: &Instance) -> Solution {
+        farthest_insertion(instance)
+    }
+}
+
+/// Greedy edge matching, with the fragments chained from a random one.
+pub struct GreedyEdge;
+
+impl Constructive for GreedyEdge {
+    fn construct(&self, instance: &Instance) -> Solution {
+        greedy_edge(instance)
+    }
+}
+
+/// Orders the cities along a randomly offset Hilbert curve; requires node coordinates.
+pub struct SpaceFillingCurve;
+
+impl Constructive for SpaceFillingCurve {
+    fn construct(&self, instance: &Instance) -> Solution {
+        space_filling_curve(instance)
+    }
+}
+
+/// GRASP with perturbed costs: greedy nearest neighbor over distances scaled by random factors
+/// in `[1, 1 + noise]`, drawn anew for each construction, instead of a restricted candidate list.
+pub struct PerturbedCosts {
+    pub noise: f64,
+}
+
+impl Constructive for PerturbedCosts {
+    fn construct(&self, instance: &Instance) -> Solution {
+        perturbed_nearest_neighbor(instance, self.noise)
+    }
+}
+
+/// Sample greedy: the next city is the closest of a random sample of the remaining cities, so
+/// each step costs `sample_size` distance evaluations instead of one per remaining city.
+pub struct SampleGreedy {
+    pub sample_size: usize,
+}
+
+impl Constructive for SampleGreedy {
+    fn construct(&self, instance: &Instance) -> Solution {
+        sample_greedy(instance, self.sample_size)
+    }
+}
+
+/// Converts a successor array describing a single cycle into a path starting at `start`.
+fn path_from_successors(successor: &[usize], start: usize) -> Solution {
+    let mut solution = Solution::new(successor.len());
+    let mut city = start;
+    for _ in 0..successor.len() {
+        solution.path.push(city);
+        city = successor[city];
+    }
+    solution
+}
+
+/// Cost of inserting `city` between `a` and `b`.
+fn insertion_cost(instance: &Instance, a: usize, b: usize, city: usize) -> Cost {
+    instance.dist(a, city) + instance.dist(city, b) - instance.dist(a, b)
+}
+
+/// Cheapest position (predecessor city) to insert `city` into the partial tour.
+fn cheapest_position(
+    instance: &Instance,
+    tour: &[usize],
+    successor: &[usize],
+    city: usize,
+) -> (usize, Cost) {
+    tour.iter()
+        .map(|&a| (a, insertion_cost(instance, a, successor[a], city)))
+        .min_by_key(|&(_, cost)| cost)
+        .unwrap()
+}
+
+/// Repeatedly inserts the city with the overall cheapest insertion cost. The best insertion of
+/// every remaining city is cached and only recomputed when the edge it targets is broken.
+pub fn cheapest_insertion(instance: &Instance) -> Solution {
+    let n = instance.num_cities;
+    let start = instance
+        .start_city
+        .unwrap_or_else(|| crate::rng::random::<usize>() % n);
+    let mut successor = vec![start; n];
+    let mut tour = vec![start];
+    let mut in_tour = vec![false; n];
+    in_tour[start] = true;
+
+    let mut best: Vec<(usize, Cost)> = (0..n)
+        .map(|city| (start, insertion_cost(instance, start, start, city)))
+        .collect();
+
+    while tour.len() < n {
+        let city = (0..n)
+            .filter(|&c| !in_tour[c])
+            .min_by_key(|&c| best[c].1)
+            .unwrap();
+        let a = best[city].0;
+        let b = successor[a];
+
+        successor[a] = city;
+        successor[city] = b;
+        in_tour[city] = true;
+        tour.push(city);
+
+        for other in (0..n).filter(|&c| !in_tour[c]) {
+            if best[other].0 == a {
+                best[other] = cheapest_position(instance, &tour, &successor, other);
+            } else {
+                for (x, y) in [(a, city), (city, b)] {
+                    let cost = insertion_cost(instance, x, y, other);
+                    if cost < best[other].1 {
+                        best[other] = (x, cost);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut solution = path_from_successors(&successor, start);
+    solution.fix_endpoints(instance);
+    solution
+}
+
+/// Repeatedly inserts the city farthest from the partial tour at its cheapest position.
+pub fn farthest_insertion(instance: &Instance) -> Solution {
+    let n = instance.num_cities;
+    let start = instance
+        .start_city
+        .unwrap_or_else(|| crate::rng::random::<usize>() % n);
+    let mut successor = vec![start; n];
+    let mut tour = vec![start];
+    let mut in_tour = vec![false; n];
+    in_tour[start] = true;
+    let mut distance_to_tour: Vec<Cost> = (0..n).map(|c| instance.dist(start, c)).collect();
+
+    while tour.len() < n {
+        let city = (0..n)
+            .filter(|&c| !in_tour[c])
+            .max_by_key(|&c| distance_to_tour[c])
+            .unwrap();
+        let (a, _) = cheapest_position(instance, &tour, &successor, city);
+
+        successor[city] = successor[a];
+        successor[a] = city;
+        in_tour[city] = true;
+        tour.push(city);
+
+        for (other, distance) in distance_to_tour.iter_mut().enumerate() {
+            *distance = (*distance).min(instance.dist(city, other));
+        }
+    }
+
+    let mut solution = path_from_successors(&successor, start);
+    solution.fix_endpoints(instance);
+    solution
+}
+
+fn find(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
+    }
+    x
+}
+
+/// Number of nearest neighbors per city used as candidate edges by greedy edge.
+const GREEDY_EDGE_NEIGHBORS: usize = 10;
+
+/// The `k` nearest neighbors of every city.
+pub fn neighbor_lists(instance: &Instance, k: usize) -> Vec<Vec<usize>> {
+    let n = instance.num_cities;
+    let k = k.min(n.saturating_sub(1));
+
+    if let Some(tree) = instance.kd_tree() {
+        let state = tree.full_state();
+        return (0..n)
+            .map(|i| {
+                let mut neighbors = tree.k_nearest(i, k, &state);
+                neighbors.sort_by_key(|&j| instance.dist(i, j));
+                neighbors
+            })
+            .collect();
+    }
+
+    (0..n)
+        .map(|i| {
+            let mut others: Vec<(Cost, usize)> = match instance.row(i) {
+                Some(row) => row.iter().copied().zip(0..n).collect(),
+                None => (0..n).map(|j| (instance.dist(i, j), j)).collect(),
+            };
+            others.swap_remove(i);
+            if k < others.len() {
+                others.select_nth_unstable(k);
+                others.truncate(k);
+            }
+            others.sort_unstable();
+            others.into_iter().map(|(_, j)| j).collect()
+        })
+        .collect()
+}
+
+/// Sorts the candidate edges to each city's nearest neighbors and adds the cheapest ones that
+/// keep every degree at most 2 and close no subtour. The resulting fragments are then chained
+/// from a random endpoint by jumping from the end of each fragment to the nearest free
+/// endpoint, so that repeated constructions differ.
+pub fn greedy_edge(instance: &Instance) -> Solution {
+    let n = instance.num_cities;
+    let mut edges: Vec<(usize, usize)> = neighbor_lists(instance, GREEDY_EDGE_NEIGHBORS)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, neighbors)| neighbors.into_iter().map(move |j| (i.min(j), i.max(j))))
+        .collect();
+    edges.sort_by_key(|&(i, j)| (instance.dist(i, j), i, j));
+    edges.dedup();
+
+    let mut degree = vec![0; n];
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut adjacency = vec![Vec::with_capacity(2); n];
+    let mut added = 0;
+
+    for (i, j) in edges {
+        if added + 1 >= n {
+            break;
+        }
+        if degree[i] >= 2 || degree[j] >= 2 {
+            continue;
+        }
+        let (root_i, root_j) = (find(&mut parent, i), find(&mut parent, j));
+        if root_i == root_j {
+            continue;
+        }
+
+        parent[root_i] = root_j;
+        degree[i] += 1;
+        degree[j] += 1;
+        adjacency[i].push(j);
+        adjacency[j].push(i);
+        added += 1;
+    }
+
+    let mut solution = Solution::new(n);
+    let mut visited = vec![false; n];
+    let endpoints: Vec<usize> = (0..n).filter(|&c| degree[c] < 2).collect();
+    let mut city = match endpoints.len() {
+        0 => 0,
+        len => endpoints[crate::rng::random::<usize>() % len],
+    };
+    loop {
+        solution.path.push(city);
+        visited[city] = true;
+
+        let next = adjacency[city]
+            .iter()
+            .copied()
+            .find(|&next| !visited[next])
+            .or_else(|| {
+                (0..n)
+                    .filter(|&c| !visited[c] && degree[c] < 2)
+                    .min_by_key(|&c| instance.dist(city, c))
+            });
+        match next {
+            Some(next) => city = next,
+            None => break,
+        }
+    }
+
+    solution.fix_endpoints(instance);
+    solution
+}
+
+/// Order of the Hilbert curve used by the space-filling curve construction.
+const HILBERT_ORDER: u32 = 16;
+
+/// Distance of the grid cell `(x, y)` along a Hilbert curve covering a `2^order` square grid.
+fn hilbert_index(order: u32, mut x: u64, mut y: u64) -> u64 {
+    let side = 1u64 << order;
+    let mut index = 0;
+    let mut s = side / 2;
+    while s > 0 {
+        let rx = u64::from(x & s > 0);
+        let ry = u64::from(y & s > 0);
+        index += s * s * ((3 * rx) ^ ry);
+
+        if ry == 0 {
+            if rx == 1 {
+                x = side - 1 - x;
+                y = side - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    index
+}
+
+/// Sorts the cities by their position along a Hilbert curve, giving a tour in O(n log n). The
+/// coordinate bounding box is mapped onto half of the curve's grid at a random offset, so that
+/// repeated constructions follow different curves, and the tour is rotated to start at a
+/// random city.
+pub fn space_filling_curve(instance: &Instance) -> Solution {
+    let coords = instance
+        .coords()
+        .expect("Space-filling curve construction requires node coordinates");
+
+    let (min_x, max_x) = coords
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &(x, _)| {
+            (lo.min(x), hi.max(x))
+        });
+    let (min_y, max_y) = coords
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &(_, y)| {
+            (lo.min(y), hi.max(y))
+        });
+    let span = (max_x - min_x).max(max_y - min_y).max(f64::EPSILON);
+    let cells = ((1u64 << (HILBERT_ORDER - 1)) - 1) as f64;
+    let offset = || crate::rng::random::<u64>() % (1u64 << (HILBERT_ORDER - 1));
+    let (offset_x, offset_y) = (offset(), offset());
+
+    let mut keyed: Vec<(u64, usize)> = coords
+        .iter()
+        .enumerate()
+        .map(|(city, &(x, y))| {
+            let gx = ((x - min_x) / span * cells) as u64 + offset_x;
+            let gy = ((y - min_y) / span * cells) as u64 + offset_y;
+            (hilbert_index(HILBERT_ORDER, gx, gy), city)
+        })
+        .collect();
+    keyed.sort_unstable();
+
+    let mut solution = Solution::new(instance.num_cities);
+    solution.path = keyed.into_iter().map(|(_, city)| city).collect();
+    let offset = crate::rng::random::<usize>() % solution.path.len();
+    solution.path.rotate_left(offset);
+    solution.fix_endpoints(instance);
+    solution
+}
+
+/// Distances of an instance with every edge scaled by a pseudo-random factor in
+/// `[1, 1 + noise]` derived from `seed`, so that one construction sees a consistent perturbed
+/// instance without storing `n * n` factors.
+struct PerturbedDistances<'a> {
+    instance: &'a Instance,
+    seed: u64,
+    noise: f64,
+}
+
+impl PerturbedDistances<'_> {
+    fn dist(&self, a: usize, b: usize) -> f64 {
+        let (low, high) = (a.min(b) as u64, a.max(b) as u64);
+        // SplitMix64 finalizer over the seed and the edge.
+        let mut x = self.seed ^ (low << 32 | high);
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+        x ^= x >> 31;
+        let unit = (x >> 11) as f64 / (1u64 << 53) as f64;
+        self.instance.dist(a, b) as f64 * (1.0 + self.noise * unit)
+    }
+}
+
+/// Nearest neighbor from a random start city over distances perturbed by up to `noise`. Only
+/// the construction sees the perturbed costs: the tour is evaluated on the true distances.
+pub fn perturbed_nearest_neighbor(instance: &Instance, noise: f64) -> Solution {
+    let n = instance.num_cities;
+    let costs = PerturbedDistances {
+        instance,
+        seed: crate::rng::random::<u64>(),
+        noise,
+    };
+    let start = instance
+        .start_city
+        .unwrap_or_else(|| crate::rng::random::<usize>() % n);
+
+    let mut solution = Solution::new(n);
+    solution.path.push(start);
+    let mut remaining: Vec<usize> = (0..n).filter(|&city| city != start).collect();
+    while !remaining.is_empty() {
+        let last_city = *solution.path.last().unwrap();
+        let (index, _) = remaining
+            .iter()
+            .enumerate()
+            .map(|(index, &city)| (index, costs.dist(last_city, city)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+        solution.path.push(remaining.swap_remove(index));
+    }
+
+    solution.fix_endpoints(instance);
+    solution
+}
+
+/// Builds a tour from a random start city, appending at each step the city closest to the
+/// last one among `sample_size` remaining cities drawn at random.
+pub fn sample_greedy(instance: &Instance, sample_size: usize) -> Solution {
+    let n = instance.num_cities;
+    let mut rng = crate::rng::thread_rng();
+    let start = instance
+        .start_city
+        .unwrap_or_else(|| crate::rng::random::<usize>() % n);
+
+    let mut solution = Solution::new(n);
+    solution.path.push(start);
+    let mut remaining: Vec<usize> = (0..n).filter(|&city| city != start).collect();
+    while !remaining.is_empty() {
+        let last_city = *solution.path.last().unwrap();
+        let amount = sample_size.clamp(1, remaining.len());
+        let index = index::sample(&mut rng, remaining.len(), amount)
+            .into_iter()
+            .min_by_key(|&index| instance.dist(last_city, remaining[index]))
+            .unwrap();
+        solution.path.push(remaining.swap_remove(index));
+    }
+
+    solution.fix_endpoints(instance);
+    solution
+}
@@ -0,0 +1,229 @@
+use crate::error::{Error, ErrorKind};
+use crate::grasp::constructive_phase;
+use crate::instance::Instance;
+use crate::local_search::{CandidateTwoOpt, LocalSearch};
+use crate::solution::Solution;
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use rand::Rng;
+use rayon::prelude::*;
+use tracing::info;
+
+/// Cities per cluster when the number of clusters is not given.
+pub const DEFAULT_CLUSTER_SIZE: usize = 500;
+
+/// Maximum number of Lloyd iterations of the k-means clustering.
+const KMEANS_ITERATIONS: usize = 100;
+
+/// Fraction of the time limit spent ordering the clusters.
+const ORDER_SHARE: f64 = 0.05;
+
+/// Decimal digits kept in the distances between cluster centroids, which may be close together.
+const CENTROID_PRECISION: u32 = 3;
+
+/// Cluster-first decomposition for instances too large to solve whole: the cities are split by
+/// k-means over their coordinates into `clusters` clusters, each solved on its own by `solve`,
+/// and the cluster tours are joined in the order of a tour over the cluster centroids. The
+/// joined tour is then improved by 2-opt over candidate neighbours. Each cluster gets a share of
+/// the time limit and of the iterations proportional to its size, and the final 2-opt that of a
+/// tenth of the cities.
+pub fn decompose(
+    instance: &Instance,
+    termination: &Termination,
+    clusters: usize,
+    trace: &Trace,
+    solve: &dyn Fn(&Instance, &Termination) -> Result<Solution, Error>,
+) -> Result<Solution, Error> {
+    let coords = instance.coords().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidArguments,
+            "Decomposition requires node coordinates",
+        )
+    })?;
+    if instance.constraints.is_some()
+        || instance.start_city.is_some()
+        || instance.end_city.is_some()
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidArguments,
+            "Decomposition does not support edge constraints or fixed endpoints",
+        ));
+    }
+
+    let n = instance.num_cities;
+    let labels = kmeans(coords, clusters.clamp(1, n));
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); clusters.clamp(1, n)];
+    for (city, &label) in labels.iter().enumerate() {
+        members[label].push(city);
+    }
+    members.retain(|cities| !cities.is_empty());
+    info!("Split {} cities into {} clusters", n, members.len());
+
+    let centroids: Vec<(f64, f64)> = members
+        .iter()
+        .map(|cities| centroid(cities.iter().map(|&city| coords[city])))
+        .collect();
+    let order: Vec<usize> = if members.len() > 3 {
+        let centroid_instance = Instance::from_coords(centroids.clone(), CENTROID_PRECISION);
+        let share = termination.share(ORDER_SHARE);
+        let order = solve(&centroid_instance, &share)?.path;
+        termination.absorb(&share);
+        order
+    } else {
+        (0..members.len()).collect()
+    };
+
+    let mut remaining = n + n / 10;
+    let tours: Vec<Vec<usize>> = members
+        .iter()
+        .map(|cities| {
+            let fraction = cities.len() as f64 / remaining as f64;
+            remaining -= cities.len();
+            if cities.len() <= 3 {
+                return Ok(cities.clone());
+            }
+            let cluster = instance.restricted(cities)?;
+            let solution = if termination.should_stop() {
+                constructive_phase(&cluster)
+            } else {
+                let share = termination.share(fraction);
+                let solution = solve(&cluster, &share)?;
+                termination.absorb(&share);
+                solution
+            };
+            Ok(solution.path.iter().map(|&city| cities[city]).collect())
+        })
+        .collect::<Result<_, Error>>()?;
+
+    let mut solution = Solution::new(n);
+    solution.path = stitch(instance, coords, &tours, &centroids, &order);
+    solution.eval(instance);
+    trace.record_improvement(1, 0, &solution, instance);
+
+    CandidateTwoOpt.improve_until(&mut solution, instance, &|| termination.should_stop());
+    solution.eval(instance);
+    termination.report(solution.total_distance);
+    trace.record_improvement(2, 0, &solution, instance);
+    Ok(solution)
+}
+
+/// Cluster of each point after k-means clustering into at most `k` clusters, seeded with
+/// k-means++. Clusters left empty keep their label, so some labels may have no points.
+pub fn kmeans(points: &[(f64, f64)], k: usize) -> Vec<usize> {
+    let mut rng = crate::rng::thread_rng();
+    let n = points.len();
+    let mut centers = vec![points[rng.gen_range(0..n)]];
+    let mut nearest: Vec<f64> = points
+        .iter()
+        .map(|&point| squared_distance(point, centers[0]))
+        .collect();
+    while centers.len() < k {
+        let total: f64 = nearest.iter().sum();
+        if total <= 0.0 {
+            break;
+        }
+        // k-means++: the next center is drawn with probability proportional to the squared
+        // distance to the closest center so far.
+        let mut target = rng.gen::<f64>() * total;
+        let next = nearest
+            .iter()
+            .position(|&weight| {
+                target -= weight;
+                target < 0.0
+            })
+            .unwrap_or(n - 1);
+        centers.push(points[next]);
+        for (point, nearest) in points.iter().zip(&mut nearest) {
+            *nearest = nearest.min(squared_distance(*point, points[next]));
+        }
+    }
+
+    let mut labels = vec![0; n];
+    for _ in 0..KMEANS_ITERATIONS {
+        let changed = labels
+            .par_iter_mut()
+            .zip(points)
+            .map(|(label, &point)| {
+                let closest = (0..centers.len())
+                    .min_by(|&a, &b| {
+                        squared_distance(point, centers[a])
+                            .total_cmp(&squared_distance(point, centers[b]))
+                    })
+                    .unwrap();
+                let changed = *label != closest;
+                *label = closest;
+                changed
+            })
+            .filter(|&changed| changed)
+            .count();
+        if changed == 0 {
+            break;
+        }
+        let mut sums = vec![(0, 0.0, 0.0); centers.len()];
+        for (&label, &(x, y)) in labels.iter().zip(points) {
+            let (count, sum_x, sum_y) = &mut sums[label];
+            *count += 1;
+            *sum_x += x;
+            *sum_y += y;
+        }
+        for (center, &(count, sum_x, sum_y)) in centers.iter_mut().zip(&sums) {
+            if count > 0 {
+                *center = (sum_x / count as f64, sum_y / count as f64);
+            }
+        }
+    }
+    labels
+}
+
+/// Joins the cluster `tours`, taken in `order`, into one path. Each tour is entered at its city
+/// closest to the previous exit (for the first, to the centroid of the last cluster) and
+/// followed in the direction whose last city is closer to the centroid of the next cluster.
+fn stitch(
+    instance: &Instance,
+    coords: &[(f64, f64)],
+    tours: &[Vec<usize>],
+    centroids: &[(f64, f64)],
+    order: &[usize],
+) -> Vec<usize> {
+    let mut path: Vec<usize> = Vec::with_capacity(instance.num_cities);
+    for (position, &cluster) in order.iter().enumerate() {
+        let tour = &tours[cluster];
+        let len = tour.len();
+        let entry = match path.last() {
+            Some(&exit) => (0..len).min_by_key(|&i| instance.dist(exit, tour[i])),
+            None => {
+                let previous = centroids[order[order.len() - 1]];
+                (0..len).min_by(|&a, &b| {
+                    squared_distance(coords[tour[a]], previous)
+                        .total_cmp(&squared_distance(coords[tour[b]], previous))
+                })
+            }
+        }
+        .unwrap();
+
+        // Forwards, the tour ends at the city before the entry; backwards, at the one after.
+        let next = centroids[order[(position + 1) % order.len()]];
+        let forward = squared_distance(coords[tour[(entry + len - 1) % len]], next)
+            <= squared_distance(coords[tour[(entry + 1) % len]], next);
+        path.extend((0..len).map(|step| {
+            if forward {
+                tour[(entry + step) % len]
+            } else {
+                tour[(entry + len - step) % len]
+            }
+        }));
+    }
+    path
+}
+
+fn centroid(points: impl Iterator<Item = (f64, f64)>) -> (f64, f64) {
+    let (count, sum_x, sum_y) = points.fold((0, 0.0, 0.0), |(count, sum_x, sum_y), (x, y)| {
+        (count + 1, sum_x + x, sum_y + y)
+    });
+    (sum_x / count as f64, sum_y / count as f64)
+}
+
+fn squared_distance((x1, y1): (f64, f64), (x2, y2): (f64, f64)) -> f64 {
+    (x1 - x2).powi(2) + (y1 - y2).powi(2)
+}
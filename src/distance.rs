@@ -0,0 +1,151 @@
+use crate::instance::Cost;
+
+/// Source of the distances between cities.
+pub trait DistanceProvider: Sync {
+    fn dist(&self, i: usize, j: usize) -> Cost;
+}
+
+/// Number of independent accumulators of the chunked kernels, so that consecutive additions do
+/// not depend on each other and can be vectorized.
+const LANES: usize = 8;
+
+/// Precomputed row-major `num_cities * num_cities` distance matrix.
+pub struct MatrixDistances {
+    num_cities: usize,
+    distances: Vec<Cost>,
+}
+
+impl MatrixDistances {
+    pub fn new(num_cities: usize, distances: Vec<Cost>) -> Self {
+        MatrixDistances {
+            num_cities,
+            distances,
+        }
+    }
+
+    /// Computes every distance of `provider` once, which the caller has checked with
+    /// `memory::check_matrix`.
+    pub fn from_provider(num_cities: usize, provider: &dyn DistanceProvider) -> Self {
+        let mut distances = vec![0; num_cities * num_cities];
+        for i in 0..num_cities {
+            for j in 0..num_cities {
+                distances[i * num_cities + j] = provider.dist(i, j);
+            }
+        }
+        Self::new(num_cities, distances)
+    }
+
+    /// The whole matrix in row-major order.
+    pub fn as_slice(&self) -> &[Cost] {
+        &self.distances
+    }
+
+    /// Distances from city `i` to every city.
+    #[inline]
+    pub fn row(&self, i: usize) -> &[Cost] {
+        &self.distances[i * self.num_cities..(i + 1) * self.num_cities]
+    }
+
+    /// Sum of the distances between consecutive cities of `path`, accumulated `LANES` edges at
+    /// a time.
+    pub fn path_length(&self, path: &[usize]) -> Cost {
+        let edges = path.len().saturating_sub(1);
+        let mut from = path[..edges].chunks_exact(LANES);
+        let mut to = path[path.len() - edges..].chunks_exact(LANES);
+
+        let mut lanes = [0; LANES];
+        for (from, to) in from.by_ref().zip(to.by_ref()) {
+            for lane in 0..LANES {
+                lanes[lane] += self.dist(from[lane], to[lane]);
+            }
+        }
+        let tail: Cost = from
+            .remainder()
+            .iter()
+            .zip(to.remainder())
+            .map(|(&a, &b)| self.dist(a, b))
+            .sum();
+        lanes.iter().sum::<Cost>() + tail
+    }
+
+    /// Adds `delta` to the distance between `a` and `b` in both directions.
+    pub fn add_to_edge(&mut self, a: usize, b: usize, delta: Cost) {
+        let n = self.num_cities;
+        self.distances[a * n + b] += delta;
+        self.distances[b * n + a] += delta;
+    }
+}
+
+impl DistanceProvider for MatrixDistances {
+    #[inline]
+    fn dist(&self, i: usize, j: usize) -> Cost {
+        self.distances[i * self.num_cities + j]
+    }
+}
+
+/// EUC_2D distances computed from the node coordinates on every query, using O(n) memory.
+pub struct EuclideanDistances {
+    coords: Vec<(f64, f64)>,
+    scale: f64,
+}
+
+impl EuclideanDistances {
+    /// Distances are scaled by `scale` before rounding to the nearest integer.
+    pub fn new(coords: Vec<(f64, f64)>, scale: f64) -> Self {
+        EuclideanDistances { coords, scale }
+    }
+}
+
+impl DistanceProvider for EuclideanDistances {
+    #[inline]
+    fn dist(&self, i: usize, j: usize) -> Cost {
+        let dx = self.coords[i].0 - self.coords[j].0;
+        let dy = self.coords[i].1 - self.coords[j].1;
+        ((dx * dx + dy * dy).sqrt() * self.scale + 0.5).floor() as Cost
+    }
+}
+
+/// Mean radius of the Earth in meters.
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Great-circle distances in meters between points given as latitude and longitude in degrees.
+pub struct HaversineDistances {
+    points: Vec<(f64, f64)>,
+    scale: f64,
+}
+
+impl HaversineDistances {
+    /// Distances are scaled by `scale` before rounding to the nearest integer.
+    pub fn new(points: Vec<(f64, f64)>, scale: f64) -> Self {
+        HaversineDistances { points, scale }
+    }
+}
+
+impl DistanceProvider for HaversineDistances {
+    fn dist(&self, i: usize, j: usize) -> Cost {
+        let (lat1, lon1) = self.points[i];
+        let (lat2, lon2) = self.points[j];
+        let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+        let half_dlat = (lat2 - lat1) / 2.0;
+        let half_dlon = (lon2 - lon1).to_radians() / 2.0;
+        let h = half_dlat.sin().powi(2) + lat1.cos() * lat2.cos() * half_dlon.sin().powi(2);
+        let meters = 2.0 * EARTH_RADIUS * h.sqrt().min(1.0).asin();
+        (meters * self.scale + 0.5).floor() as Cost
+    }
+}
+
+/// Distance provider of an instance.
+pub enum Distances {
+    Matrix(MatrixDistances),
+    Euclidean(EuclideanDistances),
+}
+
+impl DistanceProvider for Distances {
+    #[inline]
+    fn dist(&self, i: usize, j: usize) -> Cost {
+        match self {
+            Distances::Matrix(matrix) => matrix.dist(i, j),
+            Distances::Euclidean(euclidean) => euclidean.dist(i, j),
+        }
+    }
+}
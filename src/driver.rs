@@ -0,0 +1,51 @@
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use rayon::prelude::*;
+
+/// One worker state per thread of the current thread pool, for `batch_with`.
+pub fn workers<S: Default>() -> Vec<S> {
+    (0..rayon::current_num_threads())
+        .map(|_| S::default())
+        .collect()
+}
+
+/// Runs one batch of iterations on the current thread pool: every worker runs `iterate` once
+/// on its own state, and the results are returned with the index of the worker. Iterations
+/// that would start once the stop criteria are met are skipped, except that of the first
+/// worker, so a batch always has a result. Every iteration is counted in `termination` and
+/// `trace`.
+pub fn batch_with<S: Send, T: Send>(
+    workers: &mut [S],
+    termination: &Termination,
+    trace: &Trace,
+    iterate: impl Fn(usize, &mut S) -> T + Sync,
+) -> Vec<(usize, T)> {
+    let results: Vec<(usize, T)> = workers
+        .par_iter_mut()
+        .enumerate()
+        .filter_map(|(worker, state)| {
+            if worker > 0 && termination.should_stop() {
+                return None;
+            }
+            Some((worker, iterate(worker, state)))
+        })
+        .collect();
+    for &(worker, _) in &results {
+        trace.count_iteration(worker);
+        termination.count_iteration();
+    }
+    results
+}
+
+/// `batch_with` for iterations without state.
+pub fn batch<T: Send>(
+    termination: &Termination,
+    trace: &Trace,
+    iterate: impl Fn(usize) -> T + Sync,
+) -> Vec<(usize, T)> {
+    let mut workers = workers::<()>();
+    batch_with(&mut workers, termination, trace, |worker, _| {
+        iterate(worker)
+    })
+}
@@ -0,0 +1,143 @@
+use std::any::Any;
+use std::fmt;
+use std::panic;
+
+/// Exit codes of the command-line tool, listed in its help.
+pub const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0    success
+  2    invalid arguments
+  3    the instance (or a file it depends on) could not be read or parsed
+  4    the constraints are infeasible, or the best tour still violates some
+  5    the target distance was not reached
+  6    the distance matrix would exceed --max-memory
+  7    the verified tour is not a valid tour of the instance
+  8    an output file could not be written
+  101  internal error
+  130  interrupted (by the dashboard's q key, or by Ctrl-C)";
+
+/// Category of a fatal error, each with its own process exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    InvalidArguments,
+    Instance,
+    Infeasible,
+    TargetNotReached,
+    MemoryLimit,
+    InvalidTour,
+    Output,
+    Interrupted,
+    /// Any other failure; the exit code of a Rust panic.
+    Internal,
+}
+
+impl ErrorKind {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::InvalidArguments => 2,
+            ErrorKind::Instance => 3,
+            ErrorKind::Infeasible => 4,
+            ErrorKind::TargetNotReached => 5,
+            ErrorKind::MemoryLimit => 6,
+            ErrorKind::InvalidTour => 7,
+            ErrorKind::Output => 8,
+            ErrorKind::Interrupted => 130,
+            ErrorKind::Internal => 101,
+        }
+    }
+
+    /// Name of the kind in `--json-errors` output.
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorKind::InvalidArguments => "invalid_arguments",
+            ErrorKind::Instance => "instance",
+            ErrorKind::Infeasible => "infeasible",
+            ErrorKind::TargetNotReached => "target_not_reached",
+            ErrorKind::MemoryLimit => "memory_limit",
+            ErrorKind::InvalidTour => "invalid_tour",
+            ErrorKind::Output => "output",
+            ErrorKind::Interrupted => "interrupted",
+            ErrorKind::Internal => "internal",
+        }
+    }
+}
+
+/// Fatal error of a run, returned by the loaders and variants and reported by the
+/// command-line tool with the exit code of its kind.
+#[derive(Debug, Clone)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Error {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Output error of failing to write the file or directory `path`.
+    pub fn output(path: impl fmt::Display, error: impl fmt::Display) -> Self {
+        Error::new(
+            ErrorKind::Output,
+            format!("Failed to write {}: {}", path, error),
+        )
+    }
+
+    /// Internal error with the message of a panic payload.
+    pub fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Unknown error".to_string()
+        };
+        Error::new(ErrorKind::Internal, message)
+    }
+
+    /// The error as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"error\": \"{}\", \"exit_code\": {}, \"message\": \"{}\"}}",
+            self.kind.name(),
+            self.kind.exit_code(),
+            escape_json(&self.message)
+        )
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Reports panics, which are internal errors, as usual on stderr, or not at all with `json`,
+/// where the error is printed once the run is aborted.
+pub fn install_hook(json: bool) {
+    let default = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if !json {
+            default(info);
+        }
+    }));
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,402 @@
+use crate::error::{Error, ErrorKind};
+use crate::instance::{name_from_path, Cost, Instance};
+use crate::objective::Objective;
+use crate::optimum::{gap, known_optimum};
+use crate::solution::Solution;
+use crate::termination::Termination;
+use crate::time::Instant;
+use crate::trace::Trace;
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{info, info_span};
+
+/// Time-to-target experiment: executes `solve` `runs` times, each with the stop criteria built
+/// by `termination` and the target, and writes the sorted hitting times with their empirical
+/// cumulative probabilities `(i - 0.5) / n` to a CSV file. Runs that do not reach the target
+/// are reported as censored. Returns the number of runs that reached the target.
+pub fn time_to_target<T, F>(
+    runs: usize,
+    target: Cost,
+    termination: T,
+    output: &str,
+    solve: F,
+) -> Result<usize, Error>
+where
+    T: Fn() -> Termination,
+    F: Fn(&Termination, &Trace) -> Result<Solution, Error>,
+{
+    let mut hitting_times = Vec::with_capacity(runs);
+
+    for run in 1..=runs {
+        let _span = info_span!("ttt", run).entered();
+        info!("TTT run {} of {}", run, runs);
+        let termination = termination().with_target(Some(target));
+        let trace = Trace::new();
+        let solution = solve(&termination, &trace)?;
+
+        match trace.first_hit(target) {
+            Some(elapsed) => {
+                info!("Target {} reached in {:.3?}", target, elapsed);
+                hitting_times.push((run, elapsed));
+            }
+            None => info!(
+                "Target {} not reached (best distance = {})",
+                target, solution.total_distance
+            ),
+        }
+    }
+
+    hitting_times.sort_by_key(|&(_, elapsed)| elapsed);
+
+    let file = File::create(output).map_err(|error| Error::output(output, error))?;
+    let mut writer = BufWriter::new(file);
+
+    writeln!(writer, "rank,run,time_s,probability")
+        .map_err(|error| Error::output(output, error))?;
+    for (rank, (run, elapsed)) in hitting_times.iter().enumerate() {
+        let probability = (rank as f64 + 0.5) / runs as f64;
+        writeln!(
+            writer,
+            "{},{},{:.6},{:.6}",
+            rank + 1,
+            run,
+            elapsed.as_secs_f64(),
+            probability
+        )
+        .map_err(|error| Error::output(output, error))?;
+    }
+    writer
+        .flush()
+        .map_err(|error| Error::output(output, error))?;
+
+    println!(
+        "\nTarget reached in {} of {} runs. TTT data saved to {}",
+        hitting_times.len(),
+        runs,
+        output
+    );
+    Ok(hitting_times.len())
+}
+
+/// Result of one run of a multi-run experiment.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunResult {
+    pub run: usize,
+    pub distance: Cost,
+    pub time_to_best: Duration,
+    pub total_time: Duration,
+}
+
+/// Executes `solve` `runs` times, each with fresh randomness, the stop criteria built by
+/// `termination` and the trace built by `trace`, prints the best/mean/standard deviation of the
+/// distances and the mean time-to-best, and optionally writes the per-run results to `output`.
+/// Returns the best solution over all runs under `objective`, with the trace of its run.
+pub fn multi_run<T, R, F>(
+    runs: usize,
+    termination: T,
+    trace: R,
+    objective: Objective,
+    output: Option<&str>,
+    solve: F,
+) -> Result<(Solution, Trace), Error>
+where
+    T: Fn() -> Termination,
+    R: Fn() -> Trace,
+    F: Fn(&Termination, &Trace) -> Result<Solution, Error>,
+{
+    let mut results = Vec::with_capacity(runs);
+    let mut best: Option<(Solution, Trace)> = None;
+
+    for run in 1..=runs {
+        let _span = info_span!("run", run).entered();
+        info!("Run {} of {}", run, runs);
+        let termination = termination();
+        let trace = trace();
+        let start = Instant::now();
+        let solution = solve(&termination, &trace)?;
+        let total_time = start.elapsed();
+
+        results.push(RunResult {
+            run,
+            distance: solution.total_distance,
+            time_to_best: trace.time_to_best().unwrap_or(total_time),
+            total_time,
+        });
+        if best
+            .as_ref()
+            .is_none_or(|(best, _)| objective.better(&solution, best))
+        {
+            best = Some((solution, trace));
+        }
+    }
+
+    let distances: Vec<f64> = results.iter().map(|r| r.distance as f64).collect();
+    let mean = distances.iter().sum::<f64>() / runs as f64;
+    let variance = distances.iter().map(|d| (d - mean).powi(2)).sum::<f64>()
+        / (runs.saturating_sub(1)).max(1) as f64;
+    let mean_time_to_best = results
+        .iter()
+        .map(|r| r.time_to_best.as_secs_f64())
+        .sum::<f64>()
+        / runs as f64;
+    let best_distance = results
+        .iter()
+        .map(|r| r.distance)
+        .min()
+        .unwrap_or(Cost::MAX);
+
+    println!("\n=== Summary over {} runs ===", runs);
+    println!("Best distance: {}", best_distance);
+    println!("Mean distance: {:.2}", mean);
+    println!("Std. deviation: {:.2}", variance.sqrt());
+    println!("Mean time-to-best: {:.3}s", mean_time_to_best);
+
+    if let Some(output) = output {
+        write_run_results(output, &results)?;
+        println!("Per-run results saved to {}", output);
+    }
+
+    Ok(best.expect("There should be at least one run"))
+}
+
+/// Writes the per-run results as JSON if `output` ends in `.json`, and as CSV otherwise.
+fn write_run_results(output: &str, results: &[RunResult]) -> Result<(), Error> {
+    let file = File::create(output).map_err(|error| Error::output(output, error))?;
+    let mut writer = BufWriter::new(file);
+
+    if output.ends_with(".json") {
+        #[cfg(feature = "json")]
+        {
+            #[derive(serde::Serialize)]
+            struct Row {
+                run: usize,
+                distance: Cost,
+                time_to_best_s: f64,
+                total_time_s: f64,
+            }
+
+            let rows: Vec<Row> = results
+                .iter()
+                .map(|r| Row {
+                    run: r.run,
+                    distance: r.distance,
+                    time_to_best_s: r.time_to_best.as_secs_f64(),
+                    total_time_s: r.total_time.as_secs_f64(),
+                })
+                .collect();
+            serde_json::to_writer_pretty(&mut writer, &rows)
+                .map_err(|error| Error::output(output, error))?;
+        }
+        #[cfg(not(feature = "json"))]
+        return Err(json_unsupported());
+    } else {
+        writeln!(writer, "run,distance,time_to_best_s,total_time_s")
+            .map_err(|error| Error::output(output, error))?;
+        for r in results {
+            writeln!(
+                writer,
+                "{},{},{:.6},{:.6}",
+                r.run,
+                r.distance,
+                r.time_to_best.as_secs_f64(),
+                r.total_time.as_secs_f64()
+            )
+            .map_err(|error| Error::output(output, error))?;
+        }
+    }
+    writer.flush().map_err(|error| Error::output(output, error))
+}
+
+#[cfg(not(feature = "json"))]
+fn json_unsupported() -> Error {
+    Error::new(
+        ErrorKind::InvalidArguments,
+        "JSON results require the json feature",
+    )
+}
+
+/// Result of solving one instance of a batch.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InstanceResult {
+    pub instance: String,
+    pub num_cities: usize,
+    pub distance: String,
+    pub gap: Option<f64>,
+    pub time_to_best: Duration,
+    pub total_time: Duration,
+}
+
+/// Solves every file of `instance_files` once, `parallel` of them at a time, each with the stop
+/// criteria built by `termination`. The instances being solved at the same time split the
+/// threads of the current pool evenly. Prints one line per instance and optionally writes the
+/// results to `output`. The first instance that fails to load or solve stops the batch, and its
+/// error is returned.
+pub fn batch_solve<T, L, F>(
+    instance_files: &[String],
+    parallel: usize,
+    termination: T,
+    output: Option<&str>,
+    load: L,
+    solve: F,
+) -> Result<(), Error>
+where
+    T: Fn() -> Termination + Sync,
+    L: Fn(&str) -> Result<Instance, Error> + Sync,
+    F: Fn(&Instance, &Termination, &Trace) -> Result<Solution, Error> + Sync,
+{
+    if parallel == 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidArguments,
+            "The number of parallel instances must be at least 1",
+        ));
+    }
+    let threads = (rayon::current_num_threads() / parallel).max(1);
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(instance_files.len()));
+    let failure: Mutex<Option<Error>> = Mutex::new(None);
+
+    std::thread::scope(|scope| {
+        for _ in 0..parallel.min(instance_files.len()) {
+            scope.spawn(|| {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .expect("Failed to build the thread pool");
+                pool.install(|| loop {
+                    if failure.lock().unwrap().is_some() {
+                        break;
+                    }
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(instance_file) = instance_files.get(index) else {
+                        break;
+                    };
+                    let _span = info_span!("instance", file = instance_file.as_str()).entered();
+                    info!("Solving {}", instance_file);
+
+                    let termination = termination();
+                    let trace = Trace::new();
+                    let start = Instant::now();
+                    let solved = load(instance_file).and_then(|instance| {
+                        let solution = solve(&instance, &termination, &trace)?;
+                        Ok((instance, solution))
+                    });
+                    let total_time = start.elapsed();
+                    let (instance, solution) = match solved {
+                        Ok(solved) => solved,
+                        Err(error) => {
+                            failure.lock().unwrap().get_or_insert(error);
+                            break;
+                        }
+                    };
+
+                    let gap = instance
+                        .name
+                        .as_deref()
+                        .and_then(known_optimum)
+                        .filter(|_| !instance.open_tour)
+                        .map(|optimum| gap(solution.total_distance, optimum * instance.scale()));
+                    results.lock().unwrap().push((
+                        index,
+                        InstanceResult {
+                            instance: instance_file.clone(),
+                            num_cities: instance.num_cities,
+                            distance: instance.format_cost(solution.total_distance),
+                            gap,
+                            time_to_best: trace.time_to_best().unwrap_or(total_time),
+                            total_time,
+                        },
+                    ));
+                });
+            });
+        }
+    });
+
+    if let Some(error) = failure.into_inner().unwrap() {
+        return Err(error);
+    }
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|&(index, _)| index);
+    let results: Vec<InstanceResult> = results.into_iter().map(|(_, result)| result).collect();
+
+    println!("\n=== Summary over {} instances ===", results.len());
+    for r in &results {
+        let gap = r
+            .gap
+            .map_or(String::new(), |gap| format!(" (gap = {:.2}%)", gap));
+        println!(
+            "{}: {}{} in {:.3}s",
+            name_from_path(&r.instance),
+            r.distance,
+            gap,
+            r.total_time.as_secs_f64()
+        );
+    }
+
+    if let Some(output) = output {
+        write_instance_results(output, &results)?;
+        println!("Per-instance results saved to {}", output);
+    }
+    Ok(())
+}
+
+/// Writes the per-instance results as JSON if `output` ends in `.json`, and as CSV otherwise.
+fn write_instance_results(output: &str, results: &[InstanceResult]) -> Result<(), Error> {
+    let file = File::create(output).map_err(|error| Error::output(output, error))?;
+    let mut writer = BufWriter::new(file);
+
+    if output.ends_with(".json") {
+        #[cfg(feature = "json")]
+        {
+            #[derive(serde::Serialize)]
+            struct Row<'a> {
+                instance: &'a str,
+                cities: usize,
+                /// Formatted with the precision of the instance.
+                distance: Option<serde_json::Number>,
+                gap_percent: Option<f64>,
+                time_to_best_s: f64,
+                total_time_s: f64,
+            }
+
+            let rows: Vec<Row> = results
+                .iter()
+                .map(|r| Row {
+                    instance: &r.instance,
+                    cities: r.num_cities,
+                    distance: r.distance.parse().ok(),
+                    gap_percent: r.gap,
+                    time_to_best_s: r.time_to_best.as_secs_f64(),
+                    total_time_s: r.total_time.as_secs_f64(),
+                })
+                .collect();
+            serde_json::to_writer_pretty(&mut writer, &rows)
+                .map_err(|error| Error::output(output, error))?;
+        }
+        #[cfg(not(feature = "json"))]
+        return Err(json_unsupported());
+    } else {
+        writeln!(
+            writer,
+            "instance,cities,distance,gap_percent,time_to_best_s,total_time_s"
+        )
+        .map_err(|error| Error::output(output, error))?;
+        for r in results {
+            writeln!(
+                writer,
+                "{},{},{},{},{:.6},{:.6}",
+                r.instance,
+                r.num_cities,
+                r.distance,
+                r.gap.map_or(String::new(), |gap| format!("{:.4}", gap)),
+                r.time_to_best.as_secs_f64(),
+                r.total_time.as_secs_f64()
+            )
+            .map_err(|error| Error::output(output, error))?;
+        }
+    }
+    writer.flush().map_err(|error| Error::output(output, error))
+}
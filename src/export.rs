@@ -0,0 +1,96 @@
+use crate::error::Error;
+use crate::instance::Instance;
+use crate::solution::Solution;
+use crate::trace::Trace;
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// gnuplot script plotting the exported files.
+const GNUPLOT_SCRIPT: &str = "\
+set terminal pngcairo size 1200,400
+set output 'plot.png'
+set multiplot layout 1,3
+set title 'Best tour'
+set size ratio -1
+plot 'tour.dat' using 1:2 with linespoints pointtype 7 pointsize 0.5 notitle
+set size noratio
+set title 'Best distance over time'
+set xlabel 'time (s)'
+plot 'trace.dat' using 1:2 with steps notitle
+set title 'Elite pool'
+set xlabel 'rank'
+plot 'elite.dat' using 1:2 with boxes notitle
+unset multiplot
+";
+
+/// Writes whitespace-separated files for plotting the run into `dir`, each with a `#` header:
+/// `tour.dat` (coordinates of the best tour in order, back to the first city when the tour is
+/// closed; only for instances with coordinates), `trace.dat` (time and distance of each
+/// improvement), `elite.dat` (distances of the final elite pool, best first) and `plot.gp`, a
+/// gnuplot script plotting the three.
+pub fn export_plot_data(
+    dir: &str,
+    instance: &Instance,
+    solution: &Solution,
+    trace: &Trace,
+) -> Result<(), Error> {
+    std::fs::create_dir_all(dir).map_err(|error| Error::output(dir, error))?;
+    let dir = Path::new(dir);
+
+    if let Some(coords) = instance.coords() {
+        write_file(&dir.join("tour.dat"), |writer| {
+            writeln!(writer, "# x y city")?;
+            let closing = (!instance.open_tour)
+                .then(|| solution.path.first())
+                .flatten();
+            for &city in solution.path.iter().chain(closing) {
+                let (x, y) = coords[city];
+                writeln!(writer, "{} {} {}", x, y, city + 1)?;
+            }
+            Ok(())
+        })?;
+    }
+
+    write_file(&dir.join("trace.dat"), |writer| {
+        writeln!(writer, "# time_s distance")?;
+        for event in trace.events() {
+            writeln!(
+                writer,
+                "{:.6} {}",
+                event.elapsed.as_secs_f64(),
+                instance.format_cost(event.distance)
+            )?;
+        }
+        Ok(())
+    })?;
+
+    let mut elite = trace.snapshot().elite_distances;
+    elite.sort_unstable();
+    write_file(&dir.join("elite.dat"), |writer| {
+        writeln!(writer, "# rank distance")?;
+        for (rank, distance) in elite.iter().enumerate() {
+            writeln!(writer, "{} {}", rank + 1, instance.format_cost(*distance))?;
+        }
+        Ok(())
+    })?;
+
+    write_file(&dir.join("plot.gp"), |writer| {
+        writer.write_all(GNUPLOT_SCRIPT.as_bytes())
+    })
+}
+
+/// Creates the file `path` and fills it with `write`.
+fn write_file(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> Result<(), Error> {
+    File::create(path)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            write(&mut writer)?;
+            writer.flush()
+        })
+        .map_err(|error| Error::output(path.display(), error))
+}
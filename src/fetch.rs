@@ -0,0 +1,58 @@
+use crate::error::{Error, ErrorKind};
+use crate::instance::Instance;
+
+use std::io::Read;
+use std::path::Path;
+use tracing::info;
+
+/// Downloads the instance `name` into `dir` unless it is already there, and returns its path.
+/// `url` is the download URL with `{name}` standing for the instance name; the file is saved
+/// under the last segment of the resulting URL, and removed again if it does not parse.
+pub fn fetch_instance(name: &str, url: &str, dir: &str) -> Result<String, Error> {
+    let url = url.replace("{name}", name);
+    let file_name = url
+        .rsplit('/')
+        .next()
+        .filter(|file_name| !file_name.is_empty())
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidArguments,
+                format!("No file name in the instance URL {}", url),
+            )
+        })?;
+    let path = Path::new(dir).join(file_name);
+    let path = path.to_string_lossy().into_owned();
+    if Path::new(&path).exists() {
+        info!("Using the downloaded instance {}", path);
+        return Ok(path);
+    }
+
+    info!("Downloading {}", url);
+    let failed = |error: &dyn std::fmt::Display| {
+        Error::new(
+            ErrorKind::Instance,
+            format!("Failed to download {}: {}", url, error),
+        )
+    };
+    let response = ureq::get(&url).call().map_err(|error| failed(&error))?;
+    let mut content = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut content)
+        .map_err(|error| failed(&error))?;
+
+    std::fs::create_dir_all(dir).map_err(|error| Error::output(dir, error))?;
+    std::fs::write(&path, content).map_err(|error| Error::output(&path, error))?;
+    if let Err(error) = Instance::load(&path) {
+        let _ = std::fs::remove_file(&path);
+        return Err(Error::new(
+            ErrorKind::Instance,
+            format!(
+                "The file downloaded from {} is not a valid instance: {}",
+                url, error
+            ),
+        ));
+    }
+    info!("Saved {} to {}", url, path);
+    Ok(path)
+}
@@ -0,0 +1,44 @@
+use crate::error::Error;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Writes a random EUC_2D instance in TSPLIB format with `num_cities` cities placed uniformly
+/// in the square `[0, size) x [0, size)`. The same `seed` always produces the same instance.
+pub fn write_random_instance(
+    filename: &str,
+    num_cities: usize,
+    size: f64,
+    seed: Option<u64>,
+) -> Result<(), Error> {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let name = crate::instance::name_from_path(filename);
+
+    let mut write = || -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(filename)?);
+        writeln!(writer, "NAME: {}", name)?;
+        writeln!(writer, "TYPE: TSP")?;
+        writeln!(
+            writer,
+            "COMMENT: {} random cities in a {} x {} square",
+            num_cities, size, size
+        )?;
+        writeln!(writer, "DIMENSION: {}", num_cities)?;
+        writeln!(writer, "EDGE_WEIGHT_TYPE: EUC_2D")?;
+        writeln!(writer, "NODE_COORD_SECTION")?;
+        for city in 1..=num_cities {
+            let x: f64 = rng.gen_range(0.0..size);
+            let y: f64 = rng.gen_range(0.0..size);
+            writeln!(writer, "{} {:.2} {:.2}", city, x, y)?;
+        }
+        writeln!(writer, "EOF")?;
+        writer.flush()
+    };
+    write().map_err(|error| Error::output(filename, error))
+}
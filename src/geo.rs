@@ -0,0 +1,109 @@
+use crate::error::{Error, ErrorKind};
+
+/// Latitude and longitude in degrees of the points of a CSV file whose header names its `lat`
+/// (or `latitude`) and `lon` (or `lng`, `longitude`) columns, or `None` if it has no such
+/// header.
+pub fn read_lat_lon(content: &str) -> Result<Option<Vec<(f64, f64)>>, Error> {
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+    let Some(header) = lines.next() else {
+        return Ok(None);
+    };
+    let header: Vec<String> = header
+        .split(',')
+        .map(|field| field.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| header.iter().position(|field| names.contains(&&field[..]));
+    let (Some(lat), Some(lon)) = (
+        column(&["lat", "latitude"]),
+        column(&["lon", "lng", "longitude"]),
+    ) else {
+        return Ok(None);
+    };
+
+    lines
+        .map(|line| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let coordinate = |index: usize| {
+                fields
+                    .get(index)
+                    .and_then(|field| field.parse().ok())
+                    .ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::Instance,
+                            format!("Invalid coordinates in CSV line: {}", line),
+                        )
+                    })
+            };
+            Ok((coordinate(lat)?, coordinate(lon)?))
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// Builds an instance over `points` (latitude and longitude in degrees) whose distances are
+/// the road distances in meters returned by the OSRM table service at `url`, e.g.
+/// `http://localhost:5000/table/v1/driving`. `precision` decimal digits of the distances are
+/// kept.
+#[cfg(feature = "osrm")]
+pub fn osrm_instance(
+    points: &[(f64, f64)],
+    url: &str,
+    precision: u32,
+) -> Result<crate::instance::Instance, Error> {
+    use crate::distance::{Distances, MatrixDistances};
+    use crate::instance::{Cost, Instance};
+
+    #[derive(serde::Deserialize)]
+    struct TableResponse {
+        code: String,
+        distances: Option<Vec<Vec<Option<f64>>>>,
+    }
+
+    let coordinates: Vec<String> = points
+        .iter()
+        .map(|(lat, lon)| format!("{},{}", lon, lat))
+        .collect();
+    let request = format!(
+        "{}/{}?annotations=distance",
+        url.trim_end_matches('/'),
+        coordinates.join(";")
+    );
+    let failed = |message: String| Error::new(ErrorKind::Instance, message);
+    let response: TableResponse = ureq::get(&request)
+        .call()
+        .map_err(|error| failed(format!("OSRM table request failed: {}", error)))?
+        .into_json()
+        .map_err(|error| {
+            failed(format!(
+                "Failed to parse the OSRM table response: {}",
+                error
+            ))
+        })?;
+    let (Some(table), "Ok") = (response.distances, response.code.as_str()) else {
+        return Err(failed(format!(
+            "OSRM table request failed with code {}",
+            response.code
+        )));
+    };
+
+    let num_cities = points.len();
+    let scale = 10f64.powi(precision as i32);
+    let distances = table
+        .into_iter()
+        .flatten()
+        .map(|meters| {
+            let meters = meters.ok_or_else(|| {
+                failed("OSRM found no route between two of the points".to_string())
+            })?;
+            Ok((meters * scale).round() as Cost)
+        })
+        .collect::<Result<_, Error>>()?;
+    let distances = Distances::Matrix(MatrixDistances::new(num_cities, distances));
+    let coords = points.iter().map(|&(lat, lon)| (lon, lat)).collect();
+    Ok(Instance::with_distances(
+        num_cities,
+        distances,
+        Some(coords),
+        precision,
+    ))
+}
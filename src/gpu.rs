@@ -0,0 +1,271 @@
+use crate::instance::{Cost, Instance};
+use crate::local_search::LocalSearch;
+use crate::solution::Solution;
+
+use wgpu::util::DeviceExt;
+
+/// Threads per workgroup of the 2-opt kernel.
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Largest absolute distance the kernel accepts, so that a move delta (four distances) fits in
+/// an `i32`.
+const MAX_GPU_DISTANCE: Cost = 1 << 28;
+
+/// One invocation per position `i`: scans every `j` and writes the best delta of reversing
+/// `path[i..j]` together with its `j`.
+const TWO_OPT_SHADER: &str = r#"
+struct Params {
+    n: u32,
+}
+
+@group(0) @binding(0) var<storage, read> dist: array<i32>;
+@group(0) @binding(1) var<storage, read> path: array<u32>;
+@group(0) @binding(2) var<uniform> params: Params;
+@group(0) @binding(3) var<storage, read_write> best: array<vec2<i32>>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let n = params.n;
+    let i = id.x + 1u;
+    if (i + 1u >= n) {
+        return;
+    }
+
+    let a = path[i - 1u];
+    let b = path[i];
+    let removed_ab = dist[a * n + b];
+    var best_delta = 0;
+    var best_j = 0;
+    for (var j = i + 2u; j < n; j = j + 1u) {
+        let c = path[j - 1u];
+        let d = path[j];
+        let delta = dist[a * n + c] + dist[b * n + d] - removed_ab - dist[c * n + d];
+        if (delta < best_delta) {
+            best_delta = delta;
+            best_j = i32(j);
+        }
+    }
+    best[i] = vec2<i32>(best_delta, best_j);
+}
+"#;
+
+/// Best-improvement 2-opt whose move evaluation runs on the GPU: every pass evaluates all
+/// moves in parallel, and the best one is applied on the CPU. Uses the same moves as
+/// `local_search_2opt` and requires a precomputed distance matrix, which is uploaded once per
+/// call to `improve`.
+pub struct GpuTwoOpt {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl GpuTwoOpt {
+    pub fn new() -> Self {
+        pollster::block_on(Self::init())
+    }
+
+    async fn init() -> Self {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                ..Default::default()
+            })
+            .await
+            .expect("No GPU adapter available for GPU 2-opt");
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("grasp"),
+                    required_features: wgpu::Features::empty(),
+                    required_limits: adapter.limits(),
+                    memory_hints: wgpu::MemoryHints::Performance,
+                },
+                None,
+            )
+            .await
+            .expect("Failed to open the GPU device");
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("2-opt"),
+            source: wgpu::ShaderSource::Wgsl(TWO_OPT_SHADER.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("2-opt"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        GpuTwoOpt {
+            device,
+            queue,
+            pipeline,
+        }
+    }
+
+    /// Runs the kernel on `path` and returns the best move as `(delta, i, j)`.
+    fn best_move(
+        &self,
+        path: &[u32],
+        path_buffer: &wgpu::Buffer,
+        bind_group: &wgpu::BindGroup,
+        best_buffer: &wgpu::Buffer,
+        staging_buffer: &wgpu::Buffer,
+    ) -> (i32, usize, usize) {
+        self.queue
+            .write_buffer(path_buffer, 0, bytemuck::cast_slice(path));
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.dispatch_workgroups((path.len() as u32).div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(best_buffer, 0, staging_buffer, 0, best_buffer.size());
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = staging_buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| {
+            result.expect("Failed to read the 2-opt results from the GPU")
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+
+        let best = {
+            let data = slice.get_mapped_range();
+            let results: &[[i32; 2]] = bytemuck::cast_slice(&data);
+            results
+                .iter()
+                .enumerate()
+                .map(|(i, &[delta, j])| (delta, i, j as usize))
+                .min()
+                .unwrap()
+        };
+        staging_buffer.unmap();
+        best
+    }
+}
+
+impl Default for GpuTwoOpt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalSearch for GpuTwoOpt {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        self.improve_until(solution, instance, &|| false);
+    }
+
+    /// Checks `should_stop` before every pass over the moves.
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        let n = solution.path.len();
+        if n < 4 {
+            return false;
+        }
+        let matrix = instance
+            .matrix()
+            .expect("GPU 2-opt requires a precomputed distance matrix");
+        let distances: Vec<i32> = matrix
+            .as_slice()
+            .iter()
+            .map(|&d| {
+                if d.abs() > MAX_GPU_DISTANCE {
+                    panic!(
+                        "GPU 2-opt requires distances of at most {}",
+                        MAX_GPU_DISTANCE
+                    );
+                }
+                d as i32
+            })
+            .collect();
+
+        let storage = |label, contents: &[u8], usage| {
+            self.device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some(label),
+                    contents,
+                    usage,
+                })
+        };
+        let dist_buffer = storage(
+            "distances",
+            bytemuck::cast_slice(&distances),
+            wgpu::BufferUsages::STORAGE,
+        );
+        let path_buffer = storage(
+            "path",
+            &vec![0; n * 4],
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        );
+        let params_buffer = storage(
+            "params",
+            bytemuck::cast_slice(&[n as u32, 0, 0, 0]),
+            wgpu::BufferUsages::UNIFORM,
+        );
+        let best_buffer = storage(
+            "best",
+            &vec![0; n * 8],
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        );
+        let staging_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("staging"),
+            size: best_buffer.size(),
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                (0, &dist_buffer),
+                (1, &path_buffer),
+                (2, &params_buffer),
+                (3, &best_buffer),
+            ]
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding,
+                resource: buffer.as_entire_binding(),
+            }),
+        });
+
+        let mut path: Vec<u32> = solution.path.iter().map(|&city| city as u32).collect();
+        let mut truncated = false;
+        loop {
+            if should_stop() {
+                truncated = true;
+                break;
+            }
+            let (delta, i, j) = self.best_move(
+                &path,
+                &path_buffer,
+                &bind_group,
+                &best_buffer,
+                &staging_buffer,
+            );
+            if delta >= 0 {
+                break;
+            }
+            path[i..j].reverse();
+        }
+
+        solution.path = path.into_iter().map(|city| city as usize).collect();
+        solution.eval(instance);
+        truncated
+    }
+
+    fn name(&self) -> &str {
+        "GPU 2-opt"
+    }
+}
@@ -1,60 +1,144 @@
-use crate::instance::Instance;
-use crate::local_search::local_search_2opt;
-use crate::solution::Solution;
+use crate::best::BestCollector;
+use crate::construction::Constructive;
+use crate::driver;
+use crate::instance::{Cost, Instance};
+use crate::local_search::LocalSearch;
+use crate::objective::Objective;
+use crate::perturbation::Perturbation;
+use crate::solution::{PrLocalSearch, PrSelection, PrStop, Solution};
+use crate::termination::Termination;
+use crate::trace::Trace;
 
+use clap::ValueEnum;
+use rand::Rng;
 use rayon::prelude::*;
-use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
-use std::sync::Arc;
-use std::sync::Mutex;
-use std::time::{Duration, Instant};
-
-pub fn grasp(instance: &Instance, time_limit: Duration) -> Solution {
-    let best_score = Arc::new(AtomicI32::new(i32::MAX));
-    let stop_flag = Arc::new(AtomicBool::new(false));
-    let start_time = Instant::now();
-
-    let best_solution = (0..num_cpus::get())
-        .into_par_iter()
-        .map(|_| {
-            while !stop_flag.load(Ordering::Relaxed) {
-                if start_time.elapsed() >= time_limit {
-                    stop_flag.store(true, Ordering::Relaxed);
-                    break;
-                }
+use tracing::{debug, info, trace};
 
-                let mut solution = constructive_phase(instance);
-                local_search_2opt(&mut solution, instance);
-                solution.eval(instance);
+/// Runs one batch of independent GRASP iterations with `driver::batch`, one per thread. Each
+/// solution is returned with the index of the worker that built it. Iterations sampled by
+/// `trace` have the costs before and after local search recorded.
+pub(crate) fn grasp_batch(
+    instance: &Instance,
+    termination: &Termination,
+    construction: &dyn Constructive,
+    local_search: &dyn LocalSearch,
+    trace: &Trace,
+) -> Vec<(usize, Solution)> {
+    batch_from(instance, termination, local_search, trace, &|_| {
+        construction.construct(instance)
+    })
+}
 
-                let current_best_score = best_score.load(Ordering::Relaxed);
-                if solution.total_distance < current_best_score {
-                    best_score.store(solution.total_distance, Ordering::Relaxed);
-                    println!("Improved distance = {}", solution.total_distance);
-                }
+/// Like `grasp_batch`, but each iteration improves the solution returned by `start` for its
+/// worker.
+fn batch_from(
+    instance: &Instance,
+    termination: &Termination,
+    local_search: &dyn LocalSearch,
+    trace: &Trace,
+    start: &(dyn Fn(usize) -> Solution + Sync),
+) -> Vec<(usize, Solution)> {
+    driver::batch(termination, trace, |thread| {
+        let sampled = trace.sample_iteration();
+        let mut solution = start(thread);
+        let constructed = sampled.map(|_| {
+            solution.eval(instance);
+            solution.total_distance
+        });
+        let truncated =
+            local_search.improve_until(&mut solution, instance, &|| termination.should_stop());
+        if truncated {
+            trace.count_truncated();
+        }
+        solution.eval(instance);
+        debug_assert_eq!(solution.validate(instance), Ok(()));
+        if let (Some(iteration), Some(constructed)) = (sampled, constructed) {
+            trace.record_iteration(
+                iteration,
+                thread,
+                constructed,
+                solution.total_distance,
+                truncated,
+            );
+        }
+        solution
+    })
+}
+
+pub fn grasp(
+    instance: &Instance,
+    termination: &Termination,
+    construction: &dyn Constructive,
+    local_search: &dyn LocalSearch,
+    trace: &Trace,
+) -> Solution {
+    let mut best_solution: Option<Solution> = None;
+
+    let mut iteration = 0;
+    while !termination.should_stop() {
+        for (thread, solution) in
+            grasp_batch(instance, termination, construction, local_search, trace)
+        {
+            iteration += 1;
+            trace!(
+                iteration,
+                thread,
+                distance = solution.total_distance,
+                "Iteration done"
+            );
+
+            if best_solution
+                .as_ref()
+                .is_none_or(|best| instance.objective.better(&solution, best))
+            {
+                termination.report(instance.objective.value(&solution));
+                trace.record_improvement(iteration, thread, &solution, instance);
+                best_solution = Some(solution);
             }
-            best_score.load(Ordering::Relaxed)
-        })
-        .reduce_with(|best, next| if next < best { next } else { best });
+        }
+        construction.learn(best_solution.as_slice());
+    }
 
-    let mut final_solution = constructive_phase(instance);
-    final_solution.total_distance =
-        best_solution.expect("GRASP should return at least one solution");
-    final_solution
+    best_solution.expect("GRASP should return at least one solution")
 }
 
+/// Number of edges of `sol1` that are not in `sol2`, invariant to rotation and direction.
 pub fn symmetric_difference(sol1: &Solution, sol2: &Solution) -> usize {
-    sol1.path
-        .iter()
-        .zip(sol2.path.iter())
-        .filter(|&(a, b)| a != b)
+    let n = sol2.path.len();
+    let mut neighbors = vec![(usize::MAX, usize::MAX); n];
+    for i in 0..n {
+        let city = sol2.path[i];
+        neighbors[city] = (sol2.path[(i + n - 1) % n], sol2.path[(i + 1) % n]);
+    }
+
+    (0..sol1.path.len())
+        .filter(|&i| {
+            let from = sol1.path[i];
+            let to = sol1.path[(i + 1) % sol1.path.len()];
+            neighbors[from].0 != to && neighbors[from].1 != to
+        })
         .count()
 }
 
+/// Policy used to pick which elite solution is replaced once the elite set is full.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReplacementPolicy {
+    /// Replace the worst elite solution.
+    Worst,
+    /// Replace the most similar elite solution among those worse than the candidate.
+    MostSimilarWorse,
+    /// Replace the solution with the worst combined quality and diversity score.
+    QualityDiversity,
+}
+
 pub fn update_elite_set(
     elite_set: &mut Vec<Solution>,
     solution: Solution,
     max_elite_size: usize,
     min_difference: usize,
+    policy: ReplacementPolicy,
+    objective: Objective,
 ) {
     if elite_set.is_empty() {
         elite_set.push(solution);
@@ -63,119 +147,591 @@ pub fn update_elite_set(
 
     let is_different = elite_set
         .iter()
-        .all(|s| symmetric_difference(&s, &solution) >= min_difference);
+        .all(|s| symmetric_difference(s, &solution) >= min_difference);
 
-    if is_different {
-        if elite_set.len() < max_elite_size {
-            elite_set.push(solution);
-        } else {
-            let worst_index = elite_set
-                .iter()
-                .enumerate()
-                .max_by_key(|&(_, sol)| sol.total_distance)
-                .map(|(i, _)| i)
-                .unwrap();
-            if solution.total_distance < elite_set[worst_index].total_distance {
-                elite_set[worst_index] = solution;
-            }
+    if !is_different {
+        return;
+    }
+
+    if elite_set.len() < max_elite_size {
+        elite_set.push(solution);
+        return;
+    }
+
+    let replace_index = match policy {
+        ReplacementPolicy::Worst => elite_set
+            .iter()
+            .enumerate()
+            .max_by_key(|&(_, sol)| objective.key(sol))
+            .map(|(i, _)| i)
+            .filter(|&i| objective.better(&solution, &elite_set[i])),
+        ReplacementPolicy::MostSimilarWorse => elite_set
+            .iter()
+            .enumerate()
+            .filter(|&(_, sol)| objective.better(&solution, sol))
+            .min_by_key(|&(_, sol)| symmetric_difference(sol, &solution))
+            .map(|(i, _)| i),
+        ReplacementPolicy::QualityDiversity => {
+            let best = elite_set.iter().map(|s| objective.value(s)).min().unwrap();
+            let worst = elite_set.iter().map(|s| objective.value(s)).max().unwrap();
+            let n = solution.path.len().max(1) as f64;
+
+            let score = |i: usize| {
+                let quality =
+                    (objective.value(&elite_set[i]) - best) as f64 / (worst - best + 1) as f64;
+                let diversity = elite_set
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, s)| symmetric_difference(s, &elite_set[i]))
+                    .chain(std::iter::once(symmetric_difference(
+                        &solution,
+                        &elite_set[i],
+                    )))
+                    .min()
+                    .unwrap() as f64
+                    / n;
+                quality - diversity
+            };
+
+            (0..elite_set.len())
+                .filter(|&i| objective.better(&solution, &elite_set[i]))
+                .max_by(|&a, &b| score(a).total_cmp(&score(b)))
         }
+    };
+
+    if let Some(i) = replace_index {
+        elite_set[i] = solution;
     }
 }
 
-pub fn grasp_static_pr(instance: &Instance, time_limit: Duration, elite_size: usize) -> Solution {
-    let min_difference = (instance.num_cities as f64 * 0.1).round() as usize;
+/// Parameters of the StaticPR variant.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StaticPrConfig {
+    pub elite_size: usize,
+    pub min_diversity: f64,
+    pub replacement_policy: ReplacementPolicy,
+    /// Fraction, in `(0, 1]`, of the way to the guiding solution that path relinking walks.
+    pub pr_depth: f64,
+    pub pr_local_search: PrLocalSearch,
+    pub pr_stop: PrStop,
+    pub pr_selection: PrSelection,
+}
 
-    let elite_set = Arc::new(Mutex::new(Vec::with_capacity(elite_size)));
-    let best_score = Arc::new(AtomicI32::new(i32::MAX));
-    let best_solution = Arc::new(Mutex::new(None));
-    let stop_flag = Arc::new(AtomicBool::new(false));
-    let start_time = Instant::now();
+/// Share of the time and iterations of StaticPR spent building the elite set, the rest going to
+/// path relinking.
+const ELITE_SHARE: f64 = 0.8;
 
-    let _ = (0..num_cpus::get())
-        .into_par_iter()
-        .map(|_| {
-            while !stop_flag.load(Ordering::Relaxed) {
-                if start_time.elapsed() >= time_limit {
-                    stop_flag.store(true, Ordering::Relaxed);
-                    break;
-                }
+/// GRASP building an elite set during `ELITE_SHARE` of the run, followed by path relinking
+/// between every pair of elite solutions for the rest of it, each walk going `pr_depth` of the
+/// way and counting as an iteration. `elite` seeds the elite set (e.g. with the elite set of a
+/// previous run) and receives its final state.
+pub fn grasp_static_pr(
+    instance: &Instance,
+    termination: &Termination,
+    construction: &dyn Constructive,
+    local_search: &dyn LocalSearch,
+    config: &StaticPrConfig,
+    elite: &mut Vec<Solution>,
+    trace: &Trace,
+) -> Solution {
+    let StaticPrConfig {
+        elite_size,
+        min_diversity,
+        replacement_policy,
+        pr_depth,
+        pr_local_search,
+        pr_stop,
+        pr_selection,
+    } = *config;
+    let min_difference = (instance.num_cities as f64 * min_diversity).round() as usize;
 
-                let mut solution = constructive_phase(instance);
-                local_search_2opt(&mut solution, instance);
-                solution.eval(instance);
+    let mut elite_set = Vec::with_capacity(elite_size);
+    for solution in std::mem::take(elite) {
+        update_elite_set(
+            &mut elite_set,
+            solution,
+            elite_size,
+            min_difference,
+            replacement_policy,
+            instance.objective,
+        );
+    }
+    let mut best = elite_set
+        .iter()
+        .min_by_key(|solution| instance.objective.key(solution))
+        .map(Solution::copy);
+    if let Some(best) = &best {
+        info!(
+            "Starting from {} elite solutions, best distance = {}",
+            elite_set.len(),
+            instance.format_cost(best.total_distance)
+        );
+        termination.report(instance.objective.value(best));
+        trace.publish_best(best);
+    }
 
-                let current_best_score = best_score.load(Ordering::Relaxed);
-                if solution.total_distance < current_best_score {
-                    best_score.store(solution.total_distance, Ordering::Relaxed);
-                    *best_solution.lock().unwrap() = Some(solution.copy());
-                    println!("Improved distance = {}", solution.total_distance);
-                }
+    // The target and interruptions still stop the run through `termination`.
+    let building = termination.share(ELITE_SHARE);
+    let mut iteration = 0;
+    while !building.should_stop() && !termination.should_stop() {
+        for (thread, solution) in
+            grasp_batch(instance, &building, construction, local_search, trace)
+        {
+            iteration += 1;
+            trace!(
+                iteration,
+                thread,
+                distance = solution.total_distance,
+                "Iteration done"
+            );
 
-                let mut elite_set = elite_set.lock().unwrap();
-                update_elite_set(&mut elite_set, solution.copy(), elite_size, min_difference);
+            if best
+                .as_ref()
+                .is_none_or(|best| instance.objective.better(&solution, best))
+            {
+                best = Some(solution.copy());
+                termination.report(instance.objective.value(&solution));
+                trace.record_improvement(iteration, thread, &solution, instance);
             }
-            best_score.load(Ordering::Relaxed)
-        })
-        .reduce_with(|best, _| best);
 
-    {
-        let elite_set = elite_set.lock().unwrap();
-        elite_set.par_iter().enumerate().for_each(|(i, _)| {
-            if stop_flag.load(Ordering::Relaxed) {
+            update_elite_set(
+                &mut elite_set,
+                solution,
+                elite_size,
+                min_difference,
+                replacement_policy,
+                instance.objective,
+            );
+        }
+        trace.publish_elite(&elite_set);
+        construction.learn(&elite_set);
+    }
+    termination.absorb(&building);
+
+    let best_solution = BestCollector::new(instance.objective);
+    if let Some(best) = best {
+        best_solution.improve(&best);
+    }
+
+    elite_set.par_iter().enumerate().for_each(|(i, _)| {
+        if termination.should_stop() {
+            return;
+        }
+
+        for j in i + 1..elite_set.len() {
+            let mut s = elite_set[i].copy();
+            s.path_relinking(
+                &elite_set[j],
+                instance,
+                local_search,
+                pr_depth,
+                pr_local_search,
+                pr_stop,
+                pr_selection,
+            );
+
+            if local_search.improve_until(&mut s, instance, &|| termination.should_stop()) {
+                trace.count_truncated();
+            }
+            s.eval(instance);
+            debug_assert_eq!(s.validate(instance), Ok(()));
+            termination.count_iteration();
+
+            if best_solution.improve(&s) {
+                termination.report(instance.objective.value(&s));
+                trace.record_improvement(
+                    i * elite_set.len() + j,
+                    rayon::current_thread_index().unwrap_or(0),
+                    &s,
+                    instance,
+                );
+            }
+
+            if termination.should_stop() {
                 return;
             }
+        }
+    });
 
-            for j in i + 1..elite_set.len() {
-                let mut s = elite_set[i].copy();
-                s.path_relinking(&elite_set[j], instance);
+    let best = best_solution
+        .finish()
+        .expect("There should be at least one solution");
+    update_elite_set(
+        &mut elite_set,
+        best.copy(),
+        elite_size,
+        min_difference,
+        replacement_policy,
+        instance.objective,
+    );
+    *elite = elite_set;
+    best
+}
+
+/// Parameters of the Cooperative variant.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CooperativeConfig {
+    pub elite_size: usize,
+    pub min_diversity: f64,
+    pub replacement_policy: ReplacementPolicy,
+    /// Every this many batches, the threads restart from the elite set.
+    pub restart_interval: usize,
+    /// Perturbation applied to the elite solutions the threads restart from.
+    pub perturbation: Perturbation,
+    pub perturbation_strength: usize,
+    /// A thread whose own best solution has not improved for this many iterations restarts
+    /// from the elite set at its next iteration, regardless of `restart_interval`.
+    pub stagnation_threshold: Option<usize>,
+}
 
-                local_search_2opt(&mut s, instance);
+/// Cooperative GRASP: the threads share an elite set, and every `restart_interval` batches
+/// each of them restarts its search from a perturbation of a random elite solution instead of a
+/// fresh construction. With a stagnation threshold, threads that stop improving also restart
+/// early.
+pub fn grasp_cooperative(
+    instance: &Instance,
+    termination: &Termination,
+    construction: &dyn Constructive,
+    local_search: &dyn LocalSearch,
+    config: &CooperativeConfig,
+    trace: &Trace,
+) -> Solution {
+    let min_difference = (instance.num_cities as f64 * config.min_diversity).round() as usize;
+    let restart_interval = config.restart_interval.max(1);
 
-                let current_best_score = best_score.load(Ordering::Relaxed);
-                if s.total_distance < current_best_score {
-                    best_score.store(s.total_distance, Ordering::Relaxed);
-                    *best_solution.lock().unwrap() = Some(s.copy());
-                    println!("Improved distance = {}", s.total_distance);
+    let mut elite_set: Vec<Solution> = Vec::with_capacity(config.elite_size);
+    let mut best: Option<Solution> = None;
+
+    // Best value of each thread under the objective and number of its iterations since that
+    // best improved.
+    let threads = rayon::current_num_threads();
+    let mut thread_best = vec![(Cost::MAX, Cost::MAX); threads];
+    let mut stagnation = vec![0; threads];
+
+    let mut iteration = 0;
+    let mut batch_index = 0;
+    while !termination.should_stop() {
+        batch_index += 1;
+        let restart_all = batch_index % restart_interval == 0;
+        let stagnating: Vec<bool> = stagnation
+            .iter()
+            .map(|&count| config.stagnation_threshold.is_some_and(|n| count >= n))
+            .collect();
+
+        let batch = if (restart_all || stagnating.contains(&true)) && !elite_set.is_empty() {
+            if !restart_all {
+                debug!(
+                    "{} stagnating threads restart from the elite set",
+                    stagnating.iter().filter(|&&stagnant| stagnant).count()
+                );
+            }
+            for (count, _) in stagnation.iter_mut().zip(&stagnating).filter(|(_, &s)| s) {
+                *count = 0;
+            }
+            batch_from(instance, termination, local_search, trace, &|thread| {
+                if !restart_all && !stagnating.get(thread).copied().unwrap_or(false) {
+                    return construction.construct(instance);
                 }
+                let mut rng = crate::rng::thread_rng();
+                let mut solution = elite_set[rng.gen_range(0..elite_set.len())].copy();
+                config.perturbation.apply(
+                    &mut solution,
+                    instance.movable_end(),
+                    config.perturbation_strength,
+                    &mut rng,
+                );
+                solution.fix_endpoints(instance);
+                solution
+            })
+        } else {
+            grasp_batch(instance, termination, construction, local_search, trace)
+        };
 
-                if start_time.elapsed() >= time_limit {
-                    stop_flag.store(true, Ordering::Relaxed);
-                    return;
+        for (thread, solution) in batch {
+            iteration += 1;
+            if let Some(own_best) = thread_best.get_mut(thread) {
+                let key = instance.objective.key(&solution);
+                if key < *own_best {
+                    *own_best = key;
+                    stagnation[thread] = 0;
+                } else {
+                    stagnation[thread] += 1;
                 }
             }
-        });
+            trace!(
+                iteration,
+                thread,
+                distance = solution.total_distance,
+                "Iteration done"
+            );
+
+            if best
+                .as_ref()
+                .is_none_or(|best| instance.objective.better(&solution, best))
+            {
+                best = Some(solution.copy());
+                termination.report(instance.objective.value(&solution));
+                trace.record_improvement(iteration, thread, &solution, instance);
+            }
+
+            update_elite_set(
+                &mut elite_set,
+                solution,
+                config.elite_size,
+                min_difference,
+                config.replacement_policy,
+                instance.objective,
+            );
+        }
+        trace.publish_elite(&elite_set);
+        construction.learn(&elite_set);
     }
 
-    let final_solution = best_solution
-        .lock()
-        .unwrap()
-        .take()
-        .expect("There should be at least one solution");
-    final_solution
+    best.expect("GRASP should return at least one solution")
+}
+
+/// Restricted candidate list (RCL) of the randomized nearest neighbor construction: which of
+/// the cities not yet visited the next one is drawn from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rcl {
+    /// The `k` closest cities.
+    Cardinality(usize),
+    /// The closest fraction of the cities, rounded up.
+    Fraction(f64),
+    /// The cities whose distance is at most `min + alpha * (max - min)`, so 0 is greedy and 1
+    /// uniformly random.
+    Value(f64),
+    /// `Value` with an alpha drawn uniformly from `[0, max]` for each construction.
+    RandomValue(f64),
+}
+
+impl Default for Rcl {
+    fn default() -> Self {
+        Rcl::Fraction(1.0 / 3.0)
+    }
+}
+
+impl Rcl {
+    /// Size of a cardinality-based list over `count` candidates, or `None` if the list is
+    /// value-based.
+    pub(crate) fn cardinality(&self, count: usize) -> Option<usize> {
+        let k = match *self {
+            Rcl::Cardinality(k) => k,
+            Rcl::Fraction(fraction) => (count as f64 * fraction).ceil() as usize,
+            Rcl::Value(_) | Rcl::RandomValue(_) => return None,
+        };
+        Some(k.clamp(1, count.max(1)))
+    }
 }
 
 /// Constructive phase of GRASP
 pub fn constructive_phase(instance: &Instance) -> Solution {
+    biased_constructive_phase(instance, Rcl::default(), None)
+}
+
+/// Index in `0..count` drawn with probability proportional to `weight`.
+fn weighted_index(count: usize, weight: impl Fn(usize) -> f64) -> usize {
+    let total: f64 = (0..count).map(&weight).sum();
+    let mut target = crate::rng::random::<f64>() * total;
+    for index in 0..count {
+        target -= weight(index);
+        if target < 0.0 {
+            return index;
+        }
+    }
+    count - 1
+}
+
+/// Constructive phase of GRASP drawing each city from the restricted candidate list `rcl`.
+/// Given `weight`, the next city is drawn with probability proportional to
+/// `weight(last_city, city)` instead of uniformly.
+pub fn biased_constructive_phase(
+    instance: &Instance,
+    rcl: Rcl,
+    weight: Option<&dyn Fn(usize, usize) -> f64>,
+) -> Solution {
+    let rcl = match rcl {
+        Rcl::RandomValue(max) => Rcl::Value(crate::rng::random::<f64>() * max),
+        rcl => rcl,
+    };
+
     let mut solution = Solution::new(instance.num_cities);
     let mut remaining: Vec<usize> = (0..instance.num_cities).collect();
 
-    let start_city = remaining.remove(rand::random::<usize>() % remaining.len());
+    let start_city = match instance.start_city {
+        Some(city) => city,
+        None => crate::rng::random::<usize>() % remaining.len(),
+    };
+    remaining.retain(|&x| x != start_city);
     solution.path.push(start_city);
 
+    // Value-based lists need the distances to all the remaining cities.
+    if let (Some(tree), Some(_)) = (instance.kd_tree(), rcl.cardinality(1)) {
+        let mut state = tree.full_state();
+        tree.remove(&mut state, start_city);
+        for remaining in (1..instance.num_cities).rev() {
+            let last_city = *solution.path.last().unwrap();
+            let k = rcl.cardinality(remaining).unwrap();
+            let candidates = tree.k_nearest(last_city, k, &state);
+            let next_city = match weight {
+                Some(weight) => {
+                    candidates
+                        [weighted_index(candidates.len(), |i| weight(last_city, candidates[i]))]
+                }
+                None => candidates[crate::rng::random::<usize>() % candidates.len()],
+            };
+
+            tree.remove(&mut state, next_city);
+            solution.path.push(next_city);
+        }
+        solution.fix_endpoints(instance);
+        return solution;
+    }
+
+    // Candidates are (index in `remaining`, distance) pairs; the buffer is reused across steps.
+    let mut candidates: Vec<(usize, Cost)> = Vec::with_capacity(remaining.len());
     while !remaining.is_empty() {
         let last_city = *solution.path.last().unwrap();
-        let mut candidates: Vec<(usize, i32)> = remaining
-            .iter()
-            .map(|&city| (city, instance.distances[last_city][city]))
-            .collect();
-        candidates.sort_by_key(|&(_, dist)| dist);
+        candidates.clear();
+        match instance.row(last_city) {
+            Some(row) => candidates.extend(
+                remaining
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &city)| (index, row[city])),
+            ),
+            None => candidates.extend(
+                remaining
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &city)| (index, instance.dist(last_city, city))),
+            ),
+        }
+        if let Some(constraints) = &instance.constraints {
+            if let Some(&forced) = candidates
+                .iter()
+                .find(|&&(index, _)| constraints.is_forced(last_city, remaining[index]))
+            {
+                candidates.clear();
+                candidates.push(forced);
+            } else if candidates
+                .iter()
+                .any(|&(index, _)| !constraints.is_forbidden(last_city, remaining[index]))
+            {
+                candidates
+                    .retain(|&(index, _)| !constraints.is_forbidden(last_city, remaining[index]));
+            }
+        }
 
-        let k = (candidates.len() as f32 / 3.0).ceil() as usize;
-        let next_city = candidates[rand::random::<usize>() % k].0;
+        // Only the first k candidates matter, in no particular order.
+        let k = match rcl.cardinality(candidates.len()) {
+            Some(k) => {
+                if k < candidates.len() {
+                    candidates.select_nth_unstable_by_key(k - 1, |&(_, dist)| dist);
+                }
+                k
+            }
+            None => {
+                let Rcl::Value(alpha) = rcl else {
+                    unreachable!("Random alphas are drawn before the construction")
+                };
+                let min = candidates.iter().map(|&(_, dist)| dist).min().unwrap();
+                let max = candidates.iter().map(|&(_, dist)| dist).max().unwrap();
+                let threshold = min + (alpha * (max - min) as f64) as Cost;
+                let mut k = 0;
+                for i in 0..candidates.len() {
+                    if candidates[i].1 <= threshold {
+                        candidates.swap(i, k);
+                        k += 1;
+                    }
+                }
+                k
+            }
+        };
+        let (index, _) = match weight {
+            Some(weight) => {
+                candidates[weighted_index(k, |i| weight(last_city, remaining[candidates[i].0]))]
+            }
+            None => candidates[crate::rng::random::<usize>() % k],
+        };
 
-        remaining.retain(|&x| x != next_city);
-        solution.path.push(next_city);
+        solution.path.push(remaining.swap_remove(index));
     }
 
+    solution.fix_endpoints(instance);
     solution
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::construction::NearestNeighbor;
+    use crate::local_search::TwoOpt;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// 2-opt counting the calls of `improve`, which only path relinking makes: the GRASP
+    /// iterations go through `improve_until`.
+    #[derive(Default)]
+    struct CountingTwoOpt {
+        calls: AtomicUsize,
+    }
+
+    impl LocalSearch for CountingTwoOpt {
+        fn improve(&self, solution: &mut Solution, instance: &Instance) {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            TwoOpt.improve(solution, instance);
+        }
+
+        fn improve_until(
+            &self,
+            solution: &mut Solution,
+            instance: &Instance,
+            should_stop: &dyn Fn() -> bool,
+        ) -> bool {
+            TwoOpt.improve_until(solution, instance, should_stop)
+        }
+
+        fn name(&self) -> &str {
+            "counting 2-opt"
+        }
+    }
+
+    #[test]
+    fn static_pr_relinks_the_elite_set() {
+        let coords = (0..30)
+            .map(|i| ((i * 37 % 101) as f64, (i * 59 % 103) as f64))
+            .collect();
+        let instance = Instance::from_coords(coords, 0);
+        let termination = Termination::new(Duration::from_secs(60)).with_max_iterations(Some(50));
+        let local_search = CountingTwoOpt::default();
+        let config = StaticPrConfig {
+            elite_size: 5,
+            min_diversity: 0.0,
+            replacement_policy: ReplacementPolicy::Worst,
+            pr_depth: 1.0,
+            pr_local_search: PrLocalSearch::BestOnly,
+            pr_stop: PrStop::FullWalk,
+            pr_selection: PrSelection::Sequential,
+        };
+        let mut elite = Vec::new();
+
+        let best = grasp_static_pr(
+            &instance,
+            &termination,
+            &NearestNeighbor::default(),
+            &local_search,
+            &config,
+            &mut elite,
+            &Trace::new(),
+        );
+
+        assert!(elite.len() >= 2);
+        assert!(local_search.calls.load(Ordering::Relaxed) > 0);
+        assert_eq!(best.validate(&instance), Ok(()));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::driver;
+use crate::grasp::constructive_phase;
+use crate::instance::Instance;
+use crate::local_search::{local_search_2opt, local_search_3opt};
+use crate::perturbation::Perturbation;
+use crate::solution::Solution;
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+/// Iterated Local Search: each worker starts from a GRASP iteration and alternates
+/// `perturbation`, applied `strength` times, with 2-opt and 3-opt local search.
+pub fn ils(
+    instance: &Instance,
+    termination: &Termination,
+    perturbation: Perturbation,
+    strength: usize,
+    trace: &Trace,
+) -> Solution {
+    let should_stop = || termination.should_stop();
+    let mut workers: Vec<Option<Solution>> = driver::workers();
+    let mut best: Option<Solution> = None;
+
+    let mut iteration = 0;
+    while !termination.should_stop() {
+        // Each iteration returns the worker's current tour when it changed: the initial tour,
+        // then every accepted candidate, so none is lost if the time runs out meanwhile.
+        let batch = driver::batch_with(&mut workers, termination, trace, |_, current| {
+            let Some(current) = current else {
+                let mut initial = constructive_phase(instance);
+                local_search_2opt(&mut initial, instance, &should_stop);
+                initial.eval(instance);
+                *current = Some(initial.copy());
+                return Some(initial);
+            };
+
+            let mut candidate = current.copy();
+            perturbation.apply(
+                &mut candidate,
+                instance.movable_end(),
+                strength,
+                &mut crate::rng::thread_rng(),
+            );
+            candidate.eval(instance);
+            local_search_2opt(&mut candidate, instance, &should_stop);
+            local_search_3opt(&mut candidate, instance, &should_stop);
+
+            if candidate.total_distance <= current.total_distance {
+                *current = candidate.copy();
+                return Some(candidate);
+            }
+            None
+        });
+
+        for (thread, current) in batch {
+            iteration += 1;
+            let Some(current) = current else {
+                continue;
+            };
+            if best
+                .as_ref()
+                .is_none_or(|best| current.total_distance < best.total_distance)
+            {
+                termination.report(current.total_distance);
+                trace.record_improvement(iteration, thread, &current, instance);
+                best = Some(current);
+            }
+        }
+    }
+
+    best.expect("There should be at least one solution")
+}
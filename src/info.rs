@@ -0,0 +1,181 @@
+use crate::error::Error;
+use crate::instance::{Cost, Instance};
+use crate::tsplib::{self, Tsplib};
+
+use rand::Rng;
+use rayon::prelude::*;
+
+/// Instances up to this size have every triple checked for triangle-inequality violations;
+/// larger ones are estimated from `TRIANGLE_SAMPLES` random triples.
+const TRIANGLE_EXACT_MAX_CITIES: usize = 500;
+const TRIANGLE_SAMPLES: usize = 1_000_000;
+
+/// Metadata of an instance file and statistics of its distances, in the instance's units.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InstanceSummary {
+    pub name: String,
+    pub dimension: usize,
+    pub edge_weight_type: Option<String>,
+    pub edge_weight_format: Option<String>,
+    /// Pairs of cities whose distance differs between the two directions.
+    pub asymmetric_pairs: usize,
+    pub min_distance: f64,
+    pub max_distance: f64,
+    pub mean_distance: f64,
+    pub triangle_violations: usize,
+    /// Number of triples sampled for `triangle_violations`, or `None` if all were checked.
+    pub triangle_samples: Option<usize>,
+    /// `((min_x, min_y), (max_x, max_y))` of the node coordinates.
+    pub bounding_box: Option<((f64, f64), (f64, f64))>,
+}
+
+/// Whether `dist(i, k) > dist(i, j) + dist(j, k)`.
+fn violates_triangle(instance: &Instance, i: usize, j: usize, k: usize) -> bool {
+    instance.dist(i, k) > instance.dist(i, j) + instance.dist(j, k)
+}
+
+/// Counts the triangle-inequality violations over every triple, or over a random sample of
+/// them for large instances. Returns the count and the sample size.
+fn triangle_violations(instance: &Instance) -> (usize, Option<usize>) {
+    let n = instance.num_cities;
+    if n <= TRIANGLE_EXACT_MAX_CITIES {
+        let violations = (0..n)
+            .into_par_iter()
+            .map(|i| {
+                let mut count = 0;
+                for j in (0..n).filter(|&j| j != i) {
+                    for k in (0..n).filter(|&k| k != i && k != j) {
+                        if violates_triangle(instance, i, j, k) {
+                            count += 1;
+                        }
+                    }
+                }
+                count
+            })
+            .sum();
+        (violations, None)
+    } else {
+        let mut rng = crate::rng::thread_rng();
+        let violations = (0..TRIANGLE_SAMPLES)
+            .filter(|_| {
+                let i = rng.gen_range(0..n);
+                let j = rng.gen_range(0..n);
+                let k = rng.gen_range(0..n);
+                i != j && j != k && i != k && violates_triangle(instance, i, j, k)
+            })
+            .count();
+        (violations, Some(TRIANGLE_SAMPLES))
+    }
+}
+
+/// Loads `filename` and computes its summary.
+pub fn summarize(filename: &str, precision: u32) -> Result<InstanceSummary, Error> {
+    let content = tsplib::read_file(filename)?;
+    let tsplib = Tsplib::tokenize(&content);
+    let instance = Instance::load_with_precision(filename, precision)?;
+    let n = instance.num_cities;
+
+    let asymmetric_pairs = (0..n)
+        .into_par_iter()
+        .map(|i| {
+            (i + 1..n)
+                .filter(|&j| instance.dist(i, j) != instance.dist(j, i))
+                .count()
+        })
+        .sum();
+
+    let (min, max, sum) = (0..n)
+        .into_par_iter()
+        .map(|i| {
+            (0..n)
+                .filter(|&j| j != i)
+                .fold((Cost::MAX, Cost::MIN, 0i128), |(lo, hi, sum), j| {
+                    let d = instance.dist(i, j);
+                    (lo.min(d), hi.max(d), sum + d as i128)
+                })
+        })
+        .reduce(
+            || (Cost::MAX, Cost::MIN, 0),
+            |a, b| (a.0.min(b.0), a.1.max(b.1), a.2 + b.2),
+        );
+    let scale = instance.scale() as f64;
+    let pairs = (n * n.saturating_sub(1)).max(1) as f64;
+
+    let (triangle_violations, triangle_samples) = triangle_violations(&instance);
+
+    let bounding_box = instance.coords().map(|coords| {
+        coords.iter().fold(
+            ((f64::MAX, f64::MAX), (f64::MIN, f64::MIN)),
+            |((min_x, min_y), (max_x, max_y)), &(x, y)| {
+                ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))
+            },
+        )
+    });
+
+    Ok(InstanceSummary {
+        name: instance.name.clone().unwrap_or_default(),
+        dimension: n,
+        edge_weight_type: tsplib.entry("EDGE_WEIGHT_TYPE").map(str::to_string),
+        edge_weight_format: tsplib.entry("EDGE_WEIGHT_FORMAT").map(str::to_string),
+        asymmetric_pairs,
+        min_distance: min as f64 / scale,
+        max_distance: max as f64 / scale,
+        mean_distance: sum as f64 / pairs / scale,
+        triangle_violations,
+        triangle_samples,
+        bounding_box,
+    })
+}
+
+/// Prints the metadata of `filename` along with statistics of its distances, so that an
+/// instance can be sanity-checked before running the solver on it.
+pub fn print_info(filename: &str, precision: u32) -> Result<(), Error> {
+    let summary = summarize(filename, precision)?;
+
+    println!("Name: {}", summary.name);
+    println!("Dimension: {}", summary.dimension);
+    println!(
+        "Edge weight type: {}",
+        summary.edge_weight_type.as_deref().unwrap_or("unknown")
+    );
+    if let Some(format) = &summary.edge_weight_format {
+        println!("Edge weight format: {}", format);
+    }
+
+    if summary.asymmetric_pairs == 0 {
+        println!("Symmetric: yes");
+    } else {
+        println!(
+            "Symmetric: no ({} asymmetric pairs)",
+            summary.asymmetric_pairs
+        );
+    }
+
+    if summary.dimension > 1 {
+        println!(
+            "Distances: min = {}, max = {}, mean = {:.2}",
+            summary.min_distance, summary.max_distance, summary.mean_distance
+        );
+    }
+
+    match summary.triangle_samples {
+        None => println!(
+            "Triangle-inequality violations: {}",
+            summary.triangle_violations
+        ),
+        Some(samples) => println!(
+            "Triangle-inequality violations: {} in {} sampled triples",
+            summary.triangle_violations, samples
+        ),
+    }
+
+    match summary.bounding_box {
+        Some(((min_x, min_y), (max_x, max_y))) => println!(
+            "Bounding box: x in [{}, {}], y in [{}, {}]",
+            min_x, max_x, min_y, max_y
+        ),
+        None => println!("Bounding box: no coordinates"),
+    }
+    Ok(())
+}
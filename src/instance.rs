@@ -1,114 +1,668 @@
+use crate::candidates::CandidateLists;
+use crate::constraints::EdgeConstraints;
+use crate::distance::{
+    DistanceProvider, Distances, EuclideanDistances, HaversineDistances, MatrixDistances,
+};
+use crate::error::{Error, ErrorKind};
+use crate::geo;
+use crate::kdtree::KdTree;
+use crate::memory;
+use crate::objective::Objective;
+use crate::tsplib::{self, Tsplib};
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Type of distances and tour lengths.
+pub type Cost = i64;
+
+/// EUC_2D instances with more cities than this compute their distances on the fly instead of
+/// storing the O(n²) matrix.
+const MATRIX_MAX_CITIES: usize = 20_000;
+
 pub struct Instance {
+    /// NAME of the TSPLIB file, or the file name when it has none.
+    pub name: Option<String>,
+    /// TYPE of the TSPLIB file, e.g. `TSP` or `ATSP`.
+    pub problem_type: Option<String>,
+    /// COMMENT lines of the TSPLIB file, joined with spaces.
+    pub comment: Option<String>,
     pub num_cities: usize,
-    pub distances: Vec<Vec<i32>>,
+    distances: Distances,
+    coords: Option<Vec<(f64, f64)>>,
+    /// Spatial index over `coords`, built when the distances are Euclidean over them.
+    kd_tree: Option<KdTree>,
+    /// Candidate neighbours for local search, built on first use.
+    candidates: OnceLock<CandidateLists>,
+    /// Hamiltonian path instead of cycle: the edge back to the first city costs nothing.
+    pub open_tour: bool,
+    /// City that every tour must start at (position 0).
+    pub start_city: Option<usize>,
+    /// City that every tour must end at (last position).
+    pub end_city: Option<usize>,
+    /// Forced and forbidden edges, including the FIXED_EDGES_SECTION of the TSPLIB file,
+    /// already folded into `distances` as penalties.
+    pub constraints: Option<EdgeConstraints>,
+    /// Constant added to every tour length to cancel the forced-edge bonuses in `distances`.
+    pub distance_offset: Cost,
+    /// Decimal digits kept in the EUC_2D distances, which are scaled by `10^precision`.
+    precision: u32,
+    /// Index in the original instance of each city of an instance built by `subset`.
+    pub original_cities: Option<Vec<usize>>,
+    /// Criterion that tours are ranked by.
+    pub objective: Objective,
+    /// Variance of the cost of each edge, for stochastic edge costs whose means are the
+    /// distances. Given in the units of the distances squared, unscaled by the precision.
+    pub variances: Option<MatrixDistances>,
+    /// Weight of the standard deviation of the tour length in the robust objective.
+    pub risk_aversion: f64,
 }
 
 impl Instance {
-    pub fn load(filename: &str) -> Self {
-        let content = std::fs::read_to_string(filename).expect("Failed to read instance file");
-        let lines: Vec<&str> = content.lines().collect();
+    pub fn load(filename: &str) -> Result<Self, Error> {
+        Self::load_with_precision(filename, 0)
+    }
 
-        let mut num_cities = 0;
-        let mut distances = Vec::new();
-        let mut coords = Vec::new();
+    /// Loads an instance keeping `precision` decimal digits of the EUC_2D distances, which are
+    /// stored as integers scaled by `10^precision`. Files ending in `.gz` are decompressed, and
+    /// files ending in `.json` or `.csv` are read with `parse_json` or `parse_csv`.
+    pub fn load_with_precision(filename: &str, precision: u32) -> Result<Self, Error> {
+        let content = tsplib::read_file(filename)?;
+        let format = filename.strip_suffix(".gz").unwrap_or(filename);
+        let mut instance = if format.ends_with(".json") {
+            Self::parse_json(&content, precision)?
+        } else if format.ends_with(".csv") {
+            Self::parse_csv(&content, precision)?
+        } else {
+            Self::parse(&content, precision)?
+        };
+        if instance.name.is_none() {
+            instance.name = Some(name_from_path(filename).to_string());
+        }
+        Ok(instance)
+    }
 
-        let mut line_iter = lines.iter();
-
-        while let Some(&line) = line_iter.next() {
-            if line.starts_with("DIMENSION") {
-                num_cities = line.split_whitespace().last().unwrap().parse().unwrap();
-                distances = vec![vec![0; num_cities]; num_cities];
-            } else if line.starts_with("EDGE_WEIGHT_TYPE: EXPLICIT") {
-                while let Some(&line) = line_iter.next() {
-                    if line.starts_with("EDGE_WEIGHT_FORMAT") {
-                        let format = line.split_whitespace().last().unwrap();
-                        match format {
-                            "FULL_MATRIX" => {
-                                while let Some(&line) = line_iter.next() {
-                                    if line.starts_with("EDGE_WEIGHT_SECTION") {
-                                        break;
-                                    }
-                                }
-
-                                for (row_index, line) in
-                                    line_iter.clone().enumerate().take(num_cities)
-                                {
-                                    let row: Vec<i32> = line
-                                        .split_whitespace()
-                                        .map(|x| x.parse().expect("Failed to parse distance"))
-                                        .collect();
-                                    distances[row_index][..row.len()].copy_from_slice(&row);
-                                }
-                            }
-                            "UPPER_ROW" => {
-                                while let Some(&line) = line_iter.next() {
-                                    if line.starts_with("EDGE_WEIGHT_SECTION") {
-                                        break;
-                                    }
-                                }
-
-                                let mut row_index = 0;
-                                let mut col_index = 1;
-                                for line in line_iter.clone() {
-                                    if line.starts_with("EOF") {
-                                        break;
-                                    }
-
-                                    for value in line.split_whitespace() {
-                                        distances[row_index][col_index] =
-                                            value.parse().expect("Failed to parse distance");
-                                        distances[col_index][row_index] =
-                                            distances[row_index][col_index];
-                                        col_index += 1;
-                                        if col_index >= num_cities {
-                                            row_index += 1;
-                                            col_index = row_index + 1;
-                                        }
-                                    }
-                                }
-                            }
-                            _ => panic!("Unsupported EDGE_WEIGHT_FORMAT: {}", format),
-                        }
-                        break;
-                    }
-                }
-            } else if line.starts_with("EDGE_WEIGHT_TYPE: EUC_2D") {
-                while let Some(&line) = line_iter.next() {
-                    if line.starts_with("NODE_COORD_SECTION") {
-                        break;
-                    }
-                }
+    /// Parses an instance from the contents of a TSPLIB file, keeping `precision` decimal
+    /// digits of the EUC_2D distances.
+    pub fn parse(content: &str, precision: u32) -> Result<Self, Error> {
+        let tsplib = Tsplib::tokenize(content);
+        let num_cities: usize = tsplib
+            .parse_entry("DIMENSION")?
+            .ok_or_else(|| invalid("Missing DIMENSION in the instance file"))?;
+        if num_cities == 0 {
+            return Err(invalid("The instance has no cities"));
+        }
+        let display_coords = tsplib
+            .numbers("DISPLAY_DATA_SECTION")?
+            .map(|values| node_coords(&values, num_cities, "DISPLAY_DATA_SECTION"))
+            .transpose()?;
 
-                for _ in 0..num_cities {
-                    if let Some(&line) = line_iter.next() {
-                        let coords_data: Vec<f64> = line
-                            .split_whitespace()
-                            .skip(1)
-                            .map(|x| x.parse().expect("Failed to parse coordinate"))
-                            .collect();
-                        coords.push((coords_data[0], coords_data[1]));
-                    }
-                }
+        let edge_weight_type = tsplib
+            .entry("EDGE_WEIGHT_TYPE")
+            .ok_or_else(|| invalid("Missing EDGE_WEIGHT_TYPE in the instance file"))?;
+        let mut instance = match edge_weight_type {
+            "EUC_2D" => {
+                let values = tsplib
+                    .numbers("NODE_COORD_SECTION")?
+                    .ok_or_else(|| invalid("Missing NODE_COORD_SECTION in the instance file"))?;
+                let coords = node_coords(&values, num_cities, "NODE_COORD_SECTION")?;
+                Self::from_coords(coords, precision)
+            }
+            "EXPLICIT" => {
+                memory::check_matrix(num_cities)?;
+                let weights: Vec<Cost> = tsplib
+                    .numbers("EDGE_WEIGHT_SECTION")?
+                    .ok_or_else(|| invalid("Missing EDGE_WEIGHT_SECTION in the instance file"))?;
+                let format = tsplib.entry("EDGE_WEIGHT_FORMAT").unwrap_or("FULL_MATRIX");
+                let distances = explicit_distances(&weights, num_cities, format)?;
+                let distances = Distances::Matrix(MatrixDistances::new(num_cities, distances));
+                Self::with_distances(num_cities, distances, display_coords, precision)
+            }
+            _ => {
+                return Err(invalid(format!(
+                    "Unsupported EDGE_WEIGHT_TYPE: {}",
+                    edge_weight_type
+                )))
+            }
+        };
+        instance.name = tsplib.entry("NAME").map(str::to_string);
+        instance.problem_type = tsplib.entry("TYPE").map(str::to_string);
+        if !tsplib.comments().is_empty() {
+            instance.comment = Some(tsplib.comments().join(" "));
+        }
+        if let Some(values) = tsplib.numbers("FIXED_EDGES_SECTION")? {
+            EdgeConstraints::forcing(fixed_edges(&values, num_cities)?).apply(&mut instance)?;
+        }
+        Ok(instance)
+    }
+
+    /// Parses an instance from a JSON object `{"n": 3, "matrix": [[0, 4, 2], ...]}` holding the
+    /// full distance matrix, row by row (`n` is optional). Distances may have decimals, of which
+    /// `precision` digits are kept.
+    #[cfg(feature = "json")]
+    pub fn parse_json(content: &str, precision: u32) -> Result<Self, Error> {
+        #[derive(serde::Deserialize)]
+        struct JsonInstance {
+            n: Option<usize>,
+            matrix: Vec<Vec<f64>>,
+        }
+
+        let json: JsonInstance = serde_json::from_str(content)
+            .map_err(|error| invalid(format!("Failed to parse the JSON instance: {}", error)))?;
+        let num_cities = json.matrix.len();
+        if json.n.is_some_and(|n| n != num_cities) {
+            return Err(invalid(format!(
+                "The JSON instance has n = {} but {} matrix rows",
+                json.n.unwrap(),
+                num_cities
+            )));
+        }
+        if json.matrix.iter().any(|row| row.len() != num_cities) {
+            return Err(invalid(format!(
+                "Every matrix row of the JSON instance must have {} distances",
+                num_cities
+            )));
+        }
+
+        memory::check_matrix(num_cities)?;
+        let scale = 10f64.powi(precision as i32);
+        let distances = json
+            .matrix
+            .iter()
+            .flatten()
+            .map(|&dist| (dist * scale).round() as Cost)
+            .collect();
+        let distances = Distances::Matrix(MatrixDistances::new(num_cities, distances));
+        Ok(Self::with_distances(num_cities, distances, None, precision))
+    }
+
+    #[cfg(not(feature = "json"))]
+    pub fn parse_json(_content: &str, _precision: u32) -> Result<Self, Error> {
+        Err(Error::new(
+            ErrorKind::InvalidArguments,
+            "JSON instances require the json feature",
+        ))
+    }
+
+    /// Parses an EUC_2D instance from a CSV list of coordinates, one city per row. The last two
+    /// columns of a row are its `x` and `y`, so a leading id or name column is ignored, and a
+    /// first row that is not numeric is taken as a header. A header naming `lat` and `lon`
+    /// columns makes it a list of geographic points instead, solved with haversine distances.
+    pub fn parse_csv(content: &str, precision: u32) -> Result<Self, Error> {
+        if let Some(points) = geo::read_lat_lon(content)? {
+            return Self::from_lat_lon(points, precision);
+        }
 
-                for i in 0..num_cities {
-                    for j in i + 1..num_cities {
-                        let dx = coords[i].0 - coords[j].0;
-                        let dy = coords[i].1 - coords[j].1;
-                        let dist = ((dx * dx + dy * dy).sqrt() + 0.5).floor() as i32;
-                        distances[i][j] = dist;
-                        distances[j][i] = dist;
-                    }
+        let mut coords = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [.., x, y] = fields[..] else {
+                return Err(invalid(format!(
+                    "Expected x and y columns in CSV line: {}",
+                    line
+                )));
+            };
+            match (x.parse(), y.parse()) {
+                (Ok(x), Ok(y)) => coords.push((x, y)),
+                _ if index == 0 => continue,
+                _ => {
+                    return Err(invalid(format!(
+                        "Invalid coordinates in CSV line: {}",
+                        line
+                    )))
                 }
             }
         }
-
-        if distances.is_empty() {
-            panic!("Failed to parse the instance file");
+        if coords.is_empty() {
+            return Err(invalid("The CSV instance has no cities"));
         }
+        Ok(Self::from_coords(coords, precision))
+    }
+
+    /// Builds an EUC_2D instance over `coords`, keeping `precision` decimal digits of the
+    /// distances.
+    pub fn from_coords(coords: Vec<(f64, f64)>, precision: u32) -> Self {
+        let num_cities = coords.len();
+        let scale = 10f64.powi(precision as i32);
+        let euclidean = EuclideanDistances::new(coords.clone(), scale);
+        // Without room for the matrix under the `--max-memory` cap, the distances are computed
+        // on the fly.
+        let distances =
+            if num_cities <= MATRIX_MAX_CITIES && memory::check_matrix(num_cities).is_ok() {
+                Distances::Matrix(MatrixDistances::from_provider(num_cities, &euclidean))
+            } else {
+                Distances::Euclidean(euclidean)
+            };
+
+        let mut instance = Self::with_distances(num_cities, distances, Some(coords), precision);
+        instance.build_kd_tree();
+        instance
+    }
+
+    /// Builds an instance over `points` (latitude and longitude in degrees) whose distances are
+    /// the great-circle distances in meters, keeping `precision` decimal digits.
+    pub fn from_lat_lon(points: Vec<(f64, f64)>, precision: u32) -> Result<Self, Error> {
+        let num_cities = points.len();
+        memory::check_matrix(num_cities)?;
+        let haversine = HaversineDistances::new(points.clone(), 10f64.powi(precision as i32));
+        let distances = Distances::Matrix(MatrixDistances::from_provider(num_cities, &haversine));
+        let coords = points.into_iter().map(|(lat, lon)| (lon, lat)).collect();
+        Ok(Self::with_distances(
+            num_cities,
+            distances,
+            Some(coords),
+            precision,
+        ))
+    }
 
+    /// Builds an instance from distances that were computed with `precision` decimal digits.
+    pub fn with_distances(
+        num_cities: usize,
+        distances: Distances,
+        coords: Option<Vec<(f64, f64)>>,
+        precision: u32,
+    ) -> Self {
         Instance {
+            name: None,
+            problem_type: None,
+            comment: None,
             num_cities,
             distances,
+            coords,
+            kd_tree: None,
+            candidates: OnceLock::new(),
+            open_tour: false,
+            start_city: None,
+            end_city: None,
+            constraints: None,
+            distance_offset: 0,
+            precision,
+            original_cities: None,
+            objective: Objective::default(),
+            variances: None,
+            risk_aversion: 0.0,
+        }
+    }
+
+    /// The instance restricted to `cities`, numbered in the order given, which records the
+    /// original index of each. Constraints between two kept cities are kept too.
+    pub fn subset(mut self, cities: &[usize]) -> Result<Self, Error> {
+        let constraints = EdgeConstraints::lift(&mut self);
+        let mut subset = self.restricted(cities)?;
+
+        if let Some(constraints) = constraints {
+            let mut index = vec![None; self.num_cities];
+            for (i, &city) in cities.iter().enumerate() {
+                index[city] = Some(i);
+            }
+            let remap = |edges: &HashSet<(usize, usize)>| -> Vec<(usize, usize)> {
+                edges
+                    .iter()
+                    .filter_map(|&(a, b)| Some((index[a]?, index[b]?)))
+                    .collect()
+            };
+            let mut kept = EdgeConstraints::forcing(remap(&constraints.forced));
+            kept.forbidden = remap(&constraints.forbidden)
+                .into_iter()
+                .map(|(a, b)| (a.min(b), a.max(b)))
+                .collect();
+            kept.apply(&mut subset)?;
+        }
+
+        Ok(subset)
+    }
+
+    /// The instance restricted to `cities` like `subset`, without its constraints.
+    pub fn restricted(&self, cities: &[usize]) -> Result<Self, Error> {
+        let coords = self
+            .coords
+            .as_ref()
+            .map(|coords| cities.iter().map(|&city| coords[city]).collect());
+
+        let mut subset = if self.kd_tree.is_some() {
+            Self::from_coords(coords.unwrap(), self.precision)
+        } else {
+            let n = cities.len();
+            memory::check_matrix(n)?;
+            let mut distances = vec![0; n * n];
+            for (i, &a) in cities.iter().enumerate() {
+                for (j, &b) in cities.iter().enumerate() {
+                    distances[i * n + j] = self.dist(a, b);
+                }
+            }
+            let distances = Distances::Matrix(MatrixDistances::new(n, distances));
+            Self::with_distances(n, distances, coords, self.precision)
+        };
+        subset.name = self.name.clone();
+        subset.problem_type = self.problem_type.clone();
+        subset.comment = self.comment.clone();
+        subset.open_tour = self.open_tour;
+        subset.original_cities = Some(cities.to_vec());
+        Ok(subset)
+    }
+
+    /// Instance over the same cities whose distances are `weight` times these plus `1 - weight`
+    /// times `scale` times those of `other`, scalarizing the two into a single objective.
+    pub fn weighted(&self, other: &Instance, weight: f64, scale: f64) -> Result<Self, Error> {
+        let n = self.num_cities;
+        memory::check_matrix(n)?;
+        let mut distances = vec![0; n * n];
+        for i in 0..n {
+            for j in 0..n {
+                distances[i * n + j] = (weight * self.dist(i, j) as f64
+                    + (1.0 - weight) * scale * other.dist(i, j) as f64)
+                    .round() as Cost;
+            }
+        }
+        let distances = Distances::Matrix(MatrixDistances::new(n, distances));
+        let mut weighted = Self::with_distances(n, distances, self.coords.clone(), self.precision);
+        weighted.open_tour = self.open_tour;
+        weighted.start_city = self.start_city;
+        weighted.end_city = self.end_city;
+        Ok(weighted)
+    }
+
+    /// Uses the distances of `variances`, an instance over the same cities, as the variances of
+    /// the edge costs, weighed by `risk_aversion` in the robust objective.
+    pub fn set_variances(&mut self, variances: &Instance, risk_aversion: f64) -> Result<(), Error> {
+        let n = self.num_cities;
+        memory::check_matrix(n)?;
+        let mut matrix = vec![0; n * n];
+        for i in 0..n {
+            for j in 0..n {
+                matrix[i * n + j] = variances.dist(i, j);
+            }
+        }
+        self.variances = Some(MatrixDistances::new(n, matrix));
+        self.risk_aversion = risk_aversion;
+        Ok(())
+    }
+
+    /// Variance of the cost of the edge from city `i` to city `j`, 0 without variances.
+    pub fn variance(&self, i: usize, j: usize) -> Cost {
+        self.variances
+            .as_ref()
+            .map_or(0, |variances| variances.row(i)[j])
+    }
+
+    /// Variance of the length of the tour `path`, the sum of the variances of its edges.
+    pub fn tour_variance(&self, path: &[usize]) -> Cost {
+        let Some(variances) = &self.variances else {
+            return 0;
+        };
+        let closing = if self.open_tour {
+            0
+        } else {
+            variances.row(*path.last().unwrap())[path[0]]
+        };
+        variances.path_length(path) + closing
+    }
+
+    /// Risk term of a tour length of variance `variance`: its standard deviation times the risk
+    /// aversion, scaled like the distances.
+    pub fn risk(&self, variance: Cost) -> Cost {
+        (self.risk_aversion * (variance.max(0) as f64).sqrt() * self.scale() as f64).round() as Cost
+    }
+
+    /// Builds the k-d tree over the node coordinates. Only meaningful when the distances are
+    /// the Euclidean distances between them.
+    pub fn build_kd_tree(&mut self) {
+        let coords = self
+            .coords
+            .as_deref()
+            .expect("The instance has no coordinates");
+        self.kd_tree = Some(KdTree::new(coords));
+    }
+
+    /// Candidate neighbours of every city, computed the first time they are needed.
+    pub fn candidates(&self) -> &CandidateLists {
+        self.candidates.get_or_init(|| CandidateLists::new(self))
+    }
+
+    /// The k-d tree over the node coordinates, when the instance is geometric and no edge
+    /// constraints have changed its distances.
+    pub fn kd_tree(&self) -> Option<&KdTree> {
+        self.kd_tree.as_ref().filter(|_| self.constraints.is_none())
+    }
+
+    /// Factor applied to the distances by the precision mode.
+    pub fn scale(&self) -> Cost {
+        10i64.pow(self.precision)
+    }
+
+    /// Formats a cost in the instance's original units.
+    pub fn format_cost(&self, cost: Cost) -> String {
+        if self.precision == 0 {
+            cost.to_string()
+        } else {
+            format!(
+                "{:.*}",
+                self.precision as usize,
+                cost as f64 / self.scale() as f64
+            )
+        }
+    }
+
+    /// Distance from city `i` to city `j`.
+    #[inline]
+    pub fn dist(&self, i: usize, j: usize) -> Cost {
+        self.distances.dist(i, j)
+    }
+
+    /// Distances from city `i` to every city, when they are precomputed.
+    #[inline]
+    pub fn row(&self, i: usize) -> Option<&[Cost]> {
+        self.matrix().map(|matrix| matrix.row(i))
+    }
+
+    /// Sum of the distances between consecutive cities of `path`, without the closing edge.
+    pub fn path_length(&self, path: &[usize]) -> Cost {
+        match self.matrix() {
+            Some(matrix) => matrix.path_length(path),
+            None => path
+                .windows(2)
+                .map(|edge| self.dist(edge[0], edge[1]))
+                .sum(),
+        }
+    }
+
+    /// The precomputed distance matrix, unless distances are computed on the fly.
+    pub fn matrix(&self) -> Option<&MatrixDistances> {
+        match &self.distances {
+            Distances::Matrix(matrix) => Some(matrix),
+            Distances::Euclidean(_) => None,
+        }
+    }
+
+    pub fn matrix_mut(&mut self) -> Option<&mut MatrixDistances> {
+        match &mut self.distances {
+            Distances::Matrix(matrix) => Some(matrix),
+            Distances::Euclidean(_) => None,
+        }
+    }
+
+    /// Mean distance between two distinct cities, estimated from a sample of city pairs when
+    /// distances are computed on the fly.
+    pub fn mean_distance(&self) -> f64 {
+        let n = self.num_cities;
+        match self.matrix() {
+            Some(matrix) => {
+                let total: f64 = matrix.as_slice().iter().map(|&d| d as f64).sum();
+                total / (n * n.saturating_sub(1)).max(1) as f64
+            }
+            None => {
+                const SAMPLES: usize = 100_000;
+                let total: f64 = (0..SAMPLES)
+                    .map(|_| {
+                        let i = crate::rng::random::<usize>() % n;
+                        let j = (i + 1 + crate::rng::random::<usize>() % (n - 1)) % n;
+                        self.dist(i, j) as f64
+                    })
+                    .sum();
+                total / SAMPLES as f64
+            }
+        }
+    }
+
+    /// Distance of an edge closing the tour (from the last city back to the first one), which
+    /// is zero on open tours.
+    pub fn closing_distance(&self, from: usize, to: usize) -> Cost {
+        if self.open_tour {
+            0
+        } else {
+            self.dist(from, to)
+        }
+    }
+
+    /// One past the last path position that moves may relocate: the last position is excluded
+    /// when the end city is fixed.
+    pub fn movable_end(&self) -> usize {
+        if self.end_city.is_some() {
+            self.num_cities - 1
+        } else {
+            self.num_cities
+        }
+    }
+
+    /// Node coordinates, when the instance provides them (NODE_COORD_SECTION or
+    /// DISPLAY_DATA_SECTION).
+    pub fn coords(&self) -> Option<&[(f64, f64)]> {
+        self.coords.as_deref()
+    }
+}
+
+/// Instance name derived from its file name, e.g. `bays29` for `instances/bays29.tsp` or
+/// `instances/bays29.tsp.gz`.
+pub fn name_from_path(filename: &str) -> &str {
+    let filename = filename.strip_suffix(".gz").unwrap_or(filename);
+    std::path::Path::new(filename)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(filename)
+}
+
+/// Error of a malformed instance file.
+fn invalid(message: impl Into<String>) -> Error {
+    Error::new(ErrorKind::Instance, message)
+}
+
+/// Coordinates of the `num_cities` nodes listed in `values` as `id x y` triples, placed by id.
+fn node_coords(values: &[f64], num_cities: usize, section: &str) -> Result<Vec<(f64, f64)>, Error> {
+    if values.len() != num_cities * 3 {
+        return Err(invalid(format!(
+            "Expected {} nodes in {}",
+            num_cities, section
+        )));
+    }
+    let mut coords = vec![(0.0, 0.0); num_cities];
+    for node in values.chunks(3) {
+        let id = node[0] as usize;
+        if id == 0 || id > num_cities {
+            return Err(invalid(format!(
+                "Invalid node id in {}: {}",
+                section, node[0]
+            )));
+        }
+        coords[id - 1] = (node[1], node[2]);
+    }
+    Ok(coords)
+}
+
+/// Edges listed in a FIXED_EDGES_SECTION as pairs of node ids, terminated by `-1`.
+fn fixed_edges(values: &[i64], num_cities: usize) -> Result<Vec<(usize, usize)>, Error> {
+    let end = values.iter().position(|&value| value == -1);
+    let values = &values[..end.unwrap_or(values.len())];
+    if !values.len().is_multiple_of(2) {
+        return Err(invalid("FIXED_EDGES_SECTION must list pairs of nodes"));
+    }
+    let city = |id: i64| match usize::try_from(id) {
+        Ok(id) if (1..=num_cities).contains(&id) => Ok(id - 1),
+        _ => Err(invalid(format!(
+            "Invalid node id in FIXED_EDGES_SECTION: {}",
+            id
+        ))),
+    };
+    values
+        .chunks(2)
+        .map(|pair| Ok((city(pair[0])?, city(pair[1])?)))
+        .collect()
+}
+
+/// Full distance matrix from the EDGE_WEIGHT_SECTION `weights` stored in `format`.
+fn explicit_distances(
+    weights: &[Cost],
+    num_cities: usize,
+    format: &str,
+) -> Result<Vec<Cost>, Error> {
+    let n = num_cities;
+    let expect = |count: usize| match weights.len() {
+        len if len == count => Ok(()),
+        _ => Err(invalid(format!(
+            "Expected {} weights in EDGE_WEIGHT_SECTION",
+            count
+        ))),
+    };
+    match format {
+        "FULL_MATRIX" => {
+            expect(n * n)?;
+            Ok(weights.to_vec())
+        }
+        "UPPER_ROW" => {
+            expect(n * (n - 1) / 2)?;
+            let mut distances = vec![0; n * n];
+            let pairs = (0..n).flat_map(|row| (row + 1..n).map(move |col| (row, col)));
+            for ((row, col), &dist) in pairs.zip(weights) {
+                distances[row * n + col] = dist;
+                distances[col * n + row] = dist;
+            }
+            Ok(distances)
         }
+        _ => Err(invalid(format!(
+            "Unsupported EDGE_WEIGHT_FORMAT: {}",
+            format
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_an_upper_row_matrix() {
+        let instance = Instance::parse(
+            "DIMENSION: 3
+EDGE_WEIGHT_TYPE: EXPLICIT
+EDGE_WEIGHT_FORMAT: UPPER_ROW
+EDGE_WEIGHT_SECTION
+4 2
+5
+EOF
+",
+            0,
+        )
+        .unwrap();
+        assert_eq!(instance.num_cities, 3);
+        assert_eq!(instance.dist(0, 1), 4);
+        assert_eq!(instance.dist(2, 0), 2);
+        assert_eq!(instance.dist(1, 2), 5);
+    }
+
+    #[test]
+    fn parse_requires_the_edge_weight_type() {
+        let error = Instance::parse("DIMENSION: 2\nEDGE_WEIGHT_SECTION\n0 1 1 0\nEOF\n", 0);
+        assert!(matches!(error, Err(error) if error.kind == ErrorKind::Instance));
+    }
+
+    #[test]
+    fn parse_rejects_an_empty_instance() {
+        let error = Instance::parse(
+            "DIMENSION: 0\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: UPPER_ROW\nEOF\n",
+            0,
+        );
+        assert!(matches!(error, Err(error) if error.kind == ErrorKind::Instance));
     }
 }
@@ -0,0 +1,276 @@
+use crate::construction::Constructive;
+use crate::grasp::{grasp_batch, update_elite_set, StaticPrConfig};
+use crate::instance::Instance;
+use crate::local_search::LocalSearch;
+use crate::solution::{read_solutions, write_solutions, Solution};
+use crate::termination::Termination;
+use crate::time::Instant;
+use crate::trace::Trace;
+
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tracing::{info, trace, warn};
+
+/// Time an island keeps retrying to reach the coordinator before giving up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// GRASP with an elite set that also absorbs solutions migrated from other islands.
+struct Island<'a> {
+    instance: &'a Instance,
+    construction: &'a dyn Constructive,
+    local_search: &'a dyn LocalSearch,
+    config: StaticPrConfig,
+    min_difference: usize,
+    elite_set: Vec<Solution>,
+    best: Option<Solution>,
+    iteration: usize,
+}
+
+impl<'a> Island<'a> {
+    fn new(
+        instance: &'a Instance,
+        construction: &'a dyn Constructive,
+        local_search: &'a dyn LocalSearch,
+        config: &StaticPrConfig,
+    ) -> Self {
+        Island {
+            instance,
+            construction,
+            local_search,
+            config: *config,
+            min_difference: (instance.num_cities as f64 * config.min_diversity).round() as usize,
+            elite_set: Vec::with_capacity(config.elite_size),
+            best: None,
+            iteration: 0,
+        }
+    }
+
+    /// Considers `solution` for the best solution and the elite set.
+    fn offer(
+        &mut self,
+        solution: Solution,
+        thread: usize,
+        termination: &Termination,
+        trace: &Trace,
+    ) {
+        self.iteration += 1;
+        if self
+            .best
+            .as_ref()
+            .is_none_or(|best| self.instance.objective.better(&solution, best))
+        {
+            termination.report(self.instance.objective.value(&solution));
+            trace.record_improvement(self.iteration, thread, &solution, self.instance);
+            self.best = Some(solution.copy());
+        }
+        update_elite_set(
+            &mut self.elite_set,
+            solution,
+            self.config.elite_size,
+            self.min_difference,
+            self.config.replacement_policy,
+            self.instance.objective,
+        );
+    }
+
+    /// Merges migrated solutions into the elite set. With `relink`, each migrant is also
+    /// relinked towards the island's best solution, so that the two searches are combined.
+    fn absorb(
+        &mut self,
+        migrants: Vec<Solution>,
+        relink: bool,
+        termination: &Termination,
+        trace: &Trace,
+    ) {
+        for mut migrant in migrants {
+            if relink {
+                if let Some(best) = &self.best {
+                    migrant.path_relinking(
+                        best,
+                        self.instance,
+                        self.local_search,
+                        self.config.pr_depth,
+                        self.config.pr_local_search,
+                        self.config.pr_stop,
+                        self.config.pr_selection,
+                    );
+                    self.local_search.improve(&mut migrant, self.instance);
+                    migrant.eval(self.instance);
+                }
+            }
+            self.offer(migrant, 0, termination, trace);
+        }
+        trace.publish_elite(&self.elite_set);
+    }
+
+    /// Runs GRASP iterations until `termination` stops the run, calling `migrate` with the elite
+    /// set every `interval` and absorbing the solutions it returns. `migrate` is called one last
+    /// time once the run is over.
+    fn run(
+        &mut self,
+        termination: &Termination,
+        trace: &Trace,
+        interval: Duration,
+        mut migrate: impl FnMut(&[Solution]) -> Vec<Solution>,
+    ) -> Solution {
+        let mut last_migration = Instant::now();
+        while !termination.should_stop() {
+            let batch = grasp_batch(
+                self.instance,
+                termination,
+                self.construction,
+                self.local_search,
+                trace,
+            );
+            for (thread, solution) in batch {
+                trace!(
+                    iteration = self.iteration + 1,
+                    thread,
+                    distance = solution.total_distance,
+                    "Iteration done"
+                );
+                self.offer(solution, thread, termination, trace);
+            }
+            trace.publish_elite(&self.elite_set);
+            self.construction.learn(&self.elite_set);
+
+            if last_migration.elapsed() >= interval && !termination.should_stop() {
+                let migrants = migrate(&self.elite_set);
+                info!("Received {} migrants", migrants.len());
+                self.absorb(migrants, true, termination, trace);
+                last_migration = Instant::now();
+            }
+        }
+
+        let migrants = migrate(&self.elite_set);
+        self.absorb(migrants, false, termination, trace);
+        self.best
+            .take()
+            .expect("The island should find at least one solution")
+    }
+}
+
+/// Answers the migrations of one island until it disconnects: every elite set it sends goes to
+/// `inbox`, and the coordinator's current elite set is sent back.
+fn serve_island(
+    stream: TcpStream,
+    instance: &Instance,
+    inbox: &Mutex<Vec<Solution>>,
+    elite_set: &Mutex<Vec<Solution>>,
+) {
+    let peer = stream
+        .peer_addr()
+        .map_or("unknown".to_string(), |addr| addr.to_string());
+    let mut reader = BufReader::new(stream.try_clone().expect("Failed to clone the connection"));
+    let mut writer = stream;
+    while let Some(migrants) = read_solutions(&mut reader, instance) {
+        inbox.lock().unwrap().extend(migrants);
+        let reply = elite_set.lock().unwrap().clone();
+        if write_solutions(&mut writer, &reply).is_err() {
+            break;
+        }
+    }
+    info!("Island {} disconnected", peer);
+}
+
+/// Coordinator of the island model: waits for `islands` islands to connect on `listen`, then
+/// runs GRASP itself while exchanging elite solutions with them every `interval`. Once its own
+/// run is over it waits for the islands to send their final elite sets, and returns the best
+/// solution found by any island.
+#[allow(clippy::too_many_arguments)]
+pub fn coordinator(
+    listen: &str,
+    islands: usize,
+    interval: Duration,
+    instance: &Instance,
+    termination: &Termination,
+    construction: &dyn Constructive,
+    local_search: &dyn LocalSearch,
+    config: &StaticPrConfig,
+    trace: &Trace,
+) -> Solution {
+    let listener = TcpListener::bind(listen).expect("Failed to bind the coordinator address");
+    info!("Waiting for {} islands on {}", islands, listen);
+    let streams: Vec<TcpStream> = (0..islands)
+        .map(|_| {
+            let (stream, addr) = listener.accept().expect("Failed to accept an island");
+            info!("Island {} connected", addr);
+            stream
+        })
+        .collect();
+
+    let inbox = Mutex::new(Vec::new());
+    let shared_elite = Mutex::new(Vec::new());
+    let mut island = Island::new(instance, construction, local_search, config);
+
+    let mut best = thread::scope(|scope| {
+        for stream in streams {
+            let (inbox, shared_elite) = (&inbox, &shared_elite);
+            scope.spawn(move || serve_island(stream, instance, inbox, shared_elite));
+        }
+
+        island.run(termination, trace, interval, |elite_set| {
+            *shared_elite.lock().unwrap() = elite_set.to_vec();
+            std::mem::take(&mut *inbox.lock().unwrap())
+        })
+    });
+
+    // The islands' final elite sets arrive once their runs are over.
+    for solution in inbox.into_inner().unwrap() {
+        if instance.objective.better(&solution, &best) {
+            info!(
+                "Improved distance = {}",
+                instance.format_cost(solution.total_distance)
+            );
+            best = solution;
+        }
+    }
+    best
+}
+
+/// Island of the island model: connects to the coordinator at `coordinator` and runs GRASP,
+/// sending its elite set and absorbing the coordinator's every `interval`. If the coordinator
+/// becomes unreachable the island carries on alone. Returns the island's best solution.
+#[allow(clippy::too_many_arguments)]
+pub fn island(
+    coordinator: &str,
+    interval: Duration,
+    instance: &Instance,
+    termination: &Termination,
+    construction: &dyn Constructive,
+    local_search: &dyn LocalSearch,
+    config: &StaticPrConfig,
+    trace: &Trace,
+) -> Solution {
+    let start = Instant::now();
+    let stream = loop {
+        match TcpStream::connect(coordinator) {
+            Ok(stream) => break stream,
+            Err(_) if start.elapsed() < CONNECT_TIMEOUT => {
+                thread::sleep(Duration::from_millis(500))
+            }
+            Err(error) => panic!("Failed to connect to the coordinator: {}", error),
+        }
+    };
+    info!("Connected to the coordinator at {}", coordinator);
+
+    let mut reader = BufReader::new(stream.try_clone().expect("Failed to clone the connection"));
+    let mut writer = Some(stream);
+    let mut island = Island::new(instance, construction, local_search, config);
+    island.run(termination, trace, interval, |elite_set| {
+        let Some(stream) = writer.as_mut() else {
+            return Vec::new();
+        };
+        let reply = write_solutions(stream, elite_set)
+            .ok()
+            .and_then(|()| read_solutions(&mut reader, instance));
+        reply.unwrap_or_else(|| {
+            warn!("Lost the connection to the coordinator, continuing alone");
+            writer = None;
+            Vec::new()
+        })
+    })
+}
@@ -0,0 +1,156 @@
+use std::collections::BinaryHeap;
+
+/// Balanced 2-d tree over the node coordinates. Each node is the median of its range of
+/// `order` along the axis of its depth, so a subtree always covers a contiguous range.
+pub struct KdTree {
+    coords: Vec<(f64, f64)>,
+    order: Vec<usize>,
+    position: Vec<usize>,
+}
+
+/// Set of cities still available to a query, with the number of available cities under each
+/// tree node so that emptied subtrees are skipped.
+pub struct KdState {
+    available: Vec<bool>,
+    count: Vec<usize>,
+}
+
+fn axis_value(point: (f64, f64), axis: usize) -> f64 {
+    if axis == 0 {
+        point.0
+    } else {
+        point.1
+    }
+}
+
+fn squared_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)
+}
+
+impl KdTree {
+    pub fn new(coords: &[(f64, f64)]) -> Self {
+        let mut order: Vec<usize> = (0..coords.len()).collect();
+        Self::build(coords, &mut order, 0);
+
+        let mut position = vec![0; coords.len()];
+        for (index, &city) in order.iter().enumerate() {
+            position[city] = index;
+        }
+
+        KdTree {
+            coords: coords.to_vec(),
+            order,
+            position,
+        }
+    }
+
+    fn build(coords: &[(f64, f64)], order: &mut [usize], depth: usize) {
+        if order.len() <= 1 {
+            return;
+        }
+        let mid = order.len() / 2;
+        let axis = depth % 2;
+        order.select_nth_unstable_by(mid, |&a, &b| {
+            axis_value(coords[a], axis).total_cmp(&axis_value(coords[b], axis))
+        });
+        let (left, right) = order.split_at_mut(mid);
+        Self::build(coords, left, depth + 1);
+        Self::build(coords, &mut right[1..], depth + 1);
+    }
+
+    /// State in which every city is available.
+    pub fn full_state(&self) -> KdState {
+        let n = self.order.len();
+        let mut count = vec![0; n];
+        Self::init_count(&mut count, 0, n);
+        KdState {
+            available: vec![true; n],
+            count,
+        }
+    }
+
+    fn init_count(count: &mut [usize], lo: usize, hi: usize) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        count[mid] = hi - lo;
+        Self::init_count(count, lo, mid);
+        Self::init_count(count, mid + 1, hi);
+    }
+
+    /// Marks `city` as no longer available in `state`.
+    pub fn remove(&self, state: &mut KdState, city: usize) {
+        if !state.available[city] {
+            return;
+        }
+        state.available[city] = false;
+
+        let target = self.position[city];
+        let (mut lo, mut hi) = (0, self.order.len());
+        loop {
+            let mid = (lo + hi) / 2;
+            state.count[mid] -= 1;
+            match target.cmp(&mid) {
+                std::cmp::Ordering::Equal => break,
+                std::cmp::Ordering::Less => hi = mid,
+                std::cmp::Ordering::Greater => lo = mid + 1,
+            }
+        }
+    }
+
+    /// The (up to) `k` available cities closest to `city`, excluding `city` itself, in no
+    /// particular order.
+    pub fn k_nearest(&self, city: usize, k: usize, state: &KdState) -> Vec<usize> {
+        // Squared distances are non-negative, so ordering their bit patterns as integers
+        // matches ordering the floats.
+        let mut heap: BinaryHeap<(u64, usize)> = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(0, self.order.len(), 0, city, k, state, &mut heap);
+        }
+        heap.into_iter().map(|(_, city)| city).collect()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search(
+        &self,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        city: usize,
+        k: usize,
+        state: &KdState,
+        heap: &mut BinaryHeap<(u64, usize)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        if state.count[mid] == 0 {
+            return;
+        }
+
+        let target = self.coords[city];
+        let node = self.order[mid];
+        if node != city && state.available[node] {
+            heap.push((squared_distance(target, self.coords[node]).to_bits(), node));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        let axis = depth % 2;
+        let diff = axis_value(target, axis) - axis_value(self.coords[node], axis);
+        let (near, far) = if diff < 0.0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+
+        self.search(near.0, near.1, depth + 1, city, k, state, heap);
+        let worst = heap.peek().map(|&(bits, _)| f64::from_bits(bits));
+        if heap.len() < k || worst.is_some_and(|worst| diff * diff < worst) {
+            self.search(far.0, far.1, depth + 1, city, k, state, heap);
+        }
+    }
+}
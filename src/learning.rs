@@ -0,0 +1,65 @@
+use crate::construction::Constructive;
+use crate::grasp::{biased_constructive_phase, Rcl};
+use crate::instance::Instance;
+use crate::solution::Solution;
+
+use std::sync::RwLock;
+
+/// Learning GRASP: randomized nearest neighbor whose restricted candidate list favors the edges
+/// that appear often in elite solutions. Every call to `learn` decays the edge frequencies by
+/// `decay` and adds the edges of the elite solutions, so a candidate is drawn with probability
+/// proportional to `1 + frequency`. The bias starts at zero and grows, up to `1 / (1 - decay)`,
+/// as the same edges keep appearing in the elite set.
+pub struct LearningConstruction {
+    decay: f32,
+    rcl: Rcl,
+    /// Row-major edge frequencies, allocated once the number of cities is known.
+    frequencies: RwLock<Vec<f32>>,
+}
+
+impl LearningConstruction {
+    pub fn new(decay: f32, rcl: Rcl) -> Self {
+        LearningConstruction {
+            decay,
+            rcl,
+            frequencies: RwLock::new(Vec::new()),
+        }
+    }
+}
+
+impl Constructive for LearningConstruction {
+    fn construct(&self, instance: &Instance) -> Solution {
+        let frequencies = self.frequencies.read().unwrap();
+        if frequencies.is_empty() {
+            return biased_constructive_phase(instance, self.rcl, None);
+        }
+        let n = instance.num_cities;
+        biased_constructive_phase(
+            instance,
+            self.rcl,
+            Some(&|from, to| 1.0 + frequencies[from * n + to] as f64),
+        )
+    }
+
+    fn learn(&self, elite: &[Solution]) {
+        let Some(n) = elite.first().map(|solution| solution.path.len()) else {
+            return;
+        };
+        let mut frequencies = self.frequencies.write().unwrap();
+        if frequencies.is_empty() {
+            *frequencies = vec![0.0; n * n];
+        }
+
+        for frequency in frequencies.iter_mut() {
+            *frequency *= self.decay;
+        }
+        let share = 1.0 / elite.len() as f32;
+        for solution in elite {
+            for i in 0..n {
+                let (a, b) = (solution.path[i], solution.path[(i + 1) % n]);
+                frequencies[a * n + b] += share;
+                frequencies[b * n + a] += share;
+            }
+        }
+    }
+}
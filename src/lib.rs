@@ -0,0 +1,67 @@
+//! GRASP-based solvers for the traveling salesman problem and its variants.
+
+pub mod app;
+pub mod ascii;
+pub mod benchmark;
+pub mod best;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod candidates;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod cli;
+pub mod constraints;
+pub mod construction;
+pub mod decompose;
+pub mod distance;
+pub mod driver;
+pub mod error;
+pub mod experiment;
+pub mod export;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+pub mod generate;
+pub mod geo;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod grasp;
+pub mod ils;
+pub mod info;
+pub mod instance;
+pub mod island;
+pub mod kdtree;
+pub mod learning;
+pub mod lns;
+pub mod local_search;
+pub mod memetic;
+pub mod memory;
+pub mod metaheuristic;
+pub mod mtsp;
+pub mod objective;
+pub mod optimum;
+pub mod pareto;
+pub mod pctsp;
+pub mod perturbation;
+#[cfg(feature = "png")]
+pub mod png;
+pub mod problem;
+pub mod qap;
+pub mod report;
+pub mod rng;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod simulated_annealing;
+pub mod solution;
+pub mod stats;
+pub mod svg;
+pub mod tabu_search;
+pub mod termination;
+pub mod time;
+pub mod tour;
+pub mod trace;
+pub mod tsplib;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod verify;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,199 @@
+use crate::driver;
+use crate::grasp::constructive_phase;
+use crate::instance::{Cost, Instance};
+use crate::simulated_annealing::AnnealingSchedule;
+use crate::solution::Solution;
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use clap::ValueEnum;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// How the cities removed by each LNS iteration are chosen.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LnsDestroy {
+    /// Cities chosen uniformly at random.
+    Random,
+    /// A random city and the cities closest to it.
+    Related,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LnsConfig {
+    /// Number of cities removed and reinserted by each iteration.
+    pub removal: usize,
+    pub destroy: LnsDestroy,
+    /// Geometric cooling rate of the acceptance criterion.
+    pub cooling_rate: f64,
+}
+
+/// State of the destroy-and-repair chain of one LNS worker.
+struct Chain {
+    current: Solution,
+    best: Solution,
+    temperature: f64,
+}
+
+/// Large Neighborhood Search driver running one destroy-and-repair chain per worker until the
+/// time limit. Each iteration removes `config.removal` cities from the current tour, reinserts
+/// them with cheapest insertion and accepts the result with the simulated annealing criterion.
+pub fn lns(
+    instance: &Instance,
+    termination: &Termination,
+    config: &LnsConfig,
+    trace: &Trace,
+) -> Solution {
+    if instance.num_cities < 4 {
+        let mut solution = constructive_phase(instance);
+        solution.eval(instance);
+        return solution;
+    }
+    let schedule = AnnealingSchedule::for_instance(instance, config.cooling_rate, usize::MAX);
+    let mut workers: Vec<Option<Chain>> = driver::workers();
+    let mut best: Option<Solution> = None;
+
+    let mut iteration = 0;
+    while !termination.should_stop() {
+        // Each iteration returns the best tour of the worker's chain when it improved.
+        let batch = driver::batch_with(&mut workers, termination, trace, |_, chain| {
+            let Some(chain) = chain else {
+                let mut initial = constructive_phase(instance);
+                initial.eval(instance);
+                *chain = Some(Chain {
+                    current: initial.copy(),
+                    best: initial.copy(),
+                    temperature: schedule.initial_temperature,
+                });
+                return Some(initial);
+            };
+            let mut rng = crate::rng::thread_rng();
+
+            let mut candidate = chain.current.copy();
+            let removed = destroy(&mut candidate, instance, config, &mut rng);
+            repair(&mut candidate, instance, &removed);
+            candidate.eval(instance);
+
+            let mut improved = false;
+            let delta = candidate.total_distance - chain.current.total_distance;
+            if delta <= 0 || rng.gen::<f64>() < (-(delta as f64) / chain.temperature).exp() {
+                chain.current = candidate;
+                if chain.current.total_distance < chain.best.total_distance {
+                    chain.best = chain.current.copy();
+                    improved = true;
+                }
+            }
+
+            chain.temperature *= schedule.cooling_rate;
+            if chain.temperature < schedule.min_temperature {
+                chain.temperature = schedule.initial_temperature;
+                chain.current = chain.best.copy();
+            }
+            improved.then(|| chain.best.copy())
+        });
+
+        for (thread, improved) in batch {
+            iteration += 1;
+            let Some(solution) = improved else {
+                continue;
+            };
+            if best
+                .as_ref()
+                .is_none_or(|best| solution.total_distance < best.total_distance)
+            {
+                termination.report(solution.total_distance);
+                trace.record_improvement(iteration, thread, &solution, instance);
+                best = Some(solution);
+            }
+        }
+    }
+
+    best.expect("There should be at least one solution")
+}
+
+/// Removes cities from `solution` according to `config` and returns them. Fixed start and end
+/// cities are never removed, and at least two cities are left in the tour.
+fn destroy<R: Rng>(
+    solution: &mut Solution,
+    instance: &Instance,
+    config: &LnsConfig,
+    rng: &mut R,
+) -> Vec<usize> {
+    let first = usize::from(instance.start_city.is_some());
+    let end = instance.movable_end();
+    let mut candidates = solution.path[first..end].to_vec();
+    let count = config
+        .removal
+        .min(candidates.len())
+        .min(solution.path.len() - 2);
+
+    let removed: Vec<usize> = match config.destroy {
+        LnsDestroy::Random => candidates.choose_multiple(rng, count).copied().collect(),
+        LnsDestroy::Related => {
+            let seed = *candidates.choose(rng).unwrap();
+            if count > 0 && count < candidates.len() {
+                candidates.select_nth_unstable_by_key(count - 1, |&city| instance.dist(seed, city));
+            }
+            candidates.truncate(count);
+            candidates
+        }
+    };
+
+    let mut is_removed = vec![false; instance.num_cities];
+    for &city in &removed {
+        is_removed[city] = true;
+    }
+    solution.path.retain(|&city| !is_removed[city]);
+    removed
+}
+
+/// Reinserts `removed` into `solution` in random order, each at its cheapest position.
+fn repair(solution: &mut Solution, instance: &Instance, removed: &[usize]) {
+    let mut order = removed.to_vec();
+    order.shuffle(&mut crate::rng::thread_rng());
+
+    for city in order {
+        let (position, _) = (0..=solution.path.len())
+            .filter_map(|position| {
+                insertion_delta(instance, &solution.path, position, city)
+                    .map(|delta| (position, delta))
+            })
+            .min_by_key(|&(_, delta)| delta)
+            .expect("There should be at least one insertion position");
+        solution.path.insert(position, city);
+    }
+}
+
+/// Cost change of inserting `city` before `path[position]` (or after the last city when
+/// `position == path.len()`), or `None` if that would move a fixed endpoint.
+fn insertion_delta(
+    instance: &Instance,
+    path: &[usize],
+    position: usize,
+    city: usize,
+) -> Option<Cost> {
+    let len = path.len();
+    let first = path[0];
+    let last = path[len - 1];
+
+    if position == 0 {
+        // In a closed tour this is the same as appending after the last city.
+        if instance.start_city.is_some() || !instance.open_tour {
+            return None;
+        }
+        Some(instance.dist(city, first))
+    } else if position == len {
+        if instance.end_city.is_some() {
+            return None;
+        }
+        Some(
+            instance.dist(last, city) + instance.closing_distance(city, first)
+                - instance.closing_distance(last, first),
+        )
+    } else {
+        let (a, b) = (path[position - 1], path[position]);
+        Some(instance.dist(a, city) + instance.dist(city, b) - instance.dist(a, b))
+    }
+}
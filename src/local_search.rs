@@ -1,31 +1,291 @@
-use std::fs::File;
-use std::io::{BufWriter, Write};
-use std::time::Instant;
+use rayon::prelude::*;
+use std::collections::VecDeque;
 
-use crate::grasp::constructive_phase;
-use crate::instance::Instance;
+use crate::instance::{Cost, Instance};
 use crate::solution::Solution;
+use crate::tour::{ArrayTour, Tour};
+
+/// Improvement phase applied to a solution, pluggable into the GRASP loops.
+pub trait LocalSearch: Sync {
+    fn improve(&self, solution: &mut Solution, instance: &Instance);
+
+    /// Like `improve`, but returns early once `should_stop` is true, leaving a solution that may
+    /// not be a local optimum. Returns whether the search was cut short.
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        let _ = should_stop;
+        self.improve(solution, instance);
+        false
+    }
+
+    /// Short name used in reports.
+    fn name(&self) -> &str;
+}
+
+pub struct Swap;
+
+impl LocalSearch for Swap {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        local_search_swap(solution, instance, &|| false);
+    }
+
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        local_search_swap(solution, instance, should_stop)
+    }
+
+    fn name(&self) -> &str {
+        "Swap"
+    }
+}
+
+pub struct TwoOpt;
+
+impl LocalSearch for TwoOpt {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        local_search_2opt(solution, instance, &|| false);
+    }
+
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        local_search_2opt(solution, instance, should_stop)
+    }
+
+    fn name(&self) -> &str {
+        "2-opt"
+    }
+}
+
+/// 2-opt ranking moves by the bottleneck objective: the longest edge first, then the total.
+pub struct BottleneckTwoOpt;
+
+impl LocalSearch for BottleneckTwoOpt {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        local_search_2opt_bottleneck(solution, instance, &|| false);
+    }
+
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        local_search_2opt_bottleneck(solution, instance, should_stop)
+    }
+
+    fn name(&self) -> &str {
+        "2-opt (bottleneck)"
+    }
+}
+
+/// 2-opt ranking moves by the robust objective: the expected length plus the risk term.
+pub struct RobustTwoOpt;
+
+impl LocalSearch for RobustTwoOpt {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        local_search_2opt_robust(solution, instance, &|| false);
+    }
+
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        local_search_2opt_robust(solution, instance, should_stop)
+    }
+
+    fn name(&self) -> &str {
+        "2-opt (robust)"
+    }
+}
+
+/// 2-opt applying the best move of the whole neighbourhood at each step, with the moves
+/// evaluated in parallel.
+pub struct ParallelTwoOpt;
+
+impl LocalSearch for ParallelTwoOpt {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        local_search_2opt_best_parallel(solution, instance, &|| false);
+    }
+
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        local_search_2opt_best_parallel(solution, instance, should_stop)
+    }
+
+    fn name(&self) -> &str {
+        "2-opt (parallel best improvement)"
+    }
+}
+
+/// 2-opt restricted to the moves that add an edge between a city and one of its candidate
+/// neighbours.
+pub struct CandidateTwoOpt;
+
+impl LocalSearch for CandidateTwoOpt {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        local_search_2opt_candidates(solution, instance, &|| false);
+    }
+
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        local_search_2opt_candidates(solution, instance, should_stop)
+    }
+
+    fn name(&self) -> &str {
+        "2-opt (candidates)"
+    }
+}
+
+/// 2-opt combined with node insertion, evaluated over the same pair of edges.
+pub struct TwoHOpt;
+
+impl LocalSearch for TwoHOpt {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        local_search_2hopt(solution, instance, &|| false);
+    }
+
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        local_search_2hopt(solution, instance, should_stop)
+    }
+
+    fn name(&self) -> &str {
+        "2h-opt"
+    }
+}
+
+pub struct OrOpt;
+
+impl LocalSearch for OrOpt {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        local_search_oropt(solution, instance, &|| false);
+    }
+
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        local_search_oropt(solution, instance, should_stop)
+    }
+
+    fn name(&self) -> &str {
+        "Or-opt"
+    }
+}
+
+pub struct ThreeOpt;
+
+impl LocalSearch for ThreeOpt {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        local_search_3opt(solution, instance, &|| false);
+    }
+
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        local_search_3opt(solution, instance, should_stop)
+    }
+
+    fn name(&self) -> &str {
+        "3-opt"
+    }
+}
+
+/// Variable Neighborhood Descent over an ordered list of neighborhoods.
+pub struct Vnd {
+    pub neighborhoods: Vec<Box<dyn LocalSearch>>,
+}
+
+impl Default for Vnd {
+    fn default() -> Self {
+        Vnd {
+            neighborhoods: vec![
+                Box::new(Swap),
+                Box::new(TwoOpt),
+                Box::new(OrOpt),
+                Box::new(ThreeOpt),
+            ],
+        }
+    }
+}
+
+impl LocalSearch for Vnd {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        local_search_vnd(solution, instance, &self.neighborhoods, &|| false);
+    }
+
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        local_search_vnd(solution, instance, &self.neighborhoods, should_stop)
+    }
+
+    fn name(&self) -> &str {
+        "VND"
+    }
+}
 
 /// Local search implementation using Swap (1-opt)
-pub fn local_search_swap(solution: &mut Solution, instance: &Instance) {
+pub fn local_search_swap(
+    solution: &mut Solution,
+    instance: &Instance,
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
     let mut improvement = true;
 
     while improvement {
+        if should_stop() {
+            return true;
+        }
         improvement = false;
 
         for i in 1..solution.path.len() - 1 {
             for j in i + 1..solution.path.len() - 1 {
-                let current_cost = instance.distances[solution.path[i - 1]][solution.path[i]]
-                    + instance.distances[solution.path[i]][solution.path[i + 1]]
-                    + instance.distances[solution.path[j - 1]][solution.path[j]]
-                    + instance.distances[solution.path[j]][solution.path[j + 1]];
+                let current_cost = instance.dist(solution.path[i - 1], solution.path[i])
+                    + instance.dist(solution.path[i], solution.path[i + 1])
+                    + instance.dist(solution.path[j - 1], solution.path[j])
+                    + instance.dist(solution.path[j], solution.path[j + 1]);
 
                 solution.path.swap(i, j);
 
-                let new_cost = instance.distances[solution.path[i - 1]][solution.path[i]]
-                    + instance.distances[solution.path[i]][solution.path[i + 1]]
-                    + instance.distances[solution.path[j - 1]][solution.path[j]]
-                    + instance.distances[solution.path[j]][solution.path[j + 1]];
+                let new_cost = instance.dist(solution.path[i - 1], solution.path[i])
+                    + instance.dist(solution.path[i], solution.path[i + 1])
+                    + instance.dist(solution.path[j - 1], solution.path[j])
+                    + instance.dist(solution.path[j], solution.path[j + 1]);
 
                 if new_cost < current_cost {
                     improvement = true;
@@ -41,13 +301,22 @@ pub fn local_search_swap(solution: &mut Solution, instance: &Instance) {
             }
         }
     }
+
+    false
 }
 
 /// Local search implementation using 2-opt
-pub fn local_search_2opt(solution: &mut Solution, instance: &Instance) {
+pub fn local_search_2opt(
+    solution: &mut Solution,
+    instance: &Instance,
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
     let mut improvement = true;
 
     while improvement {
+        if should_stop() {
+            return true;
+        }
         improvement = false;
 
         for i in 1..solution.path.len() - 1 {
@@ -56,10 +325,10 @@ pub fn local_search_2opt(solution: &mut Solution, instance: &Instance) {
                     continue;
                 }
 
-                let current_cost = instance.distances[solution.path[i - 1]][solution.path[i]]
-                    + instance.distances[solution.path[j - 1]][solution.path[j]];
-                let new_cost = instance.distances[solution.path[i - 1]][solution.path[j - 1]]
-                    + instance.distances[solution.path[i]][solution.path[j]];
+                let current_cost = instance.dist(solution.path[i - 1], solution.path[i])
+                    + instance.dist(solution.path[j - 1], solution.path[j]);
+                let new_cost = instance.dist(solution.path[i - 1], solution.path[j - 1])
+                    + instance.dist(solution.path[i], solution.path[j]);
 
                 if new_cost < current_cost {
                     solution.path[i..j].reverse();
@@ -74,57 +343,436 @@ pub fn local_search_2opt(solution: &mut Solution, instance: &Instance) {
             }
         }
     }
+
+    false
+}
+
+/// Local search implementation using 2-opt under the bottleneck objective. A reversal is applied
+/// when both edges it adds are shorter than the longest edge of the tour and it either removes
+/// a longest edge or shortens the tour, so every move lowers the longest edge, the number of
+/// edges that long, or else the total, in that order.
+pub fn local_search_2opt_bottleneck(
+    solution: &mut Solution,
+    instance: &Instance,
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
+    let n = solution.path.len();
+    loop {
+        if should_stop() {
+            solution.eval(instance);
+            return true;
+        }
+        let path = &solution.path;
+        let edge = |i: usize| instance.dist(path[i - 1], path[i]);
+        let longest = (1..n)
+            .map(edge)
+            .fold(instance.closing_distance(path[n - 1], path[0]), Cost::max);
+
+        let mut reversal = None;
+        'search: for i in 1..n.saturating_sub(1) {
+            for j in i + 2..n {
+                let added = [
+                    instance.dist(path[i - 1], path[j - 1]),
+                    instance.dist(path[i], path[j]),
+                ];
+                if added.iter().any(|&distance| distance >= longest) {
+                    continue;
+                }
+                let removed = [edge(i), edge(j)];
+                if removed.contains(&longest) || added[0] + added[1] < removed[0] + removed[1] {
+                    reversal = Some((i, j));
+                    break 'search;
+                }
+            }
+        }
+
+        match reversal {
+            Some((i, j)) => solution.path[i..j].reverse(),
+            None => break,
+        }
+    }
+    solution.eval(instance);
+    false
+}
+
+/// Local search implementation using 2-opt under the robust objective. The variance of the tour
+/// length is the sum of the variances of its edges, so it is kept up to date along with the
+/// tour and each reversal is evaluated by its change of expected length plus that of the risk.
+pub fn local_search_2opt_robust(
+    solution: &mut Solution,
+    instance: &Instance,
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
+    let n = solution.path.len();
+    let mut variance = instance.tour_variance(&solution.path);
+    let mut improvement = true;
+    while improvement {
+        if should_stop() {
+            solution.eval(instance);
+            return true;
+        }
+        improvement = false;
+        let risk = instance.risk(variance);
+        let path = &solution.path;
+
+        let mut reversal = None;
+        'search: for i in 1..n.saturating_sub(1) {
+            for j in i + 2..n {
+                let (a, b, c, d) = (path[i - 1], path[i], path[j - 1], path[j]);
+                let mean_delta = instance.dist(a, c) + instance.dist(b, d)
+                    - instance.dist(a, b)
+                    - instance.dist(c, d);
+                let variance_delta = instance.variance(a, c) + instance.variance(b, d)
+                    - instance.variance(a, b)
+                    - instance.variance(c, d);
+                if mean_delta + instance.risk(variance + variance_delta) < risk {
+                    reversal = Some((i, j, variance_delta));
+                    break 'search;
+                }
+            }
+        }
+
+        if let Some((i, j, variance_delta)) = reversal {
+            solution.path[i..j].reverse();
+            variance += variance_delta;
+            improvement = true;
+        }
+    }
+    solution.eval(instance);
+    false
 }
 
-pub fn benchmark_local_search(instance: &Instance, instance_name: &str, num_runs: usize) {
-    let mut results = vec![];
+/// Best-improvement variant of `local_search_2opt` over the same moves: every reversal of the
+/// current tour is evaluated, split over the rayon pool by its first position, and the one that
+/// saves the most is applied, until none improves the tour.
+pub fn local_search_2opt_best_parallel(
+    solution: &mut Solution,
+    instance: &Instance,
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
+    let len = solution.path.len();
+    if len < 4 {
+        return false;
+    }
+
+    loop {
+        if should_stop() {
+            return true;
+        }
+        let path = &solution.path;
+        let best_move = (1..len - 1)
+            .into_par_iter()
+            .filter_map(|i| {
+                (i + 2..len)
+                    .map(|j| {
+                        let delta = instance.dist(path[i - 1], path[j - 1])
+                            + instance.dist(path[i], path[j])
+                            - instance.dist(path[i - 1], path[i])
+                            - instance.dist(path[j - 1], path[j]);
+                        (delta, i, j)
+                    })
+                    .min()
+            })
+            .min();
+
+        match best_move {
+            Some((delta, i, j)) if delta < 0 => {
+                solution.path[i..j].reverse();
+                solution.eval(instance);
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Local search implementation using 2-opt over the instance's candidate lists, on an
+/// `ArrayTour`. Cities whose edges changed are queued to be examined again, so each pass only
+/// looks at the part of the tour that moved. An open tour is searched as a cycle whose closing
+/// edge is never removed.
+pub fn local_search_2opt_candidates(
+    solution: &mut Solution,
+    instance: &Instance,
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
+    let n = solution.path.len();
+    if n < 5 {
+        let truncated = local_search_2opt(solution, instance, should_stop);
+        solution.eval(instance);
+        return truncated;
+    }
+
+    // The closing edge of an open tour is not part of it, and with both endpoints fixed the edge
+    // between them must stay in the tour, so neither is ever removed.
+    let (first, last) = (solution.path[0], solution.path[n - 1]);
+    let fixed_edge = if instance.open_tour {
+        Some((first, last))
+    } else {
+        instance.start_city.zip(instance.end_city)
+    };
+    let removable = |a: usize, b: usize| {
+        fixed_edge.is_none_or(|(start, end)| (a, b) != (start, end) && (a, b) != (end, start))
+    };
 
-    for run in 1..=num_runs {
-        println!("\n=== Run {} for 2-opt ===", run);
-        let mut solution_2opt = constructive_phase(instance);
-        let start_2opt = Instant::now();
-        local_search_2opt(&mut solution_2opt, instance);
-        let duration_2opt = start_2opt.elapsed();
+    let candidates = instance.candidates();
+    let mut tour = ArrayTour::from(&*solution);
+    let mut queue: VecDeque<usize> = solution.path.iter().copied().collect();
+    let mut queued = vec![true; n];
+    let mut truncated = false;
 
-        println!(
-            "2-opt: Distance = {}, Time = {:.2?}",
-            solution_2opt.total_distance, duration_2opt
-        );
+    while let Some(a) = queue.pop_front() {
+        if truncated {
+            break;
+        }
+        queued[a] = false;
 
-        println!("\n=== Run {} for Swap ===", run);
-        let mut solution_swap = constructive_phase(instance);
-        let start_swap = Instant::now();
-        local_search_swap(&mut solution_swap, instance);
-        let duration_swap = start_swap.elapsed();
+        'moves: for forward in [true, false] {
+            let step = |city| {
+                if forward {
+                    tour.next(city)
+                } else {
+                    tour.prev(city)
+                }
+            };
+            let a_next = step(a);
+            let a_edge = instance.dist(a, a_next);
 
-        println!(
-            "Swap: Distance = {}, Time = {:.2?}",
-            solution_swap.total_distance, duration_swap
-        );
+            for &c in candidates.neighbors(a) {
+                let partial_gain = a_edge - instance.dist(a, c);
+                if partial_gain <= 0 {
+                    break;
+                }
+                let c_next = step(c);
+                if c == a_next || c_next == a {
+                    continue;
+                }
+                let gain = partial_gain + instance.dist(c, c_next) - instance.dist(a_next, c_next);
+                if gain <= 0 || !removable(a, a_next) || !removable(c, c_next) {
+                    continue;
+                }
+
+                if forward {
+                    tour.two_opt_move(a, c);
+                } else {
+                    tour.two_opt_move(a_next, c_next);
+                }
+                for city in [a, a_next, c, c_next] {
+                    if !queued[city] {
+                        queued[city] = true;
+                        queue.push_back(city);
+                    }
+                }
+                truncated = should_stop();
+                break 'moves;
+            }
+        }
+    }
 
-        results.push((
-            run,
-            solution_2opt.total_distance,
-            duration_2opt.as_micros(),
-            solution_swap.total_distance,
-            duration_swap.as_micros(),
-        ));
+    if instance.open_tour {
+        // Unroll the cycle at its closing edge, keeping the endpoints in place.
+        solution.path = tour.path_from(first);
+        if tour.next(first) == last {
+            solution.path[1..].reverse();
+        }
+        solution.eval(instance);
+    } else {
+        *solution = tour.to_solution(instance);
     }
+    truncated
+}
 
-    let file_path = format!("{}_benchmark_results.csv", instance_name);
-    let file = File::create(&file_path).expect("Unable to create file");
-    let mut writer = BufWriter::new(file);
+/// Local search implementation using 2h-opt: for each pair of edges `(a, b)` and `(c, d)`, tries
+/// the 2-opt exchange along with moving `b` between `c` and `d` and moving `c` between `a` and
+/// `b`, and applies the first improving move.
+pub fn local_search_2hopt(
+    solution: &mut Solution,
+    instance: &Instance,
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
+    let mut improvement = true;
 
-    writeln!(
-        writer,
-        "Run,2-opt Distance,2-opt Time (µs),Swap Distance,Swap Time (µs)"
-    )
-    .expect("Failed to write header to CSV");
+    while improvement {
+        if should_stop() {
+            return true;
+        }
+        improvement = false;
 
-    for (run, d2opt, t2opt, dswap, tswap) in results {
-        writeln!(writer, "{},{},{},{},{}", run, d2opt, t2opt, dswap, tswap)
-            .expect("Failed to write results to CSV");
+        'search: for i in 1..solution.path.len() - 1 {
+            for j in i + 2..solution.path.len() {
+                let a = solution.path[i - 1];
+                let b = solution.path[i];
+                let c = solution.path[j - 1];
+                let d = solution.path[j];
+                let ab = instance.dist(a, b);
+                let cd = instance.dist(c, d);
+
+                // 2-opt: reverse the path from b to c.
+                if instance.dist(a, c) + instance.dist(b, d) < ab + cd {
+                    solution.path[i..j].reverse();
+                    solution.eval(instance);
+                    improvement = true;
+                    break 'search;
+                }
+
+                // Move b between c and d.
+                let after_b = solution.path[i + 1];
+                let removal_gain = ab + instance.dist(b, after_b) - instance.dist(a, after_b);
+                let insertion_cost = instance.dist(c, b) + instance.dist(b, d) - cd;
+                if insertion_cost < removal_gain {
+                    solution.path[i..j].rotate_left(1);
+                    solution.eval(instance);
+                    improvement = true;
+                    break 'search;
+                }
+
+                // Move c between a and b.
+                let before_c = solution.path[j - 2];
+                let removal_gain = instance.dist(before_c, c) + cd - instance.dist(before_c, d);
+                let insertion_cost = instance.dist(a, c) + instance.dist(c, b) - ab;
+                if insertion_cost < removal_gain {
+                    solution.path[i..j].rotate_right(1);
+                    solution.eval(instance);
+                    improvement = true;
+                    break 'search;
+                }
+            }
+        }
+    }
+
+    false
+}
+
+/// Local search implementation using Or-opt (relocation of segments of up to 3 cities)
+pub fn local_search_oropt(
+    solution: &mut Solution,
+    instance: &Instance,
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
+    let n = solution.path.len();
+    let end = instance.movable_end();
+    let mut improvement = true;
+
+    while improvement {
+        if should_stop() {
+            return true;
+        }
+        improvement = false;
+
+        'search: for len in 1..=3 {
+            for i in 1..end.saturating_sub(len) + 1 {
+                let prev = solution.path[i - 1];
+                let first = solution.path[i];
+                let last = solution.path[i + len - 1];
+                let next = solution.path[(i + len) % n];
+                let removal_gain = if i + len == n {
+                    instance.dist(prev, first) + instance.closing_distance(last, next)
+                        - instance.closing_distance(prev, next)
+                } else {
+                    instance.dist(prev, first) + instance.dist(last, next)
+                        - instance.dist(prev, next)
+                };
+
+                for j in (0..i.saturating_sub(1)).chain(i + len..end) {
+                    let a = solution.path[j];
+                    let b = solution.path[(j + 1) % n];
+                    let insertion_cost = if j + 1 == n {
+                        instance.dist(a, first) + instance.closing_distance(last, b)
+                            - instance.closing_distance(a, b)
+                    } else {
+                        instance.dist(a, first) + instance.dist(last, b) - instance.dist(a, b)
+                    };
+
+                    if insertion_cost < removal_gain {
+                        if j > i {
+                            solution.path[i..=j].rotate_left(len);
+                        } else {
+                            solution.path[j + 1..i + len].rotate_right(len);
+                        }
+                        solution.eval(instance);
+                        improvement = true;
+                        break 'search;
+                    }
+                }
+            }
+        }
+    }
+
+    false
+}
+
+/// Variable Neighborhood Descent: applies each neighborhood in order and restarts from the
+/// first one whenever any of them improves the solution.
+pub fn local_search_vnd(
+    solution: &mut Solution,
+    instance: &Instance,
+    neighborhoods: &[Box<dyn LocalSearch>],
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
+    solution.eval(instance);
+
+    let mut k = 0;
+    while k < neighborhoods.len() {
+        let before = solution.total_distance;
+        if neighborhoods[k].improve_until(solution, instance, should_stop) {
+            return true;
+        }
+
+        if solution.total_distance < before {
+            k = 0;
+        } else {
+            k += 1;
+        }
+    }
+
+    false
+}
+
+/// Local search implementation using 3-opt segment exchange (no reversals)
+pub fn local_search_3opt(
+    solution: &mut Solution,
+    instance: &Instance,
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
+    let n = solution.path.len();
+    let end = instance.movable_end();
+    let mut improvement = true;
+
+    while improvement {
+        if should_stop() {
+            return true;
+        }
+        improvement = false;
+
+        'search: for i in 1..end - 1 {
+            for j in i + 1..end {
+                for k in j + 1..=end {
+                    let a = solution.path[i - 1];
+                    let b = solution.path[i];
+                    let c = solution.path[j - 1];
+                    let d = solution.path[j];
+                    let e = solution.path[k - 1];
+                    let f = solution.path[k % n];
+
+                    let (ef, cf) = if k == n {
+                        (
+                            instance.closing_distance(e, f),
+                            instance.closing_distance(c, f),
+                        )
+                    } else {
+                        (instance.dist(e, f), instance.dist(c, f))
+                    };
+                    let current_cost = instance.dist(a, b) + instance.dist(c, d) + ef;
+                    let new_cost = instance.dist(a, d) + instance.dist(e, b) + cf;
+
+                    if new_cost < current_cost {
+                        solution.path[i..k].rotate_left(j - i);
+                        solution.eval(instance);
+                        improvement = true;
+                        break 'search;
+                    }
+                }
+            }
+        }
     }
 
-    println!("Benchmark results saved to {}", file_path);
+    false
 }
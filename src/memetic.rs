@@ -0,0 +1,151 @@
+use crate::construction::Constructive;
+use crate::driver;
+use crate::grasp::{update_elite_set, ReplacementPolicy};
+use crate::instance::{Cost, Instance};
+use crate::local_search::LocalSearch;
+use crate::objective::Objective;
+use crate::solution::Solution;
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use rand::Rng;
+
+/// Parameters of the Memetic variant.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemeticConfig {
+    pub population_size: usize,
+    pub min_diversity: f64,
+    pub replacement_policy: ReplacementPolicy,
+}
+
+/// Memetic algorithm: order crossover between population members followed by local search
+/// education. The population is maintained with the same diversity rules as the elite set.
+pub fn memetic(
+    instance: &Instance,
+    termination: &Termination,
+    construction: &dyn Constructive,
+    local_search: &dyn LocalSearch,
+    config: &MemeticConfig,
+    trace: &Trace,
+) -> Solution {
+    let MemeticConfig {
+        population_size,
+        min_diversity,
+        replacement_policy,
+    } = *config;
+    let min_difference = (instance.num_cities as f64 * min_diversity).round() as usize;
+    let mut population: Vec<Solution> = Vec::with_capacity(population_size);
+    let mut best_score = (Cost::MAX, Cost::MAX);
+
+    let educate = |mut solution: Solution| {
+        if local_search.improve_until(&mut solution, instance, &|| termination.should_stop()) {
+            trace.count_truncated();
+        }
+        solution.eval(instance);
+        solution
+    };
+
+    let mut attempts = 0;
+    while population.len() < population_size
+        && attempts < 10 * population_size
+        && !termination.should_stop()
+    {
+        let batch = driver::batch(termination, trace, |_| {
+            educate(construction.construct(instance))
+        });
+        for (_, solution) in batch {
+            update_elite_set(
+                &mut population,
+                solution,
+                population_size,
+                min_difference,
+                replacement_policy,
+                instance.objective,
+            );
+        }
+        attempts += rayon::current_num_threads();
+    }
+
+    let mut generation = 0;
+    while !termination.should_stop() {
+        generation += 1;
+        let offspring = driver::batch(termination, trace, |_| {
+            let mut rng = crate::rng::thread_rng();
+            let parent1 = tournament(&population, instance.objective, &mut rng);
+            let parent2 = tournament(&population, instance.objective, &mut rng);
+            let mut child = order_crossover(parent1, parent2, &mut rng);
+            child.fix_endpoints(instance);
+            educate(child)
+        });
+
+        for (thread, child) in offspring {
+            let score = instance.objective.key(&child);
+            if score < best_score {
+                best_score = score;
+                termination.report(instance.objective.value(&child));
+                trace.record_improvement(generation, thread, &child, instance);
+            }
+            update_elite_set(
+                &mut population,
+                child,
+                population_size,
+                min_difference,
+                replacement_policy,
+                instance.objective,
+            );
+        }
+        trace.publish_elite(&population);
+    }
+
+    population
+        .into_iter()
+        .min_by_key(|s| instance.objective.key(s))
+        .expect("There should be at least one solution")
+}
+
+/// Binary tournament selection under `objective`.
+fn tournament<'a, R: Rng>(
+    population: &'a [Solution],
+    objective: Objective,
+    rng: &mut R,
+) -> &'a Solution {
+    let a = &population[rng.gen_range(0..population.len())];
+    let b = &population[rng.gen_range(0..population.len())];
+    if !objective.better(b, a) {
+        a
+    } else {
+        b
+    }
+}
+
+/// Order crossover (OX): keeps a random segment of `parent1` in place and fills the remaining
+/// positions with the missing cities in the order they appear in `parent2`.
+pub fn order_crossover<R: Rng>(parent1: &Solution, parent2: &Solution, rng: &mut R) -> Solution {
+    let n = parent1.path.len();
+    let mut a = rng.gen_range(0..n);
+    let mut b = rng.gen_range(0..n);
+    if a > b {
+        std::mem::swap(&mut a, &mut b);
+    }
+
+    let mut child = Solution::new(n);
+    child.path = vec![usize::MAX; n];
+    let mut used = vec![false; n];
+    for i in a..=b {
+        child.path[i] = parent1.path[i];
+        used[parent1.path[i]] = true;
+    }
+
+    let mut position = (b + 1) % n;
+    for offset in 0..n {
+        let city = parent2.path[(b + 1 + offset) % n];
+        if !used[city] {
+            child.path[position] = city;
+            used[city] = true;
+            position = (position + 1) % n;
+        }
+    }
+
+    child
+}
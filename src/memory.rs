@@ -0,0 +1,112 @@
+use crate::error::{Error, ErrorKind};
+use crate::instance::Cost;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::warn;
+
+/// Fraction of the available memory above which building a distance matrix is warned about.
+const WARN_FRACTION: f64 = 0.8;
+
+/// Largest distance matrix, in bytes, that may be built (see `--max-memory`).
+static MAX_MATRIX_BYTES: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// Caps the memory of the distance matrices built from now on to `bytes`.
+pub fn set_max_memory(bytes: u64) {
+    MAX_MATRIX_BYTES.store(bytes, Ordering::Relaxed);
+}
+
+/// Bytes of a full distance matrix over `num_cities` cities.
+pub fn matrix_bytes(num_cities: usize) -> u64 {
+    (num_cities as u64)
+        .saturating_mul(num_cities as u64)
+        .saturating_mul(size_of::<Cost>() as u64)
+}
+
+/// Whether a distance matrix over `num_cities` cities fits in the `--max-memory` cap.
+pub fn matrix_fits(num_cities: usize) -> bool {
+    matrix_bytes(num_cities) <= MAX_MATRIX_BYTES.load(Ordering::Relaxed)
+}
+
+/// Checks a distance matrix over `num_cities` cities before it is built: fails if it exceeds
+/// the `--max-memory` cap, and warns if it takes most of the available memory.
+pub fn check_matrix(num_cities: usize) -> Result<(), Error> {
+    let bytes = matrix_bytes(num_cities);
+    if !matrix_fits(num_cities) {
+        return Err(Error::new(
+            ErrorKind::MemoryLimit,
+            format!(
+                "The distance matrix of {} cities needs {}, above the --max-memory cap of {}",
+                num_cities,
+                format_bytes(bytes),
+                format_bytes(MAX_MATRIX_BYTES.load(Ordering::Relaxed))
+            ),
+        ));
+    }
+    if let Some(available) = available_memory() {
+        if bytes as f64 > WARN_FRACTION * available as f64 {
+            warn!(
+                "The distance matrix of {} cities needs {} but only {} of memory is available",
+                num_cities,
+                format_bytes(bytes),
+                format_bytes(available)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Memory available to new allocations (`MemAvailable` in `/proc/meminfo`), on Linux.
+pub fn available_memory() -> Option<u64> {
+    proc_field("/proc/meminfo", "MemAvailable:")
+}
+
+/// Peak resident set size of the process (`VmHWM` in `/proc/self/status`), on Linux.
+pub fn peak_rss() -> Option<u64> {
+    proc_field("/proc/self/status", "VmHWM:")
+}
+
+/// Value in bytes of a `<field> <n> kB` line of a `/proc` file.
+fn proc_field(path: &str, field: &str) -> Option<u64> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let line = content.lines().find(|line| line.starts_with(field))?;
+    let kilobytes: u64 = line[field.len()..]
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Parses a size in bytes with an optional binary unit, such as `512M`, `4GiB` or `1000000`.
+pub fn parse_size(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid size: {}", text))?;
+    let shift = match unit.trim().trim_end_matches("iB").trim_end_matches('B') {
+        "" => 0,
+        "K" | "k" => 10,
+        "M" => 20,
+        "G" => 30,
+        "T" => 40,
+        _ => return Err(format!("unknown size unit: {}", unit)),
+    };
+    Ok((number * (1u64 << shift) as f64) as u64)
+}
+
+/// `bytes` in the largest binary unit that keeps it at least 1.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
@@ -0,0 +1,260 @@
+use crate::cli::SolveArgs;
+use crate::construction::Constructive;
+use crate::driver;
+use crate::grasp::{update_elite_set, ReplacementPolicy, StaticPrConfig};
+use crate::instance::Instance;
+use crate::local_search::LocalSearch;
+use crate::objective::Objective;
+use crate::solution::Solution;
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use rand::Rng;
+use std::sync::RwLock;
+use tracing::trace;
+
+/// Variant pluggable into `run`, the parallel driver behind `--variant custom`: every iteration
+/// builds a tour with `construct` and improves it with `improve`, and after every batch of
+/// iterations `intensify` may derive more tours from the elite set.
+pub trait Metaheuristic: Sync {
+    fn construct(&self, instance: &Instance) -> Solution;
+
+    /// Improves `solution`, returning early once `should_stop` is true. Returns whether it was
+    /// cut short.
+    fn improve(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        let _ = (solution, instance, should_stop);
+        false
+    }
+
+    /// Tours derived from the `elite` set, which is never empty, such as by path relinking.
+    /// They go through the same selection as the tours of the iterations.
+    fn intensify(
+        &self,
+        elite: &[Solution],
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> Vec<Solution> {
+        let _ = (elite, instance, should_stop);
+        Vec::new()
+    }
+
+    /// Whether the metaheuristic ranks tours by `objective`. Those that only minimize the
+    /// total length reject the other objectives.
+    fn supports(&self, objective: Objective) -> bool {
+        objective == Objective::Total
+    }
+
+    /// Short name used in reports.
+    fn name(&self) -> &str;
+}
+
+/// Builds a metaheuristic configured by the solver options.
+pub type Factory = fn(&SolveArgs) -> Box<dyn Metaheuristic>;
+
+/// Metaheuristics registered on top of the built-in ones, by name.
+static REGISTRY: RwLock<Vec<(String, Factory)>> = RwLock::new(Vec::new());
+
+/// The built-in metaheuristics, which registered ones of the same name replace.
+const BUILTIN: [(&str, Factory); 2] = [
+    ("grasp", |args| {
+        Box::new(Grasp {
+            construction: args.construction(),
+            local_search: args.local_search(),
+            relinking: None,
+        })
+    }),
+    ("grasp-pr", |args| {
+        Box::new(Grasp {
+            construction: args.construction(),
+            local_search: args.local_search(),
+            relinking: Some(args.static_pr_config()),
+        })
+    }),
+];
+
+/// Makes the metaheuristic built by `factory` selectable as `--metaheuristic <name>`.
+pub fn register(name: &str, factory: Factory) {
+    let mut registry = REGISTRY.write().unwrap();
+    registry.retain(|(registered, _)| registered != name);
+    registry.push((name.to_string(), factory));
+}
+
+/// The metaheuristic registered as `name`, configured by `args`.
+pub fn create(name: &str, args: &SolveArgs) -> Option<Box<dyn Metaheuristic>> {
+    let factory = REGISTRY
+        .read()
+        .unwrap()
+        .iter()
+        .find(|(registered, _)| registered == name)
+        .map(|&(_, factory)| factory)
+        .or_else(|| {
+            BUILTIN
+                .iter()
+                .find(|(builtin, _)| *builtin == name)
+                .map(|&(_, factory)| factory)
+        })?;
+    Some(factory(args))
+}
+
+/// Names of the metaheuristics that can be created, built-in ones first.
+pub fn names() -> Vec<String> {
+    let registry = REGISTRY.read().unwrap();
+    let mut names: Vec<String> = BUILTIN.iter().map(|(name, _)| name.to_string()).collect();
+    for (name, _) in registry.iter() {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    names
+}
+
+/// Parameters of the elite set kept by `run`.
+#[derive(Debug, Clone, Copy)]
+pub struct DriverConfig {
+    pub elite_size: usize,
+    pub min_diversity: f64,
+    pub replacement_policy: ReplacementPolicy,
+}
+
+/// Runs `metaheuristic` until the stop criteria are met, one iteration per thread at a time,
+/// keeping the best tour, which is returned, and an elite set for `intensify`.
+pub fn run(
+    instance: &Instance,
+    termination: &Termination,
+    metaheuristic: &dyn Metaheuristic,
+    config: &DriverConfig,
+    trace: &Trace,
+) -> Solution {
+    let min_difference = (instance.num_cities as f64 * config.min_diversity).round() as usize;
+    let should_stop = || termination.should_stop();
+    let mut best: Option<Solution> = None;
+    let mut elite_set: Vec<Solution> = Vec::with_capacity(config.elite_size);
+
+    let mut iteration = 0;
+    while !termination.should_stop() {
+        let mut solutions = driver::batch(termination, trace, |_| {
+            let mut solution = metaheuristic.construct(instance);
+            solution.eval(instance);
+            if metaheuristic.improve(&mut solution, instance, &should_stop) {
+                trace.count_truncated();
+            }
+            solution.eval(instance);
+            solution
+        });
+        if !elite_set.is_empty() && !termination.should_stop() {
+            for mut solution in metaheuristic.intensify(&elite_set, instance, &should_stop) {
+                solution.eval(instance);
+                trace.count_iteration(0);
+                termination.count_iteration();
+                solutions.push((0, solution));
+            }
+        }
+
+        for (thread, solution) in solutions {
+            iteration += 1;
+            trace!(
+                iteration,
+                thread,
+                distance = solution.total_distance,
+                "Iteration done"
+            );
+            debug_assert_eq!(solution.validate(instance), Ok(()));
+
+            if best
+                .as_ref()
+                .is_none_or(|best| instance.objective.better(&solution, best))
+            {
+                termination.report(instance.objective.value(&solution));
+                trace.record_improvement(iteration, thread, &solution, instance);
+                best = Some(solution.copy());
+            }
+            update_elite_set(
+                &mut elite_set,
+                solution,
+                config.elite_size,
+                min_difference,
+                config.replacement_policy,
+                instance.objective,
+            );
+        }
+        trace.publish_elite(&elite_set);
+    }
+
+    best.unwrap_or_else(|| {
+        panic!(
+            "{} should return at least one solution",
+            metaheuristic.name()
+        )
+    })
+}
+
+/// GRASP as a metaheuristic; with `relinking`, every batch also relinks the best elite tour
+/// with another, drawn at random, and improves the result.
+struct Grasp {
+    construction: Box<dyn Constructive>,
+    local_search: Box<dyn LocalSearch>,
+    relinking: Option<StaticPrConfig>,
+}
+
+impl Metaheuristic for Grasp {
+    fn construct(&self, instance: &Instance) -> Solution {
+        self.construction.construct(instance)
+    }
+
+    fn improve(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        self.local_search
+            .improve_until(solution, instance, should_stop)
+    }
+
+    fn intensify(
+        &self,
+        elite: &[Solution],
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> Vec<Solution> {
+        let Some(config) = &self.relinking else {
+            return Vec::new();
+        };
+        if elite.len() < 2 {
+            return Vec::new();
+        }
+        let best = (0..elite.len())
+            .min_by_key(|&i| instance.objective.key(&elite[i]))
+            .unwrap();
+        let other = (best + crate::rng::thread_rng().gen_range(1..elite.len())) % elite.len();
+        let mut solution = elite[best].copy();
+        solution.path_relinking(
+            &elite[other],
+            instance,
+            &*self.local_search,
+            config.pr_depth,
+            config.pr_local_search,
+            config.pr_stop,
+            config.pr_selection,
+        );
+        self.local_search
+            .improve_until(&mut solution, instance, should_stop);
+        vec![solution]
+    }
+
+    fn supports(&self, _objective: Objective) -> bool {
+        true
+    }
+
+    fn name(&self) -> &str {
+        match self.relinking {
+            Some(_) => "GRASP+PR",
+            None => "GRASP",
+        }
+    }
+}
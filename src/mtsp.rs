@@ -0,0 +1,299 @@
+use crate::driver;
+use crate::error::{Error, ErrorKind};
+use crate::instance::{Cost, Instance};
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use clap::ValueEnum;
+use tracing::info;
+
+/// Objective minimized by the multiple-salesmen variant.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MtspObjective {
+    /// Sum of the lengths of all routes.
+    Total,
+    /// Length of the longest route, ties broken by the total.
+    MinMax,
+}
+
+/// Routes of several salesmen leaving from and returning to a common depot. Every route visits
+/// at least one city and the depot is not stored in the routes.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiSolution {
+    pub depot: usize,
+    pub routes: Vec<Vec<usize>>,
+    pub route_lengths: Vec<Cost>,
+}
+
+impl MultiSolution {
+    pub fn eval(&mut self, instance: &Instance) {
+        self.route_lengths = self
+            .routes
+            .iter()
+            .map(|route| route_length(instance, self.depot, route))
+            .collect();
+    }
+
+    pub fn total_distance(&self) -> Cost {
+        self.route_lengths.iter().sum()
+    }
+
+    pub fn longest_route(&self) -> Cost {
+        self.route_lengths.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Value of `objective`, as a pair compared lexicographically.
+    pub fn cost(&self, objective: MtspObjective) -> (Cost, Cost) {
+        cost(&self.route_lengths, objective)
+    }
+}
+
+fn cost(route_lengths: &[Cost], objective: MtspObjective) -> (Cost, Cost) {
+    let total = route_lengths.iter().sum();
+    let longest = route_lengths.iter().copied().max().unwrap_or(0);
+    match objective {
+        MtspObjective::Total => (total, longest),
+        MtspObjective::MinMax => (longest, total),
+    }
+}
+
+/// Length of the closed route depot -> `route` -> depot.
+fn route_length(instance: &Instance, depot: usize, route: &[usize]) -> Cost {
+    let mut length = 0;
+    let mut previous = depot;
+    for &city in route {
+        length += instance.dist(previous, city);
+        previous = city;
+    }
+    length + instance.dist(previous, depot)
+}
+
+/// Cities before and after position `i` of `route`, the depot at either end.
+fn neighbors(route: &[usize], depot: usize, i: usize) -> (usize, usize) {
+    let prev = if i == 0 { depot } else { route[i - 1] };
+    let next = if i + 1 == route.len() {
+        depot
+    } else {
+        route[i + 1]
+    };
+    (prev, next)
+}
+
+/// Cities between which a city inserted at position `p` of `route` would be placed.
+fn insertion_neighbors(route: &[usize], depot: usize, p: usize) -> (usize, usize) {
+    let prev = if p == 0 { depot } else { route[p - 1] };
+    let next = if p == route.len() { depot } else { route[p] };
+    (prev, next)
+}
+
+/// Randomized nearest neighbor for several salesmen: the route that is currently shortest is
+/// extended with a city picked from the closest third of the remaining ones.
+pub fn constructive_phase_mtsp(
+    instance: &Instance,
+    depot: usize,
+    salesmen: usize,
+) -> MultiSolution {
+    let mut remaining: Vec<usize> = (0..instance.num_cities).filter(|&c| c != depot).collect();
+    let mut routes: Vec<Vec<usize>> = vec![Vec::new(); salesmen];
+    let mut lengths = vec![0; salesmen];
+
+    while !remaining.is_empty() {
+        let r = (0..salesmen)
+            .min_by_key(|&r| (!routes[r].is_empty(), lengths[r]))
+            .unwrap();
+        let last_city = *routes[r].last().unwrap_or(&depot);
+
+        let mut candidates: Vec<(usize, Cost)> = remaining
+            .iter()
+            .map(|&city| (city, instance.dist(last_city, city)))
+            .collect();
+        candidates.sort_by_key(|&(_, dist)| dist);
+
+        let k = (candidates.len() as f32 / 3.0).ceil() as usize;
+        let (next_city, dist) = candidates[crate::rng::random::<usize>() % k];
+
+        remaining.retain(|&x| x != next_city);
+        routes[r].push(next_city);
+        lengths[r] += dist;
+    }
+
+    let mut solution = MultiSolution {
+        depot,
+        routes,
+        route_lengths: Vec::new(),
+    };
+    solution.eval(instance);
+    solution
+}
+
+/// First-improvement local search combining 2-opt inside each route with relocation of a city
+/// to another route and exchange of two cities between routes.
+pub fn local_search_mtsp(
+    solution: &mut MultiSolution,
+    instance: &Instance,
+    objective: MtspObjective,
+) {
+    while two_opt_routes(solution, instance)
+        || relocate(solution, instance, objective)
+        || exchange(solution, instance, objective)
+    {}
+}
+
+/// Applies the first improving 2-opt move found inside a route. Shortening a route never
+/// worsens either objective.
+fn two_opt_routes(solution: &mut MultiSolution, instance: &Instance) -> bool {
+    let depot = solution.depot;
+    for (route, length) in solution.routes.iter_mut().zip(&mut solution.route_lengths) {
+        let len = route.len();
+        let city = |route: &[usize], k: usize| {
+            if k == 0 || k == len + 1 {
+                depot
+            } else {
+                route[k - 1]
+            }
+        };
+
+        for i in 1..=len {
+            for j in i + 2..=len + 1 {
+                let (a, b) = (city(route, i - 1), city(route, i));
+                let (c, d) = (city(route, j - 1), city(route, j));
+                let delta = instance.dist(a, c) + instance.dist(b, d)
+                    - instance.dist(a, b)
+                    - instance.dist(c, d);
+
+                if delta < 0 {
+                    route[i - 1..j - 1].reverse();
+                    *length = route_length(instance, depot, route);
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Applies the first move of one city to another route that improves the objective.
+fn relocate(solution: &mut MultiSolution, instance: &Instance, objective: MtspObjective) -> bool {
+    let depot = solution.depot;
+    let current = solution.cost(objective);
+    let mut lengths = solution.route_lengths.clone();
+
+    for r1 in 0..solution.routes.len() {
+        if solution.routes[r1].len() < 2 {
+            continue;
+        }
+        for i in 0..solution.routes[r1].len() {
+            let city = solution.routes[r1][i];
+            let (prev, next) = neighbors(&solution.routes[r1], depot, i);
+            let removal_gain =
+                instance.dist(prev, city) + instance.dist(city, next) - instance.dist(prev, next);
+
+            for r2 in (0..solution.routes.len()).filter(|&r2| r2 != r1) {
+                for p in 0..=solution.routes[r2].len() {
+                    let (a, b) = insertion_neighbors(&solution.routes[r2], depot, p);
+                    let insertion_cost =
+                        instance.dist(a, city) + instance.dist(city, b) - instance.dist(a, b);
+
+                    lengths[r1] = solution.route_lengths[r1] - removal_gain;
+                    lengths[r2] = solution.route_lengths[r2] + insertion_cost;
+                    if cost(&lengths, objective) < current {
+                        solution.routes[r1].remove(i);
+                        solution.routes[r2].insert(p, city);
+                        solution.route_lengths = lengths;
+                        return true;
+                    }
+                    lengths[r2] = solution.route_lengths[r2];
+                }
+            }
+            lengths[r1] = solution.route_lengths[r1];
+        }
+    }
+    false
+}
+
+/// Applies the first exchange of two cities between different routes that improves the
+/// objective.
+fn exchange(solution: &mut MultiSolution, instance: &Instance, objective: MtspObjective) -> bool {
+    let depot = solution.depot;
+    let current = solution.cost(objective);
+    let mut lengths = solution.route_lengths.clone();
+    let replace_delta = |route: &[usize], i: usize, city: usize| {
+        let (prev, next) = neighbors(route, depot, i);
+        instance.dist(prev, city) + instance.dist(city, next)
+            - instance.dist(prev, route[i])
+            - instance.dist(route[i], next)
+    };
+
+    for r1 in 0..solution.routes.len() {
+        for r2 in r1 + 1..solution.routes.len() {
+            for i in 0..solution.routes[r1].len() {
+                for j in 0..solution.routes[r2].len() {
+                    let (x, y) = (solution.routes[r1][i], solution.routes[r2][j]);
+                    lengths[r1] =
+                        solution.route_lengths[r1] + replace_delta(&solution.routes[r1], i, y);
+                    lengths[r2] =
+                        solution.route_lengths[r2] + replace_delta(&solution.routes[r2], j, x);
+
+                    if cost(&lengths, objective) < current {
+                        solution.routes[r1][i] = y;
+                        solution.routes[r2][j] = x;
+                        solution.route_lengths = lengths;
+                        return true;
+                    }
+                }
+            }
+            lengths[r1] = solution.route_lengths[r1];
+            lengths[r2] = solution.route_lengths[r2];
+        }
+    }
+    false
+}
+
+/// GRASP for the multiple-salesmen TSP: each worker builds routes for `salesmen` salesmen
+/// starting at `depot` and improves them with `local_search_mtsp` until the time limit.
+pub fn mtsp(
+    instance: &Instance,
+    termination: &Termination,
+    depot: usize,
+    salesmen: usize,
+    objective: MtspObjective,
+    trace: &Trace,
+) -> Result<MultiSolution, Error> {
+    if salesmen == 0 || salesmen >= instance.num_cities {
+        return Err(Error::new(
+            ErrorKind::InvalidArguments,
+            format!(
+                "The number of salesmen must be between 1 and {}",
+                instance.num_cities - 1
+            ),
+        ));
+    }
+    let mut best: Option<MultiSolution> = None;
+
+    let mut iteration = 0;
+    while !termination.should_stop() {
+        let batch = driver::batch(termination, trace, |_| {
+            let mut solution = constructive_phase_mtsp(instance, depot, salesmen);
+            local_search_mtsp(&mut solution, instance, objective);
+            solution
+        });
+
+        for (thread, solution) in batch {
+            iteration += 1;
+            if best
+                .as_ref()
+                .is_none_or(|best| solution.cost(objective) < best.cost(objective))
+            {
+                let (score, _) = solution.cost(objective);
+                termination.report(score);
+                trace.record(iteration, thread, score);
+                info!("Improved distance = {}", instance.format_cost(score));
+                best = Some(solution);
+            }
+        }
+    }
+
+    Ok(best.expect("There should be at least one solution"))
+}
@@ -0,0 +1,41 @@
+use crate::instance::Cost;
+use crate::solution::Solution;
+
+use clap::ValueEnum;
+
+/// Criterion that tours are ranked by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Objective {
+    /// Total length of the tour.
+    #[default]
+    Total,
+    /// Length of the longest edge of the tour (bottleneck TSP), ties broken by the total.
+    Bottleneck,
+    /// Expected length of the tour plus its standard deviation times the risk aversion, under
+    /// stochastic edge costs whose means are the distances.
+    Robust,
+}
+
+impl Objective {
+    /// Value of `solution` under the objective, the one recorded in the trace and compared
+    /// with the target.
+    pub fn value(self, solution: &Solution) -> Cost {
+        match self {
+            Objective::Total => solution.total_distance,
+            Objective::Bottleneck => solution.longest_edge,
+            Objective::Robust => solution.total_distance + solution.risk,
+        }
+    }
+
+    /// Key that tours are ranked by, lowest first: the value under the objective, ties broken
+    /// by the total length.
+    pub fn key(self, solution: &Solution) -> (Cost, Cost) {
+        (self.value(solution), solution.total_distance)
+    }
+
+    /// Whether `a` is strictly better than `b`.
+    pub fn better(self, a: &Solution, b: &Solution) -> bool {
+        self.key(a) < self.key(b)
+    }
+}
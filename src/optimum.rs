@@ -0,0 +1,55 @@
+use crate::instance::Cost;
+
+/// Best-known tour lengths of TSPLIB instances.
+const KNOWN_OPTIMA: &[(&str, Cost)] = &[
+    ("a280", 2579),
+    ("att48", 10628),
+    ("att532", 27686),
+    ("bayg29", 1610),
+    ("bays29", 2020),
+    ("berlin52", 7542),
+    ("bier127", 118282),
+    ("brazil58", 25395),
+    ("brg180", 1950),
+    ("ch130", 6110),
+    ("ch150", 6528),
+    ("d198", 15780),
+    ("dantzig42", 699),
+    ("eil51", 426),
+    ("eil76", 538),
+    ("eil101", 629),
+    ("fri26", 937),
+    ("gr17", 2085),
+    ("gr24", 1272),
+    ("gr48", 5046),
+    ("gr96", 55209),
+    ("gr120", 6942),
+    ("kroA100", 21282),
+    ("kroB100", 22141),
+    ("kroC100", 20749),
+    ("kroD100", 21294),
+    ("kroE100", 22068),
+    ("lin105", 14379),
+    ("pcb442", 50778),
+    ("pr76", 108159),
+    ("pr1002", 259045),
+    ("rat99", 1211),
+    ("rd100", 7910),
+    ("st70", 675),
+    ("swiss42", 1273),
+    ("ts225", 126643),
+    ("tsp225", 3916),
+];
+
+/// Best-known tour length of the TSPLIB instance `name`, if it is in the table.
+pub fn known_optimum(name: &str) -> Option<Cost> {
+    KNOWN_OPTIMA
+        .iter()
+        .find(|&&(instance, _)| instance == name)
+        .map(|&(_, optimum)| optimum)
+}
+
+/// Percentage gap of `distance` above `optimum`.
+pub fn gap(distance: Cost, optimum: Cost) -> f64 {
+    100.0 * (distance - optimum) as f64 / optimum as f64
+}
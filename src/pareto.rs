@@ -0,0 +1,228 @@
+use crate::construction::Constructive;
+use crate::driver;
+use crate::error::Error;
+use crate::instance::{Cost, Instance};
+use crate::local_search::LocalSearch;
+use crate::solution::{PrLocalSearch, PrSelection, PrStop, Solution};
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use rand::Rng;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use tracing::{debug, info};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ParetoConfig {
+    /// Number of evenly spaced weights of the first objective, from 0 to 1, that the
+    /// iterations scalarize the two objectives with.
+    pub weights: usize,
+    pub pr_depth: f64,
+    pub pr_local_search: PrLocalSearch,
+    pub pr_stop: PrStop,
+    pub pr_selection: PrSelection,
+}
+
+/// Tour with its cost under both objectives: `solution.total_distance` is the first.
+#[derive(Clone)]
+pub struct ParetoPoint {
+    pub solution: Solution,
+    pub second_cost: Cost,
+}
+
+impl ParetoPoint {
+    /// Evaluates `solution` under the distances of `instance` and the costs of `second`.
+    fn new(mut solution: Solution, instance: &Instance, second: &Instance) -> Self {
+        solution.eval(instance);
+        let last = *solution.path.last().unwrap();
+        let second_cost =
+            second.path_length(&solution.path) + second.closing_distance(last, solution.path[0]);
+        ParetoPoint {
+            solution,
+            second_cost,
+        }
+    }
+
+    fn costs(&self) -> (Cost, Cost) {
+        (self.solution.total_distance, self.second_cost)
+    }
+
+    /// Whether this point is at least as good as `other` in both objectives and better in one.
+    pub fn dominates(&self, other: &ParetoPoint) -> bool {
+        let (a, b) = (self.costs(), other.costs());
+        a.0 <= b.0 && a.1 <= b.1 && a != b
+    }
+}
+
+/// Nondominated tours found so far, sorted by the first objective, with at most one tour per
+/// pair of costs.
+#[derive(Default)]
+pub struct Archive {
+    points: Vec<ParetoPoint>,
+}
+
+impl Archive {
+    /// Adds `point` unless an archived tour dominates it or has the same costs, dropping the
+    /// tours it dominates. Returns whether it was added.
+    pub fn insert(&mut self, point: ParetoPoint) -> bool {
+        if self
+            .points
+            .iter()
+            .any(|archived| archived.dominates(&point) || archived.costs() == point.costs())
+        {
+            return false;
+        }
+        self.points.retain(|archived| !point.dominates(archived));
+        let index = self
+            .points
+            .partition_point(|archived| archived.costs() < point.costs());
+        self.points.insert(index, point);
+        true
+    }
+
+    pub fn points(&self) -> &[ParetoPoint] {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+/// Bi-objective GRASP minimizing the distances of `instance` and the costs of `second` at once.
+/// Each iteration draws one of `config.weights` weightings of the two objectives and builds and
+/// improves a tour under their weighted sum, the second objective scaled to the mean distance
+/// of the first. Every other iteration instead relinks two tours of the archive of nondominated
+/// tours, which plays the role of the elite set, under a weighting. The archive is returned as
+/// the approximation of the Pareto front.
+pub fn pareto_grasp(
+    instance: &Instance,
+    second: &Instance,
+    termination: &Termination,
+    construction: &dyn Constructive,
+    local_search: &dyn LocalSearch,
+    config: &ParetoConfig,
+    trace: &Trace,
+) -> Result<Archive, Error> {
+    let scale = instance.mean_distance() / second.mean_distance().max(f64::EPSILON);
+    let weights = config.weights.max(2);
+    let weighted: Vec<Instance> = (0..weights)
+        .map(|k| instance.weighted(second, k as f64 / (weights - 1) as f64, scale))
+        .collect::<Result<_, _>>()?;
+    info!(
+        "Scalarizing the two objectives with {} weights (second cost scaled by {:.4})",
+        weights, scale
+    );
+
+    let mut archive = Archive::default();
+    let mut iteration = 0;
+    while !termination.should_stop() {
+        let archived = archive.points();
+        let points = driver::batch(termination, trace, |_| {
+            let mut rng = crate::rng::thread_rng();
+            let scalarized = &weighted[rng.gen_range(0..weights)];
+            let solution = if archived.len() >= 2 && rng.gen_bool(0.5) {
+                let a = rng.gen_range(0..archived.len());
+                let b = (a + rng.gen_range(1..archived.len())) % archived.len();
+                let mut solution = archived[a].solution.copy();
+                solution.eval(scalarized);
+                solution.path_relinking(
+                    &archived[b].solution,
+                    scalarized,
+                    local_search,
+                    config.pr_depth,
+                    config.pr_local_search,
+                    config.pr_stop,
+                    config.pr_selection,
+                );
+                solution
+            } else {
+                let mut solution = construction.construct(scalarized);
+                solution.eval(scalarized);
+                local_search
+                    .improve_until(&mut solution, scalarized, &|| termination.should_stop());
+                solution
+            };
+            ParetoPoint::new(solution, instance, second)
+        });
+
+        for (_, point) in points {
+            iteration += 1;
+            if archive.insert(point) {
+                debug!(iteration, size = archive.len(), "Archive updated");
+            }
+        }
+        let solutions: Vec<Solution> = archive
+            .points()
+            .iter()
+            .map(|point| point.solution.copy())
+            .collect();
+        trace.publish_elite(&solutions);
+    }
+    Ok(archive)
+}
+
+/// Writes the tours of `archive` with both of their costs, as JSON if `output` ends in `.json`
+/// and as CSV otherwise.
+pub fn write_front(
+    output: &str,
+    archive: &Archive,
+    instance: &Instance,
+    second: &Instance,
+) -> Result<(), Error> {
+    File::create(output)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            write_points(
+                &mut writer,
+                output.ends_with(".json"),
+                archive,
+                instance,
+                second,
+            )?;
+            writer.flush()
+        })
+        .map_err(|error| Error::output(output, error))
+}
+
+fn write_points(
+    writer: &mut impl Write,
+    json: bool,
+    archive: &Archive,
+    instance: &Instance,
+    second: &Instance,
+) -> io::Result<()> {
+    if json {
+        let rows: Vec<String> = archive
+            .points()
+            .iter()
+            .map(|point| {
+                let path: Vec<String> = point.solution.path.iter().map(|c| c.to_string()).collect();
+                format!(
+                    "  {{\"distance\": {}, \"second_cost\": {}, \"path\": [{}]}}",
+                    instance.format_cost(point.solution.total_distance),
+                    second.format_cost(point.second_cost),
+                    path.join(", ")
+                )
+            })
+            .collect();
+        writeln!(writer, "[\n{}\n]", rows.join(",\n"))?;
+    } else {
+        writeln!(writer, "distance,second_cost,path")?;
+        for point in archive.points() {
+            let path: Vec<String> = point.solution.path.iter().map(|c| c.to_string()).collect();
+            writeln!(
+                writer,
+                "{},{},{}",
+                instance.format_cost(point.solution.total_distance),
+                second.format_cost(point.second_cost),
+                path.join(" ")
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,277 @@
+use crate::driver;
+use crate::error::{Error, ErrorKind};
+use crate::instance::{Cost, Instance};
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use tracing::info;
+
+/// Prize collected when visiting each city and penalty paid for skipping it.
+#[derive(Debug, Clone)]
+pub struct Prizes {
+    pub prize: Vec<i64>,
+    pub penalty: Vec<Cost>,
+}
+
+impl Prizes {
+    /// Loads a prizes file with one `<city> <prize> <penalty>` line per city (0-based city
+    /// indices). Cities that are not listed have no prize and can be skipped for free. Blank
+    /// lines and lines starting with `#` are ignored. Penalties are scaled like the instance's
+    /// distances.
+    pub fn load(filename: &str, instance: &Instance) -> Result<Self, Error> {
+        let invalid = |message: String| Error::new(ErrorKind::Instance, message);
+        let num_cities = instance.num_cities;
+        let content = std::fs::read_to_string(filename)
+            .map_err(|error| invalid(format!("Failed to read {}: {}", filename, error)))?;
+        let mut prizes = Prizes {
+            prize: vec![0; num_cities],
+            penalty: vec![0; num_cities],
+        };
+
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid_line = || invalid(format!("Invalid prizes line: {}", line));
+            let values: Vec<i64> = line
+                .split_whitespace()
+                .map(|value| value.parse().map_err(|_| invalid_line()))
+                .collect::<Result<_, _>>()?;
+            let [city, prize, penalty] = values[..] else {
+                return Err(invalid_line());
+            };
+            if city < 0 || city as usize >= num_cities {
+                return Err(invalid(format!(
+                    "City {} does not exist in the instance",
+                    city
+                )));
+            }
+            prizes.prize[city as usize] = prize;
+            prizes.penalty[city as usize] = penalty * instance.scale();
+        }
+
+        Ok(prizes)
+    }
+}
+
+/// Tour over a subset of the cities, starting at the depot.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrizeSolution {
+    pub path: Vec<usize>,
+    pub total_distance: Cost,
+    pub total_penalty: Cost,
+    pub collected_prize: i64,
+}
+
+impl PrizeSolution {
+    pub fn eval(&mut self, instance: &Instance, prizes: &Prizes) {
+        let n = self.path.len();
+        self.total_distance = (0..n)
+            .map(|i| instance.dist(self.path[i], self.path[(i + 1) % n]))
+            .sum();
+
+        let mut visited = vec![false; instance.num_cities];
+        for &city in &self.path {
+            visited[city] = true;
+        }
+        self.total_penalty = (0..instance.num_cities)
+            .filter(|&city| !visited[city])
+            .map(|city| prizes.penalty[city])
+            .sum();
+        self.collected_prize = self.path.iter().map(|&city| prizes.prize[city]).sum();
+    }
+
+    /// Tour length plus the penalties of the skipped cities.
+    pub fn cost(&self) -> Cost {
+        self.total_distance + self.total_penalty
+    }
+}
+
+/// Cheapest position to insert `city` into `path`, as the index it would take and the added
+/// length.
+fn cheapest_insertion(instance: &Instance, path: &[usize], city: usize) -> (usize, Cost) {
+    let n = path.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (path[i], path[(i + 1) % n]);
+            let cost = instance.dist(a, city) + instance.dist(city, b) - instance.dist(a, b);
+            (i + 1, cost)
+        })
+        .min_by_key(|&(_, cost)| cost)
+        .unwrap()
+}
+
+/// Randomized insertion construction: starting from the depot, repeatedly inserts a city picked
+/// from the best third of the candidates ranked by insertion cost minus penalty. Cities are
+/// added while the minimum prize is not reached or while inserting them pays off.
+pub fn constructive_phase_pctsp(
+    instance: &Instance,
+    prizes: &Prizes,
+    depot: usize,
+    min_prize: i64,
+) -> PrizeSolution {
+    let mut path = vec![depot];
+    let mut remaining: Vec<usize> = (0..instance.num_cities).filter(|&c| c != depot).collect();
+    let mut collected_prize = prizes.prize[depot];
+
+    while !remaining.is_empty() {
+        let mut candidates: Vec<(usize, usize, Cost)> = remaining
+            .iter()
+            .map(|&city| {
+                let (position, cost) = cheapest_insertion(instance, &path, city);
+                (city, position, cost - prizes.penalty[city])
+            })
+            .collect();
+        if collected_prize >= min_prize {
+            candidates.retain(|&(_, _, saving)| saving < 0);
+            if candidates.is_empty() {
+                break;
+            }
+        }
+        candidates.sort_by_key(|&(_, _, saving)| saving);
+
+        let k = (candidates.len() as f32 / 3.0).ceil() as usize;
+        let (city, position, _) = candidates[crate::rng::random::<usize>() % k];
+
+        path.insert(position, city);
+        remaining.retain(|&x| x != city);
+        collected_prize += prizes.prize[city];
+    }
+
+    let mut solution = PrizeSolution {
+        path,
+        total_distance: 0,
+        total_penalty: 0,
+        collected_prize: 0,
+    };
+    solution.eval(instance, prizes);
+    solution
+}
+
+/// First-improvement local search combining 2-opt, removal of cities whose detour costs more
+/// than their penalty (as long as the minimum prize is kept) and insertion of skipped cities
+/// whose penalty exceeds their cheapest insertion cost.
+pub fn local_search_pctsp(
+    solution: &mut PrizeSolution,
+    instance: &Instance,
+    prizes: &Prizes,
+    min_prize: i64,
+) {
+    while two_opt(solution, instance)
+        || remove(solution, instance, prizes, min_prize)
+        || insert(solution, instance, prizes)
+    {}
+}
+
+fn two_opt(solution: &mut PrizeSolution, instance: &Instance) -> bool {
+    let n = solution.path.len();
+    for i in 1..n.saturating_sub(1) {
+        for j in i + 2..=n {
+            let a = solution.path[i - 1];
+            let b = solution.path[i];
+            let c = solution.path[j - 1];
+            let d = solution.path[j % n];
+            let delta = instance.dist(a, c) + instance.dist(b, d)
+                - instance.dist(a, b)
+                - instance.dist(c, d);
+
+            if delta < 0 {
+                solution.path[i..j].reverse();
+                solution.total_distance += delta;
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn remove(
+    solution: &mut PrizeSolution,
+    instance: &Instance,
+    prizes: &Prizes,
+    min_prize: i64,
+) -> bool {
+    let n = solution.path.len();
+    for i in 1..n {
+        let city = solution.path[i];
+        if solution.collected_prize - prizes.prize[city] < min_prize {
+            continue;
+        }
+        let (prev, next) = (solution.path[i - 1], solution.path[(i + 1) % n]);
+        let gain =
+            instance.dist(prev, city) + instance.dist(city, next) - instance.dist(prev, next);
+
+        if gain > prizes.penalty[city] {
+            solution.path.remove(i);
+            solution.total_distance -= gain;
+            solution.total_penalty += prizes.penalty[city];
+            solution.collected_prize -= prizes.prize[city];
+            return true;
+        }
+    }
+    false
+}
+
+fn insert(solution: &mut PrizeSolution, instance: &Instance, prizes: &Prizes) -> bool {
+    let mut visited = vec![false; instance.num_cities];
+    for &city in &solution.path {
+        visited[city] = true;
+    }
+
+    for city in (0..instance.num_cities).filter(|&c| !visited[c]) {
+        let (position, cost) = cheapest_insertion(instance, &solution.path, city);
+        if cost < prizes.penalty[city] {
+            solution.path.insert(position, city);
+            solution.total_distance += cost;
+            solution.total_penalty -= prizes.penalty[city];
+            solution.collected_prize += prizes.prize[city];
+            return true;
+        }
+    }
+    false
+}
+
+/// GRASP for the prize-collecting TSP: each worker builds a tour over a subset of the cities
+/// that collects at least `min_prize` and improves it with `local_search_pctsp` until the time
+/// limit. Minimizes the tour length plus the penalties of the skipped cities.
+pub fn pctsp(
+    instance: &Instance,
+    termination: &Termination,
+    prizes: &Prizes,
+    depot: usize,
+    min_prize: i64,
+    trace: &Trace,
+) -> Result<PrizeSolution, Error> {
+    if prizes.prize.iter().sum::<i64>() < min_prize {
+        return Err(Error::new(
+            ErrorKind::Infeasible,
+            "The minimum prize exceeds the prizes of all cities",
+        ));
+    }
+    let mut best: Option<PrizeSolution> = None;
+
+    let mut iteration = 0;
+    while !termination.should_stop() {
+        let batch = driver::batch(termination, trace, |_| {
+            let mut solution = constructive_phase_pctsp(instance, prizes, depot, min_prize);
+            local_search_pctsp(&mut solution, instance, prizes, min_prize);
+            solution
+        });
+
+        for (thread, solution) in batch {
+            iteration += 1;
+            if best
+                .as_ref()
+                .is_none_or(|best| solution.cost() < best.cost())
+            {
+                termination.report(solution.cost());
+                trace.record(iteration, thread, solution.cost());
+                info!("Improved cost = {}", instance.format_cost(solution.cost()));
+                best = Some(solution);
+            }
+        }
+    }
+
+    Ok(best.expect("There should be at least one solution"))
+}
@@ -0,0 +1,79 @@
+use crate::solution::Solution;
+
+use clap::ValueEnum;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Longest segment reordered by one segment shuffle move.
+const SEGMENT_LENGTH: usize = 8;
+
+/// Perturbation operators used to escape local optima. They leave `total_distance` stale, so
+/// callers re-evaluate the solution afterwards.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Perturbation {
+    DoubleBridge,
+    SegmentShuffle,
+    KSwap,
+}
+
+impl Perturbation {
+    /// Applies the operator `strength` times, moving only the path positions in `1..end` (see
+    /// `Instance::movable_end`).
+    pub fn apply<R: Rng>(self, solution: &mut Solution, end: usize, strength: usize, rng: &mut R) {
+        match self {
+            Perturbation::DoubleBridge => double_bridge(solution, end, strength, rng),
+            Perturbation::SegmentShuffle => segment_shuffle(solution, end, strength, rng),
+            Perturbation::KSwap => k_swap(solution, end, strength, rng),
+        }
+    }
+}
+
+/// `strength` double-bridge (4-opt) moves: each splits the tour into A B C D and reconnects it
+/// as A C B D, within the positions `1..end`.
+pub fn double_bridge<R: Rng>(solution: &mut Solution, end: usize, strength: usize, rng: &mut R) {
+    if end < 8 {
+        return;
+    }
+
+    for _ in 0..strength {
+        let mut cuts = [0; 3];
+        for cut in cuts.iter_mut() {
+            *cut = rng.gen_range(1..end);
+        }
+        cuts.sort_unstable();
+        if cuts[0] == cuts[1] || cuts[1] == cuts[2] {
+            continue;
+        }
+
+        let [a, b, c] = cuts;
+        solution.path[a..c].rotate_left(b - a);
+    }
+}
+
+/// Shuffles the cities of `strength` random segments of 3 to `SEGMENT_LENGTH` consecutive
+/// cities within the positions `1..end`.
+pub fn segment_shuffle<R: Rng>(solution: &mut Solution, end: usize, strength: usize, rng: &mut R) {
+    if end < 4 {
+        return;
+    }
+
+    for _ in 0..strength {
+        let length = rng.gen_range(3..=SEGMENT_LENGTH.min(end - 1));
+        let start = rng.gen_range(1..=end - length);
+        solution.path[start..start + length].shuffle(rng);
+    }
+}
+
+/// Swaps `strength` random pairs of cities within the positions `1..end`.
+pub fn k_swap<R: Rng>(solution: &mut Solution, end: usize, strength: usize, rng: &mut R) {
+    if end < 3 {
+        return;
+    }
+
+    for _ in 0..strength {
+        let i = rng.gen_range(1..end);
+        let j = rng.gen_range(1..end);
+        solution.path.swap(i, j);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::error::{Error, ErrorKind};
+use crate::instance::Instance;
+use crate::solution::Solution;
+
+use plotters::coord::Shift;
+use plotters::drawing::DrawingAreaErrorKind;
+use plotters::prelude::*;
+use std::path::Path;
+
+const MARGIN: f64 = 20.0;
+
+/// Size and labels of the PNG plots.
+#[derive(Debug, Clone, Copy)]
+pub struct PngOptions {
+    pub width: u32,
+    pub height: u32,
+    /// Whether every city is labelled with its 1-based id.
+    pub labels: bool,
+}
+
+/// Renders `solution` as a PNG with nodes and tour edges, titled with its distance. Requires
+/// node coordinates.
+pub fn write_png(
+    filename: &str,
+    instance: &Instance,
+    solution: &Solution,
+    options: &PngOptions,
+) -> Result<(), Error> {
+    let coords = coords(instance)?;
+    let root = BitMapBackend::new(filename, (options.width, options.height)).into_drawing_area();
+    draw(&root, instance, coords, solution, options, None)
+        .and_then(|_| root.present())
+        .map_err(|error| Error::output(filename, error))
+}
+
+/// Writes one PNG per solution of `tours` into `dir` as `frame_00001.png`, `frame_00002.png`
+/// and so on, titled with the frame number, for assembling into an animation of the search
+/// (e.g. `ffmpeg -i frame_%05d.png`).
+pub fn write_frames(
+    dir: &str,
+    instance: &Instance,
+    tours: &[Solution],
+    options: &PngOptions,
+) -> Result<(), Error> {
+    let coords = coords(instance)?;
+    std::fs::create_dir_all(dir).map_err(|error| Error::output(dir, error))?;
+    for (index, tour) in tours.iter().enumerate() {
+        let path = Path::new(dir).join(format!("frame_{:05}.png", index + 1));
+        let root = BitMapBackend::new(&path, (options.width, options.height)).into_drawing_area();
+        draw(&root, instance, coords, tour, options, Some(index + 1))
+            .and_then(|_| root.present())
+            .map_err(|error| Error::output(path.display(), error))?;
+    }
+    Ok(())
+}
+
+/// Writes `tours` as the frames of an animated GIF, each shown for `delay_ms` milliseconds
+/// and the last one held for a second more.
+pub fn write_gif(
+    filename: &str,
+    instance: &Instance,
+    tours: &[Solution],
+    options: &PngOptions,
+    delay_ms: u32,
+) -> Result<(), Error> {
+    let coords = coords(instance)?;
+    let size = (options.width, options.height);
+    let root = BitMapBackend::gif(filename, size, delay_ms)
+        .map_err(|error| Error::output(filename, error))?
+        .into_drawing_area();
+    let frame = |tour: &Solution, number: usize| {
+        draw(&root, instance, coords, tour, options, Some(number))
+            .and_then(|_| root.present())
+            .map_err(|error| Error::output(filename, error))
+    };
+    for (index, tour) in tours.iter().enumerate() {
+        frame(tour, index + 1)?;
+    }
+    if let Some(last) = tours.last() {
+        for _ in 0..1000 / delay_ms.max(1) {
+            frame(last, tours.len())?;
+        }
+    }
+    Ok(())
+}
+
+/// The node coordinates of `instance`, which every plot needs.
+fn coords(instance: &Instance) -> Result<&[(f64, f64)], Error> {
+    instance.coords().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidArguments,
+            "PNG plotting requires node coordinates",
+        )
+    })
+}
+
+fn draw<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    instance: &Instance,
+    coords: &[(f64, f64)],
+    solution: &Solution,
+    options: &PngOptions,
+    frame: Option<usize>,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    let (min_x, max_x) = coords
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &(x, _)| {
+            (lo.min(x), hi.max(x))
+        });
+    let (min_y, max_y) = coords
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &(_, y)| {
+            (lo.min(y), hi.max(y))
+        });
+    let (width, height) = (options.width as f64, options.height as f64);
+    let scale = ((width - 2.0 * MARGIN) / (max_x - min_x).max(f64::EPSILON))
+        .min((height - 2.0 * MARGIN) / (max_y - min_y).max(f64::EPSILON));
+
+    let project = |city: usize| {
+        let (x, y) = coords[city];
+        (
+            (MARGIN + (x - min_x) * scale) as i32,
+            (height - MARGIN - (y - min_y) * scale) as i32,
+        )
+    };
+
+    root.fill(&WHITE)?;
+
+    let mut points: Vec<(i32, i32)> = solution.path.iter().map(|&city| project(city)).collect();
+    if !instance.open_tour {
+        if let Some(&first) = points.first() {
+            points.push(first);
+        }
+    }
+    root.draw(&PathElement::new(points, BLUE.stroke_width(2)))?;
+
+    for city in 0..instance.num_cities {
+        let position = project(city);
+        root.draw(&Circle::new(position, 3, RED.filled()))?;
+        if options.labels {
+            root.draw(&Text::new(
+                (city + 1).to_string(),
+                (position.0 + 4, position.1 - 12),
+                ("sans-serif", 11).into_font(),
+            ))?;
+        }
+    }
+
+    let title = match frame {
+        Some(frame) => format!("#{}  distance = {}", frame, solution.total_distance),
+        None => format!("distance = {}", solution.total_distance),
+    };
+    root.draw(&Text::new(
+        title,
+        (MARGIN as i32, 4),
+        ("monospace", 14).into_font(),
+    ))
+}
@@ -0,0 +1,361 @@
+use crate::driver;
+use crate::grasp::Rcl;
+use crate::instance::{Cost, Instance};
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use rand::Rng;
+use tracing::{debug, trace};
+
+/// Permutation problem solvable by the generic GRASP engine of `solve`: a solution is an
+/// ordering of the elements `0..size()`, minimizing `cost`.
+pub trait Problem: Sync {
+    /// Move of the neighbourhood explored by the local search.
+    type Move: Move;
+
+    /// Number of elements permuted.
+    fn size(&self) -> usize;
+
+    fn cost(&self, permutation: &[usize]) -> Cost;
+
+    /// Greedy cost of placing `element` right after `partial`, which the candidates of the
+    /// restricted candidate list are ranked by.
+    fn greedy_cost(&self, partial: &[usize], element: usize) -> Cost;
+
+    /// Moves of the neighbourhood of a permutation, in the order the local search tries them.
+    fn neighbourhood(&self) -> impl Iterator<Item = Self::Move>;
+
+    /// Change of cost of applying `change` to `permutation`.
+    fn delta(&self, permutation: &[usize], change: &Self::Move) -> Cost;
+
+    /// Change of cost of exchanging the elements at the distinct positions of `exchange`, the
+    /// step of path relinking.
+    fn exchange_delta(&self, permutation: &[usize], exchange: &Exchange) -> Cost;
+}
+
+/// Change of a permutation in place.
+pub trait Move: Copy + Send + Sync {
+    fn apply(&self, permutation: &mut [usize]);
+}
+
+/// Exchange of the elements at two positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exchange(pub usize, pub usize);
+
+impl Move for Exchange {
+    fn apply(&self, permutation: &mut [usize]) {
+        permutation.swap(self.0, self.1);
+    }
+}
+
+/// Reversal of the positions `start..end`, a 2-opt move on a tour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reversal(pub usize, pub usize);
+
+impl Move for Reversal {
+    fn apply(&self, permutation: &mut [usize]) {
+        permutation[self.0..self.1].reverse();
+    }
+}
+
+/// Permutation with its cost.
+#[derive(Debug, Clone)]
+pub struct Permutation {
+    pub order: Vec<usize>,
+    pub cost: Cost,
+}
+
+impl Permutation {
+    pub fn new(problem: &impl Problem, order: Vec<usize>) -> Self {
+        let cost = problem.cost(&order);
+        Permutation { order, cost }
+    }
+
+    /// Number of positions where this permutation and `other` differ.
+    pub fn difference(&self, other: &Permutation) -> usize {
+        self.order
+            .iter()
+            .zip(&other.order)
+            .filter(|(a, b)| a != b)
+            .count()
+    }
+}
+
+/// Parameters of the GRASP engine.
+#[derive(Debug, Clone, Copy)]
+pub struct EngineConfig {
+    pub rcl: Rcl,
+    pub elite_size: usize,
+    /// Minimum fraction of positions in which a permutation must differ from every elite one
+    /// to join the elite set.
+    pub min_diversity: f64,
+    /// Fraction, in `(0, 1]`, of the way from the initial permutation to the guide that
+    /// relinking walks.
+    pub pr_depth: f64,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        EngineConfig {
+            rcl: Rcl::default(),
+            elite_size: 10,
+            min_diversity: 0.1,
+            pr_depth: 1.0,
+        }
+    }
+}
+
+/// GRASP with path relinking over any permutation problem: batches of greedy randomized
+/// constructions improved by first-improvement local search, one per thread, after each of
+/// which the best elite permutation is relinked with another, drawn at random.
+pub fn solve(
+    problem: &impl Problem,
+    termination: &Termination,
+    config: &EngineConfig,
+) -> Permutation {
+    let min_difference = (problem.size() as f64 * config.min_diversity).round() as usize;
+    let should_stop = || termination.should_stop();
+    let mut best: Option<Permutation> = None;
+    let mut elite_set: Vec<Permutation> = Vec::with_capacity(config.elite_size);
+    // The engine keeps no trace, since traces record tours of TSP instances.
+    let trace = Trace::new();
+
+    let mut iteration = 0;
+    while !termination.should_stop() {
+        let mut permutations = driver::batch(termination, &trace, |_| {
+            let mut permutation = construct(problem, config.rcl);
+            local_search(problem, &mut permutation, &should_stop);
+            permutation
+        });
+        if elite_set.len() >= 2 && !termination.should_stop() {
+            let guide = crate::rng::thread_rng().gen_range(1..elite_set.len());
+            let mut permutation =
+                path_relinking(problem, &elite_set[0], &elite_set[guide], config.pr_depth);
+            local_search(problem, &mut permutation, &should_stop);
+            termination.count_iteration();
+            permutations.push((0, permutation));
+        }
+
+        for (_, permutation) in permutations {
+            iteration += 1;
+            trace!(iteration, cost = permutation.cost, "Iteration done");
+            if best
+                .as_ref()
+                .is_none_or(|best| permutation.cost < best.cost)
+            {
+                debug!(iteration, cost = permutation.cost, "Improved cost");
+                termination.report(permutation.cost);
+                best = Some(permutation.clone());
+            }
+            update_elite_set(
+                &mut elite_set,
+                permutation,
+                config.elite_size,
+                min_difference,
+            );
+        }
+    }
+
+    best.expect("GRASP should return at least one solution")
+}
+
+/// Greedy randomized construction: each element is drawn from the restricted candidate list of
+/// the remaining elements, ranked by their greedy cost.
+pub fn construct(problem: &impl Problem, rcl: Rcl) -> Permutation {
+    let rcl = match rcl {
+        Rcl::RandomValue(max) => Rcl::Value(crate::rng::random::<f64>() * max),
+        rcl => rcl,
+    };
+    let mut remaining: Vec<usize> = (0..problem.size()).collect();
+    let mut order = Vec::with_capacity(remaining.len());
+    let mut candidates: Vec<(usize, Cost)> = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        candidates.clear();
+        candidates.extend(
+            remaining
+                .iter()
+                .enumerate()
+                .map(|(index, &element)| (index, problem.greedy_cost(&order, element))),
+        );
+        candidates.sort_unstable_by_key(|&(_, cost)| cost);
+        let k = match rcl.cardinality(candidates.len()) {
+            Some(k) => k,
+            None => {
+                let Rcl::Value(alpha) = rcl else {
+                    unreachable!("Random alphas are drawn before the construction")
+                };
+                let (min, max) = (candidates[0].1, candidates[candidates.len() - 1].1);
+                let threshold = min + (alpha * (max - min) as f64) as Cost;
+                candidates.partition_point(|&(_, cost)| cost <= threshold)
+            }
+        };
+        let (index, _) = candidates[crate::rng::random::<usize>() % k];
+        order.push(remaining.swap_remove(index));
+    }
+    Permutation::new(problem, order)
+}
+
+/// First-improvement local search over the neighbourhood of the problem, returning early once
+/// `should_stop` is true. Returns whether it was cut short.
+pub fn local_search<P: Problem>(
+    problem: &P,
+    permutation: &mut Permutation,
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
+    loop {
+        if should_stop() {
+            return true;
+        }
+        let improving = problem
+            .neighbourhood()
+            .map(|change| (change, problem.delta(&permutation.order, &change)))
+            .find(|&(_, delta)| delta < 0);
+        match improving {
+            Some((change, delta)) => {
+                change.apply(&mut permutation.order);
+                permutation.cost += delta;
+            }
+            None => return false,
+        }
+    }
+}
+
+/// Greedy path relinking from `initial` towards `guide`: every step exchanges the element at
+/// one of the positions where they differ with the one the guide has there, picking the
+/// exchange that leads to the cheapest permutation by its `exchange_delta`. The walk takes at
+/// most `depth` (a fraction in `(0, 1]`) of as many steps as the two differ in positions,
+/// rounded up, so that below 1 it may stop short of the guide. Returns the best permutation on
+/// the path, excluding both ends unless no step was taken.
+pub fn path_relinking(
+    problem: &impl Problem,
+    initial: &Permutation,
+    guide: &Permutation,
+    depth: f64,
+) -> Permutation {
+    let mut current = initial.order.clone();
+    let mut position = vec![0; current.len()];
+    for (index, &element) in current.iter().enumerate() {
+        position[element] = index;
+    }
+    let steps = (initial.difference(guide) as f64 * depth).ceil() as usize;
+    let mut cost = initial.cost;
+    let mut best: Option<Permutation> = None;
+
+    for _ in 0..steps {
+        let differing: Vec<usize> = (0..current.len())
+            .filter(|&index| current[index] != guide.order[index])
+            .collect();
+        let step = differing
+            .into_iter()
+            .map(|index| {
+                let other = position[guide.order[index]];
+                let delta = problem.exchange_delta(&current, &Exchange(index, other));
+                (index, other, delta)
+            })
+            .min_by_key(|&(_, _, delta)| delta);
+        let Some((index, other, delta)) = step else {
+            break;
+        };
+        current.swap(index, other);
+        cost += delta;
+        position[current[index]] = index;
+        position[current[other]] = other;
+        if current != guide.order && best.as_ref().is_none_or(|best| cost < best.cost) {
+            best = Some(Permutation {
+                order: current.clone(),
+                cost,
+            });
+        }
+    }
+    best.unwrap_or_else(|| initial.clone())
+}
+
+/// Adds `permutation` to the elite set, kept sorted by cost, if it differs from every elite
+/// permutation in at least `min_difference` positions, replacing the worst once the set is
+/// full if it is better.
+fn update_elite_set(
+    elite_set: &mut Vec<Permutation>,
+    permutation: Permutation,
+    max_elite_size: usize,
+    min_difference: usize,
+) {
+    if elite_set
+        .iter()
+        .any(|elite| elite.difference(&permutation) < min_difference.max(1))
+    {
+        return;
+    }
+    if elite_set.len() >= max_elite_size {
+        if elite_set
+            .last()
+            .is_none_or(|worst| worst.cost <= permutation.cost)
+        {
+            return;
+        }
+        elite_set.pop();
+    }
+    let index = elite_set.partition_point(|elite| elite.cost <= permutation.cost);
+    elite_set.insert(index, permutation);
+}
+
+/// The TSP as a permutation problem: the permutation is the order the cities are visited in,
+/// improved by 2-opt. This is a minimal example of the trait, not used by the TSP variants,
+/// and edge constraints and fixed endpoints are not taken into account.
+impl Problem for Instance {
+    type Move = Reversal;
+
+    fn size(&self) -> usize {
+        self.num_cities
+    }
+
+    fn cost(&self, permutation: &[usize]) -> Cost {
+        self.path_length(permutation)
+            + self.closing_distance(*permutation.last().unwrap(), permutation[0])
+    }
+
+    fn greedy_cost(&self, partial: &[usize], element: usize) -> Cost {
+        partial.last().map_or(0, |&last| self.dist(last, element))
+    }
+
+    fn neighbourhood(&self) -> impl Iterator<Item = Reversal> {
+        let n = self.num_cities;
+        (1..n.saturating_sub(1)).flat_map(move |i| (i + 2..n).map(move |j| Reversal(i, j)))
+    }
+
+    fn delta(&self, permutation: &[usize], change: &Reversal) -> Cost {
+        let &Reversal(i, j) = change;
+        let (a, b, c, d) = (
+            permutation[i - 1],
+            permutation[i],
+            permutation[j - 1],
+            permutation[j],
+        );
+        self.dist(a, c) + self.dist(b, d) - self.dist(a, b) - self.dist(c, d)
+    }
+
+    /// Change of length of the edges next to the two positions, in constant time.
+    fn exchange_delta(&self, permutation: &[usize], exchange: &Exchange) -> Cost {
+        let n = permutation.len();
+        let &Exchange(i, j) = exchange;
+        let swapped = |position: usize| match position {
+            p if p == i => permutation[j],
+            p if p == j => permutation[i],
+            p => permutation[p],
+        };
+        // Edge `k` joins the positions `k` and `k + 1`, the last one closing the tour.
+        let length = |city: &dyn Fn(usize) -> usize, k: usize| match k {
+            k if k == n - 1 => self.closing_distance(city(k), city(0)),
+            k => self.dist(city(k), city(k + 1)),
+        };
+        let mut edges = [(i + n - 1) % n, i, (j + n - 1) % n, j];
+        edges.sort_unstable();
+        let mut delta = 0;
+        for (index, &k) in edges.iter().enumerate() {
+            if index > 0 && edges[index - 1] == k {
+                continue;
+            }
+            delta += length(&swapped, k) - length(&|p| permutation[p], k);
+        }
+        delta
+    }
+}
@@ -0,0 +1,214 @@
+use crate::error::{Error, ErrorKind};
+use crate::instance::{name_from_path, Cost};
+use crate::problem::{Exchange, Problem};
+use crate::tsplib;
+
+/// Quadratic assignment problem: facility `i` is assigned to location `permutation[i]`, and the
+/// cost sums the flow between every two facilities times the distance between their locations.
+#[derive(Debug, Clone)]
+pub struct Qap {
+    pub name: Option<String>,
+    pub size: usize,
+    /// Flows between facilities, row-major.
+    flow: Vec<Cost>,
+    /// Distances between locations, row-major.
+    distance: Vec<Cost>,
+}
+
+impl Qap {
+    /// Loads a QAPLIB instance; files ending in `.gz` are decompressed.
+    pub fn load(filename: &str) -> Result<Self, Error> {
+        let mut qap = Self::parse(&tsplib::read_file(filename)?)?;
+        qap.name = Some(name_from_path(filename).to_string());
+        Ok(qap)
+    }
+
+    /// Parses a QAPLIB instance: the size `n`, then the two `n`x`n` matrices, all separated by
+    /// whitespace. The first matrix is taken as the flows and the second as the distances,
+    /// which gives the same optimal cost if they are swapped.
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let invalid = |message: String| Error::new(ErrorKind::Instance, message);
+        let values = content
+            .split_whitespace()
+            .map(|token| {
+                token
+                    .parse::<Cost>()
+                    .map_err(|_| invalid(format!("Invalid value in the QAP instance: {}", token)))
+            })
+            .collect::<Result<Vec<Cost>, Error>>()?;
+        let (&size, values) = values
+            .split_first()
+            .ok_or_else(|| invalid("Missing size in the QAP instance".to_string()))?;
+        let size = usize::try_from(size)
+            .map_err(|_| invalid(format!("Invalid size of the QAP instance: {}", size)))?;
+        let matrix = size.saturating_mul(size);
+        if values.len() < 2 * matrix {
+            return Err(invalid(format!(
+                "The QAP instance has {} values where two {}x{} matrices need {}",
+                values.len(),
+                size,
+                size,
+                2 * matrix
+            )));
+        }
+        if values.len() > 2 * matrix {
+            return Err(invalid(
+                "The QAP instance has values after its two matrices".to_string(),
+            ));
+        }
+        Ok(Qap {
+            name: None,
+            size,
+            flow: values[..matrix].to_vec(),
+            distance: values[matrix..].to_vec(),
+        })
+    }
+
+    fn flow(&self, i: usize, j: usize) -> Cost {
+        self.flow[i * self.size + j]
+    }
+
+    fn distance(&self, i: usize, j: usize) -> Cost {
+        self.distance[i * self.size + j]
+    }
+}
+
+impl Problem for Qap {
+    type Move = Exchange;
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn cost(&self, permutation: &[usize]) -> Cost {
+        (0..self.size)
+            .flat_map(|i| (0..self.size).map(move |j| (i, j)))
+            .map(|(i, j)| self.flow(i, j) * self.distance(permutation[i], permutation[j]))
+            .sum()
+    }
+
+    /// Cost between the next facility, placed at location `element`, and those already placed.
+    fn greedy_cost(&self, partial: &[usize], element: usize) -> Cost {
+        let k = partial.len();
+        let placed: Cost = partial
+            .iter()
+            .enumerate()
+            .map(|(i, &location)| {
+                self.flow(i, k) * self.distance(location, element)
+                    + self.flow(k, i) * self.distance(element, location)
+            })
+            .sum();
+        placed + self.flow(k, k) * self.distance(element, element)
+    }
+
+    fn neighbourhood(&self) -> impl Iterator<Item = Exchange> {
+        let n = self.size;
+        (0..n).flat_map(move |r| (r + 1..n).map(move |s| Exchange(r, s)))
+    }
+
+    /// Change of cost of exchanging the locations of facilities `r` and `s`, in linear time.
+    fn delta(&self, permutation: &[usize], change: &Exchange) -> Cost {
+        let &Exchange(r, s) = change;
+        let (pr, ps) = (permutation[r], permutation[s]);
+        let mut delta = self.flow(r, r) * (self.distance(ps, ps) - self.distance(pr, pr))
+            + self.flow(s, s) * (self.distance(pr, pr) - self.distance(ps, ps))
+            + self.flow(r, s) * (self.distance(ps, pr) - self.distance(pr, ps))
+            + self.flow(s, r) * (self.distance(pr, ps) - self.distance(ps, pr));
+        for (k, &pk) in permutation.iter().enumerate() {
+            if k == r || k == s {
+                continue;
+            }
+            delta += (self.flow(k, r) - self.flow(k, s))
+                * (self.distance(pk, ps) - self.distance(pk, pr))
+                + (self.flow(r, k) - self.flow(s, k))
+                    * (self.distance(ps, pk) - self.distance(pr, pk));
+        }
+        delta
+    }
+
+    fn exchange_delta(&self, permutation: &[usize], exchange: &Exchange) -> Cost {
+        self.delta(permutation, exchange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::Move;
+
+    /// Small asymmetric instance in the QAPLIB format, with nonzero diagonals.
+    const INSTANCE: &str = "
+8
+
+9 4 5 8 0 7 3 0
+2 1 5 7 3 6 8 1
+9 3 0 3 6 4 2 6
+2 1 2 9 9 7 2 2
+0 0 3 3 2 2 4 5
+3 8 3 2 3 6 4 0
+5 6 2 2 4 1 5 4
+9 9 0 9 5 1 4 5
+
+4 7 5 2 7 7 2 0
+4 0 5 6 0 8 6 5
+6 9 0 7 0 2 9 3
+1 3 7 5 8 5 8 4
+7 1 9 5 4 0 6 1
+3 5 8 9 5 2 5 4
+8 1 4 5 4 2 1 2
+4 7 2 0 1 9 8 6
+";
+
+    /// Permutations of the 8 facilities the moves are checked from.
+    const PERMUTATIONS: [[usize; 8]; 3] = [
+        [0, 1, 2, 3, 4, 5, 6, 7],
+        [7, 6, 5, 4, 3, 2, 1, 0],
+        [3, 0, 6, 2, 7, 5, 1, 4],
+    ];
+
+    #[test]
+    fn parses_both_matrices() {
+        let qap = Qap::parse(INSTANCE).unwrap();
+        assert_eq!(qap.size, 8);
+        assert_eq!((qap.flow(0, 0), qap.flow(1, 0), qap.flow(7, 7)), (9, 2, 5));
+        assert_eq!((qap.distance(0, 1), qap.distance(3, 0)), (7, 1));
+    }
+
+    #[test]
+    fn rejects_wrong_number_of_values() {
+        assert!(Qap::parse("2 1 2 3 4 5 6 7").is_err());
+        assert!(Qap::parse("1 1 2 3").is_err());
+        assert!(Qap::parse("").is_err());
+    }
+
+    #[test]
+    fn delta_matches_the_change_of_cost() {
+        let qap = Qap::parse(INSTANCE).unwrap();
+        for permutation in PERMUTATIONS {
+            let cost = qap.cost(&permutation);
+            for exchange in qap.neighbourhood() {
+                let mut exchanged = permutation;
+                exchange.apply(&mut exchanged);
+                let expected = qap.cost(&exchanged) - cost;
+                assert_eq!(
+                    qap.delta(&permutation, &exchange),
+                    expected,
+                    "{:?}",
+                    exchange
+                );
+                assert_eq!(qap.exchange_delta(&permutation, &exchange), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn greedy_costs_add_up_to_the_cost() {
+        let qap = Qap::parse(INSTANCE).unwrap();
+        for permutation in PERMUTATIONS {
+            let greedy: Cost = (0..qap.size)
+                .map(|k| qap.greedy_cost(&permutation[..k], permutation[k]))
+                .sum();
+            assert_eq!(greedy, qap.cost(&permutation));
+        }
+    }
+}
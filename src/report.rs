@@ -0,0 +1,237 @@
+use crate::error::Error;
+use crate::instance::Cost;
+use crate::solution::Solution;
+use crate::stats::Summary;
+
+use std::fmt::Write;
+use std::path::Path;
+
+const PLOT_WIDTH: f64 = 640.0;
+const PLOT_HEIGHT: f64 = 320.0;
+const PLOT_MARGIN: f64 = 50.0;
+const COLORS: [&str; 8] = [
+    "steelblue",
+    "crimson",
+    "seagreen",
+    "darkorange",
+    "purple",
+    "saddlebrown",
+    "deeppink",
+    "gray",
+];
+
+/// Results of one method on one instance, gathered for the report.
+pub struct MethodReport {
+    pub name: String,
+    pub distances: Vec<f64>,
+    /// Duration of each run, in seconds.
+    pub times: Vec<f64>,
+    pub best: Solution,
+    /// Improvements of the run that found `best`, as seconds since its start and distance.
+    pub convergence: Vec<(f64, Cost)>,
+}
+
+/// Results of every method on one instance.
+pub struct InstanceReport {
+    pub name: String,
+    pub num_cities: usize,
+    pub optimum: Option<Cost>,
+    pub methods: Vec<MethodReport>,
+    /// SVG plot of the best tour over all methods, for instances with node coordinates.
+    pub tour_svg: Option<String>,
+}
+
+/// Writes `<dir>/index.html`: the parameters of the run, then for every instance a table of
+/// the distances and times of each method, a convergence plot of their best runs and the best
+/// tour. The page is self-contained, with every plot inlined as SVG.
+pub fn write_report(
+    dir: &str,
+    parameters: &[(&str, String)],
+    instances: &[InstanceReport],
+) -> Result<(), Error> {
+    let mut html = String::new();
+    writeln!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Benchmark report</title>\n<style>\nbody {{ font-family: sans-serif; margin: 2em; }}\ntable {{ border-collapse: collapse; margin-bottom: 1em; }}\nth, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: right; }}\nth:first-child, td:first-child {{ text-align: left; }}\n</style>\n</head>\n<body>\n<h1>Benchmark report</h1>"
+    )
+    .expect("Failed to write report");
+
+    writeln!(html, "<h2>Parameters</h2>\n<table>").expect("Failed to write report");
+    for (name, value) in parameters {
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td></tr>",
+            escape(name),
+            escape(value)
+        )
+        .expect("Failed to write report");
+    }
+    writeln!(html, "</table>").expect("Failed to write report");
+
+    for instance in instances {
+        write_instance(&mut html, instance);
+    }
+    writeln!(html, "</body>\n</html>").expect("Failed to write report");
+
+    std::fs::create_dir_all(dir).map_err(|error| Error::output(dir, error))?;
+    let path = Path::new(dir).join("index.html");
+    std::fs::write(&path, html).map_err(|error| Error::output(path.display(), error))
+}
+
+fn write_instance(html: &mut String, instance: &InstanceReport) {
+    writeln!(
+        html,
+        "<h2>{}</h2>\n<p>{} cities{}</p>",
+        escape(&instance.name),
+        instance.num_cities,
+        instance.optimum.map_or(String::new(), |optimum| format!(
+            ", known optimum {}",
+            optimum
+        ))
+    )
+    .expect("Failed to write report");
+
+    writeln!(
+        html,
+        "<table>\n<tr><th>Method</th><th>Best</th><th>Mean</th><th>Median</th><th>Std</th><th>Worst</th><th>Best gap (%)</th><th>Mean time (s)</th></tr>"
+    )
+    .expect("Failed to write report");
+    for method in &instance.methods {
+        let distance = Summary::of(&method.distances);
+        let time = Summary::of(&method.times);
+        let gap = instance.optimum.map_or(String::new(), |optimum| {
+            format!(
+                "{:.2}",
+                crate::optimum::gap(method.best.total_distance, optimum)
+            )
+        });
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.2}</td><td>{:.2}</td><td>{}</td><td>{}</td><td>{:.3}</td></tr>",
+            escape(&method.name),
+            distance.min,
+            distance.mean,
+            distance.median,
+            distance.std_dev,
+            distance.max,
+            gap,
+            time.mean
+        )
+        .expect("Failed to write report");
+    }
+    writeln!(html, "</table>").expect("Failed to write report");
+
+    writeln!(html, "<h3>Convergence of the best runs</h3>").expect("Failed to write report");
+    html.push_str(&convergence_svg(&instance.methods));
+
+    if let Some(tour_svg) = &instance.tour_svg {
+        writeln!(html, "<h3>Best tour</h3>").expect("Failed to write report");
+        html.push_str(tour_svg);
+    }
+}
+
+/// Step plot of the best distance over time of each method's best run.
+fn convergence_svg(methods: &[MethodReport]) -> String {
+    let points = methods.iter().flat_map(|method| &method.convergence);
+    let max_time = points
+        .clone()
+        .map(|&(time, _)| time)
+        .fold(0.0, f64::max)
+        .max(f64::EPSILON);
+    let (min_distance, max_distance) = points.fold((Cost::MAX, Cost::MIN), |(lo, hi), &(_, d)| {
+        (lo.min(d), hi.max(d))
+    });
+    let distance_range = (max_distance - min_distance).max(1) as f64;
+
+    let project = |time: f64, distance: Cost| {
+        (
+            PLOT_MARGIN + time / max_time * (PLOT_WIDTH - 2.0 * PLOT_MARGIN),
+            PLOT_HEIGHT
+                - PLOT_MARGIN
+                - (distance - min_distance) as f64 / distance_range
+                    * (PLOT_HEIGHT - 2.0 * PLOT_MARGIN),
+        )
+    };
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+        PLOT_WIDTH, PLOT_HEIGHT
+    )
+    .expect("Failed to write SVG");
+    writeln!(
+        svg,
+        r#"<polyline points="{0},{1} {0},{2} {3},{2}" fill="none" stroke="black"/>"#,
+        PLOT_MARGIN,
+        PLOT_MARGIN,
+        PLOT_HEIGHT - PLOT_MARGIN,
+        PLOT_WIDTH - PLOT_MARGIN
+    )
+    .expect("Failed to write SVG");
+    if min_distance <= max_distance {
+        writeln!(
+            svg,
+            r#"<text x="{0}" y="{1}" font-size="12" text-anchor="end">{2}</text><text x="{0}" y="{3}" font-size="12" text-anchor="end">{4}</text>"#,
+            PLOT_MARGIN - 4.0,
+            PLOT_MARGIN + 4.0,
+            max_distance,
+            PLOT_HEIGHT - PLOT_MARGIN,
+            min_distance
+        )
+        .expect("Failed to write SVG");
+    }
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-size="12" text-anchor="end">{:.3} s</text>"#,
+        PLOT_WIDTH - PLOT_MARGIN,
+        PLOT_HEIGHT - PLOT_MARGIN + 16.0,
+        max_time
+    )
+    .expect("Failed to write SVG");
+
+    for (index, method) in methods.iter().enumerate() {
+        let color = COLORS[index % COLORS.len()];
+        let mut steps = Vec::with_capacity(2 * method.convergence.len() + 1);
+        for (i, &(time, distance)) in method.convergence.iter().enumerate() {
+            if i > 0 {
+                // Horizontal step: the previous distance holds until this improvement.
+                steps.push(project(time, method.convergence[i - 1].1));
+            }
+            steps.push(project(time, distance));
+        }
+        if let Some(&(_, last)) = method.convergence.last() {
+            steps.push(project(max_time, last));
+        }
+        let points: Vec<String> = steps
+            .iter()
+            .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+            .collect();
+        writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+            points.join(" "),
+            color
+        )
+        .expect("Failed to write SVG");
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="12" text-anchor="end" fill="{}">{}</text>"#,
+            PLOT_WIDTH - PLOT_MARGIN,
+            PLOT_MARGIN + 14.0 * index as f64,
+            color,
+            escape(&method.name)
+        )
+        .expect("Failed to write SVG");
+    }
+    writeln!(svg, "</svg>").expect("Failed to write SVG");
+    svg
+}
+
+/// `text` with the HTML special characters escaped.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,90 @@
+//! Random generators of the solvers. They can be seeded so that single-threaded runs are
+//! reproducible.
+//!
+//! Every thread draws from its own PCG stream of the seed: the seed fixes the generator's
+//! initial state and each thread selects a different stream, so threads never share or overlap
+//! a sequence the way generators seeded with `seed + i` can.
+
+use rand::distributions::{Distribution, Standard};
+use rand::{Error, Rng, RngCore, SeedableRng};
+use rand_pcg::Pcg64;
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Seed of the solver's random generators, or `None` to seed them from the OS.
+static SEED: Mutex<Option<u64>> = Mutex::new(None);
+/// Number of thread generators created from `SEED`, so that each thread gets its own stream.
+static STREAMS: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static RNG: RefCell<Pcg64> = RefCell::new(new_rng());
+}
+
+fn new_rng() -> Pcg64 {
+    match *SEED.lock().unwrap() {
+        Some(seed) => {
+            let stream = STREAMS.fetch_add(1, Ordering::Relaxed);
+            Pcg64::new(splitmix(seed), stream as u128)
+        }
+        None => Pcg64::from_entropy(),
+    }
+}
+
+/// 128-bit generator state spread from `seed` with SplitMix64, so that nearby seeds start far
+/// apart.
+fn splitmix(seed: u64) -> u128 {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    ((next() as u128) << 64) | next() as u128
+}
+
+/// Seeds the random generators used by the solvers. The current thread's generator is
+/// reseeded immediately and other threads are seeded when they first draw a number, so a run
+/// on a single thread is reproducible.
+pub fn set_seed(seed: u64) {
+    *SEED.lock().unwrap() = Some(seed);
+    STREAMS.store(0, Ordering::Relaxed);
+    RNG.with(|rng| *rng.borrow_mut() = new_rng());
+}
+
+/// Handle to the current thread's generator, seeded by `set_seed` if it was called.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadRng;
+
+impl RngCore for ThreadRng {
+    fn next_u32(&mut self) -> u32 {
+        RNG.with(|rng| rng.borrow_mut().next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        RNG.with(|rng| rng.borrow_mut().next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        RNG.with(|rng| rng.borrow_mut().fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        RNG.with(|rng| rng.borrow_mut().try_fill_bytes(dest))
+    }
+}
+
+pub fn thread_rng() -> ThreadRng {
+    ThreadRng
+}
+
+/// Random value drawn from the current thread's generator.
+pub fn random<T>() -> T
+where
+    Standard: Distribution<T>,
+{
+    ThreadRng.gen()
+}
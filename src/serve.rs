@@ -0,0 +1,426 @@
+use crate::cli::{ConstructionKind, GraspVariant, LocalSearchKind, SolveArgs};
+use crate::distance::{Distances, MatrixDistances};
+use crate::error::{Error, ErrorKind};
+use crate::instance::{Cost, Instance};
+use crate::solution::Solution;
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::info;
+
+type Response<T> = Result<Json<T>, (StatusCode, String)>;
+
+/// Most instances the server keeps at a time; more are refused until some are deleted.
+const MAX_INSTANCES: usize = 64;
+
+/// Most solves, running or finished, the server keeps at a time.
+const MAX_JOBS: usize = 256;
+
+/// Body of `POST /instances` when it is JSON rather than TSPLIB text.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InstanceRequest {
+    Tsplib { tsplib: String },
+    Matrix { matrix: Vec<Vec<Cost>> },
+}
+
+/// Body of `POST /instances/{id}/solves`. Missing fields take the values the server was
+/// started with; the names are those of the command-line options.
+#[derive(Deserialize)]
+struct SolveRequest {
+    variant: Option<String>,
+    construction: Option<String>,
+    local_search: Option<String>,
+    /// Time limit in seconds.
+    time_limit: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct InstanceInfo {
+    id: usize,
+    num_cities: usize,
+}
+
+#[derive(Serialize)]
+struct SolveStarted {
+    id: usize,
+}
+
+#[derive(Serialize)]
+struct Progress {
+    /// `running`, `done` or `failed`.
+    status: &'static str,
+    elapsed_s: f64,
+    best_distance: Option<f64>,
+    improvements: usize,
+    /// Why the solve failed, if it did.
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Tour {
+    path: Vec<usize>,
+    distance: f64,
+}
+
+/// A solve running, or finished, in the background.
+struct Job {
+    instance: Arc<Instance>,
+    termination: Termination,
+    trace: Trace,
+    result: Mutex<Option<Solution>>,
+    /// Set if the solver failed, in which case there is no result.
+    error: Mutex<Option<Error>>,
+}
+
+/// Entries identified by the order they were added in, up to a maximum number at a time.
+struct Registry<T> {
+    entries: HashMap<usize, Arc<T>>,
+    next_id: usize,
+    capacity: usize,
+}
+
+impl<T> Registry<T> {
+    fn new(capacity: usize) -> Self {
+        Registry {
+            entries: HashMap::new(),
+            next_id: 0,
+            capacity,
+        }
+    }
+
+    /// Adds `entry` and returns its id, or `None` if the registry is full.
+    fn insert(&mut self, entry: Arc<T>) -> Option<usize> {
+        if self.entries.len() >= self.capacity {
+            return None;
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.insert(id, entry);
+        Some(id)
+    }
+
+    fn get(&self, id: usize) -> Option<Arc<T>> {
+        self.entries.get(&id).cloned()
+    }
+
+    fn remove(&mut self, id: usize) -> Option<Arc<T>> {
+        self.entries.remove(&id)
+    }
+}
+
+struct Server {
+    /// Options of the command line, used for whatever a request does not set.
+    defaults: SolveArgs,
+    /// Pool shared by all solves, sized by `--threads`.
+    pool: rayon::ThreadPool,
+    /// Submitted instances and solves. Solves keep their instance alive once it is deleted.
+    instances: Mutex<Registry<Instance>>,
+    jobs: Mutex<Registry<Job>>,
+}
+
+fn bad_request(message: impl Into<String>) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, message.into())
+}
+
+fn not_found(what: &str, id: usize) -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, format!("No {} with id {}", what, id))
+}
+
+fn full(what: &str, capacity: usize) -> (StatusCode, String) {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        format!(
+            "The server already holds {} {}; delete some first",
+            capacity, what
+        ),
+    )
+}
+
+fn parse_value<T: ValueEnum>(name: &str, value: &str) -> Result<T, (StatusCode, String)> {
+    T::from_str(value, true).map_err(|_| bad_request(format!("Invalid {}: {}", name, value)))
+}
+
+/// Parses the body of `POST /instances`: a JSON object with either the TSPLIB text or the
+/// distance matrix, or the TSPLIB text itself.
+fn parse_instance(body: &str, precision: u32) -> Result<Instance, (StatusCode, String)> {
+    let text = if body.trim_start().starts_with('{') {
+        match serde_json::from_str(body).map_err(|e| bad_request(e.to_string()))? {
+            InstanceRequest::Tsplib { tsplib } => tsplib,
+            InstanceRequest::Matrix { matrix } => {
+                let n = matrix.len();
+                if n < 3 || matrix.iter().any(|row| row.len() != n) {
+                    return Err(bad_request(
+                        "The matrix must be square with at least 3 cities",
+                    ));
+                }
+                let distances = MatrixDistances::new(n, matrix.concat());
+                return Ok(Instance::with_distances(
+                    n,
+                    Distances::Matrix(distances),
+                    None,
+                    0,
+                ));
+            }
+        }
+    } else {
+        body.to_string()
+    };
+
+    Instance::parse(&text, precision).map_err(|error| match error.kind {
+        ErrorKind::MemoryLimit => (StatusCode::PAYLOAD_TOO_LARGE, error.message),
+        _ => bad_request(error.message),
+    })
+}
+
+async fn add_instance(State(server): State<Arc<Server>>, body: String) -> Response<InstanceInfo> {
+    // Parsing builds the distance matrix, which may take a while, so it runs off the async
+    // workers.
+    let precision = server.defaults.precision;
+    let instance = tokio::task::spawn_blocking(move || parse_instance(&body, precision))
+        .await
+        .map_err(|error| (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()))??;
+    let num_cities = instance.num_cities;
+    let id = server
+        .instances
+        .lock()
+        .unwrap()
+        .insert(Arc::new(instance))
+        .ok_or_else(|| full("instances", MAX_INSTANCES))?;
+    Ok(Json(InstanceInfo { id, num_cities }))
+}
+
+async fn get_instance(
+    State(server): State<Arc<Server>>,
+    Path(id): Path<usize>,
+) -> Response<InstanceInfo> {
+    let instance = server
+        .instances
+        .lock()
+        .unwrap()
+        .get(id)
+        .ok_or_else(|| not_found("instance", id))?;
+    Ok(Json(InstanceInfo {
+        id,
+        num_cities: instance.num_cities,
+    }))
+}
+
+async fn delete_instance(
+    State(server): State<Arc<Server>>,
+    Path(id): Path<usize>,
+) -> Response<InstanceInfo> {
+    let instance = server
+        .instances
+        .lock()
+        .unwrap()
+        .remove(id)
+        .ok_or_else(|| not_found("instance", id))?;
+    Ok(Json(InstanceInfo {
+        id,
+        num_cities: instance.num_cities,
+    }))
+}
+
+async fn start_solve(
+    State(server): State<Arc<Server>>,
+    Path(instance_id): Path<usize>,
+    Json(request): Json<SolveRequest>,
+) -> Response<SolveStarted> {
+    let instance = server
+        .instances
+        .lock()
+        .unwrap()
+        .get(instance_id)
+        .ok_or_else(|| not_found("instance", instance_id))?;
+
+    let mut args = server.defaults.clone();
+    if let Some(variant) = &request.variant {
+        args.variant = parse_value("variant", variant)?;
+    }
+    if let Some(construction) = &request.construction {
+        args.construction = parse_value::<ConstructionKind>("construction", construction)?;
+    }
+    if let Some(local_search) = &request.local_search {
+        args.local_search = parse_value::<LocalSearchKind>("local search", local_search)?;
+    }
+    if let GraspVariant::Mtsp | GraspVariant::Pctsp | GraspVariant::Pareto = args.variant {
+        return Err(bad_request("Only the single-tour variants can be served"));
+    }
+    if let Some(time_limit) = request.time_limit {
+        args.time_limit = Duration::from_secs(time_limit);
+    }
+
+    let job = Arc::new(Job {
+        instance,
+        termination: args.termination(),
+        trace: Trace::live(),
+        result: Mutex::new(None),
+        error: Mutex::new(None),
+    });
+    let id = server
+        .jobs
+        .lock()
+        .unwrap()
+        .insert(job.clone())
+        .ok_or_else(|| full("solves", MAX_JOBS))?;
+    info!("Solve {} started on instance {}", id, instance_id);
+
+    std::thread::spawn(move || {
+        let solution = panic::catch_unwind(AssertUnwindSafe(|| {
+            server
+                .pool
+                .install(|| args.solve(&args.variant, &job.instance, &job.termination, &job.trace))
+        }))
+        .unwrap_or_else(|payload| Err(Error::from_panic(payload)));
+        match solution {
+            Ok(solution) => {
+                info!(
+                    "Solve {} finished: distance = {}",
+                    id,
+                    job.instance.format_cost(solution.total_distance)
+                );
+                *job.result.lock().unwrap() = Some(solution);
+            }
+            Err(error) => {
+                info!("Solve {} failed: {}", id, error);
+                *job.error.lock().unwrap() = Some(error);
+            }
+        }
+    });
+
+    Ok(Json(SolveStarted { id }))
+}
+
+fn job(server: &Server, id: usize) -> Result<Arc<Job>, (StatusCode, String)> {
+    server
+        .jobs
+        .lock()
+        .unwrap()
+        .get(id)
+        .ok_or_else(|| not_found("solve", id))
+}
+
+async fn get_progress(
+    State(server): State<Arc<Server>>,
+    Path(id): Path<usize>,
+) -> Response<Progress> {
+    let job = job(&server, id)?;
+    let events = job.trace.events();
+    let scale = job.instance.scale() as f64;
+    let error = job.error.lock().unwrap().as_ref().map(Error::to_string);
+    Ok(Json(Progress {
+        status: if job.result.lock().unwrap().is_some() {
+            "done"
+        } else if error.is_some() {
+            "failed"
+        } else {
+            "running"
+        },
+        elapsed_s: job.trace.elapsed().as_secs_f64(),
+        best_distance: events
+            .iter()
+            .map(|event| event.distance)
+            .min()
+            .map(|distance| distance as f64 / scale),
+        improvements: events.len(),
+        error,
+    }))
+}
+
+async fn get_tour(State(server): State<Arc<Server>>, Path(id): Path<usize>) -> Response<Tour> {
+    let job = job(&server, id)?;
+    let result = job.result.lock().unwrap();
+    let solution = result.as_ref().ok_or_else(|| {
+        let message = match &*job.error.lock().unwrap() {
+            Some(error) => format!("Solve {} failed: {}", id, error),
+            None => format!("Solve {} is still running", id),
+        };
+        (StatusCode::CONFLICT, message)
+    })?;
+    Ok(Json(Tour {
+        path: solution.path.clone(),
+        distance: solution.total_distance as f64 / job.instance.scale() as f64,
+    }))
+}
+
+async fn stop_solve(
+    State(server): State<Arc<Server>>,
+    Path(id): Path<usize>,
+) -> Response<SolveStarted> {
+    job(&server, id)?.termination.stop();
+    Ok(Json(SolveStarted { id }))
+}
+
+/// Forgets a solve, stopping it first if it is still running.
+async fn delete_solve(
+    State(server): State<Arc<Server>>,
+    Path(id): Path<usize>,
+) -> Response<SolveStarted> {
+    server
+        .jobs
+        .lock()
+        .unwrap()
+        .remove(id)
+        .ok_or_else(|| not_found("solve", id))?
+        .termination
+        .stop();
+    Ok(Json(SolveStarted { id }))
+}
+
+/// Serves the solver over HTTP on `addr` until the process is killed. Solves use the options
+/// in `defaults` unless the request overrides them, and run in the background on a pool with
+/// as many threads as the current one.
+///
+/// - `POST /instances`: TSPLIB text, or JSON `{"tsplib": ...}` or `{"matrix": [[...]]}`.
+/// - `GET /instances/{id}`: number of cities of an instance.
+/// - `DELETE /instances/{id}`: forgets an instance.
+/// - `POST /instances/{id}/solves`: starts a solve, with JSON `variant`, `construction`,
+///   `local_search` and `time_limit` (seconds), all optional.
+/// - `GET /solves/{id}`: progress and best distance so far.
+/// - `GET /solves/{id}/tour`: final tour, once the solve is done.
+/// - `POST /solves/{id}/stop`: stops a solve early.
+/// - `DELETE /solves/{id}`: stops a solve if needed and forgets it.
+///
+/// At most `MAX_INSTANCES` instances and `MAX_JOBS` solves are kept at a time.
+pub fn serve(addr: &str, defaults: SolveArgs) {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(rayon::current_num_threads())
+        .build()
+        .expect("Failed to build the thread pool");
+    let server = Arc::new(Server {
+        defaults,
+        pool,
+        instances: Mutex::new(Registry::new(MAX_INSTANCES)),
+        jobs: Mutex::new(Registry::new(MAX_JOBS)),
+    });
+
+    let app = Router::new()
+        .route("/instances", post(add_instance))
+        .route("/instances/{id}", get(get_instance).delete(delete_instance))
+        .route("/instances/{id}/solves", post(start_solve))
+        .route("/solves/{id}", get(get_progress).delete(delete_solve))
+        .route("/solves/{id}/tour", get(get_tour))
+        .route("/solves/{id}/stop", post(stop_solve))
+        .with_state(server);
+
+    tokio::runtime::Runtime::new()
+        .expect("Failed to start the async runtime")
+        .block_on(async {
+            let listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .expect("Failed to bind the server address");
+            info!("Listening on {}", addr);
+            axum::serve(listener, app).await.expect("Server failed");
+        });
+}
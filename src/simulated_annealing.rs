@@ -0,0 +1,255 @@
+use crate::driver;
+use crate::grasp::constructive_phase;
+use crate::instance::{Cost, Instance};
+use crate::local_search::LocalSearch;
+use crate::solution::Solution;
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use rand::Rng;
+
+/// Geometric cooling schedule with reheating.
+#[derive(Debug, Clone, Copy)]
+pub struct AnnealingSchedule {
+    pub initial_temperature: f64,
+    pub cooling_rate: f64,
+    pub min_temperature: f64,
+    /// Number of times the temperature is reset once it drops below `min_temperature`.
+    pub max_reheats: usize,
+}
+
+impl AnnealingSchedule {
+    /// Schedule whose initial temperature is the mean edge length of the instance.
+    pub fn for_instance(instance: &Instance, cooling_rate: f64, max_reheats: usize) -> Self {
+        let mean = instance.mean_distance();
+
+        AnnealingSchedule {
+            initial_temperature: mean.max(1.0),
+            cooling_rate,
+            min_temperature: 0.01,
+            max_reheats,
+        }
+    }
+}
+
+/// Single cooling cycle (no reheating) used as an improvement phase.
+pub struct Annealing {
+    pub cooling_rate: f64,
+}
+
+impl LocalSearch for Annealing {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        let schedule = AnnealingSchedule::for_instance(instance, self.cooling_rate, 0);
+        solution.eval(instance);
+        anneal(solution, instance, &schedule, &|| false);
+    }
+
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        let schedule = AnnealingSchedule::for_instance(instance, self.cooling_rate, 0);
+        solution.eval(instance);
+        anneal(solution, instance, &schedule, should_stop)
+    }
+
+    fn name(&self) -> &str {
+        "SA"
+    }
+}
+
+/// Moves an annealing chain makes between checks of the stop criteria, which count as one
+/// iteration of the simulated annealing driver.
+const MOVES_PER_CHECK: usize = 1024;
+
+/// Simulated annealing driver running one annealing chain per worker until the time limit.
+pub fn simulated_annealing(
+    instance: &Instance,
+    termination: &Termination,
+    cooling_rate: f64,
+    trace: &Trace,
+) -> Solution {
+    let schedule = AnnealingSchedule::for_instance(instance, cooling_rate, usize::MAX);
+    let mut workers: Vec<Option<Chain>> = driver::workers();
+    let mut best: Option<Solution> = None;
+
+    let mut iteration = 0;
+    while !termination.should_stop() {
+        // Each iteration returns the best tour of the worker's chain when it improved.
+        let batch = driver::batch_with(&mut workers, termination, trace, |_, chain| {
+            let Some(chain) = chain else {
+                let mut initial = constructive_phase(instance);
+                initial.eval(instance);
+                *chain = Some(Chain::new(initial.copy(), &schedule));
+                return Some(initial);
+            };
+            let previous = chain.best.total_distance;
+            chain.advance(instance, &schedule, MOVES_PER_CHECK);
+            (chain.best.total_distance < previous).then(|| chain.best.copy())
+        });
+
+        for (thread, improved) in batch {
+            iteration += 1;
+            let Some(solution) = improved else {
+                continue;
+            };
+            if best
+                .as_ref()
+                .is_none_or(|best| solution.total_distance < best.total_distance)
+            {
+                termination.report(solution.total_distance);
+                trace.record_improvement(iteration, thread, &solution, instance);
+                best = Some(solution);
+            }
+        }
+    }
+
+    best.expect("There should be at least one solution")
+}
+
+/// Anneals `solution` in place by sampling random 2-opt and swap moves, leaving the best tour
+/// found in `solution`. Stops when the schedule is exhausted or `should_stop` returns true, and
+/// returns whether it was the latter.
+pub fn anneal(
+    solution: &mut Solution,
+    instance: &Instance,
+    schedule: &AnnealingSchedule,
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
+    let mut chain = Chain::new(solution.copy(), schedule);
+    let mut truncated = false;
+    while !chain.advance(instance, schedule, MOVES_PER_CHECK) {
+        if should_stop() {
+            truncated = true;
+            break;
+        }
+    }
+    *solution = chain.best;
+    truncated
+}
+
+/// Annealing chain that can be advanced a number of moves at a time.
+pub struct Chain {
+    solution: Solution,
+    /// Best tour found by the chain.
+    pub best: Solution,
+    temperature: f64,
+    reheats: usize,
+}
+
+impl Chain {
+    pub fn new(solution: Solution, schedule: &AnnealingSchedule) -> Self {
+        Chain {
+            best: solution.copy(),
+            solution,
+            temperature: schedule.initial_temperature,
+            reheats: 0,
+        }
+    }
+
+    /// Makes up to `moves` moves, returning whether the schedule is exhausted.
+    pub fn advance(
+        &mut self,
+        instance: &Instance,
+        schedule: &AnnealingSchedule,
+        moves: usize,
+    ) -> bool {
+        if self.solution.path.len() < 4 {
+            return true;
+        }
+        let end = instance.movable_end();
+        let mut rng = crate::rng::thread_rng();
+        let solution = &mut self.solution;
+
+        for _ in 0..moves {
+            if self.temperature < schedule.min_temperature {
+                if self.reheats >= schedule.max_reheats {
+                    return true;
+                }
+                self.reheats += 1;
+                self.temperature = schedule.initial_temperature;
+                *solution = self.best.copy();
+            }
+
+            let i = rng.gen_range(1..end - 1);
+            let j = rng.gen_range(i + 1..end);
+
+            let two_opt = rng.gen_bool(0.5);
+            let delta = if two_opt {
+                two_opt_delta(solution, instance, i, j)
+            } else {
+                swap_delta(solution, instance, i, j)
+            };
+
+            if delta <= 0 || rng.gen::<f64>() < (-(delta as f64) / self.temperature).exp() {
+                if two_opt {
+                    solution.path[i..=j].reverse();
+                } else {
+                    solution.path.swap(i, j);
+                }
+                solution.total_distance += delta;
+
+                if solution.total_distance < self.best.total_distance {
+                    self.best = solution.copy();
+                }
+            }
+
+            self.temperature *= schedule.cooling_rate;
+        }
+        false
+    }
+}
+
+/// Cost change of reversing `path[i..=j]`.
+fn two_opt_delta(solution: &Solution, instance: &Instance, i: usize, j: usize) -> Cost {
+    let n = solution.path.len();
+    let prev = solution.path[i - 1];
+    let next = solution.path[(j + 1) % n];
+    let a = solution.path[i];
+    let b = solution.path[j];
+
+    if j + 1 == n {
+        instance.dist(prev, b) + instance.closing_distance(a, next)
+            - instance.dist(prev, a)
+            - instance.closing_distance(b, next)
+    } else {
+        instance.dist(prev, b) + instance.dist(a, next)
+            - instance.dist(prev, a)
+            - instance.dist(b, next)
+    }
+}
+
+/// Cost change of swapping the cities at positions `i` and `j`.
+fn swap_delta(solution: &Solution, instance: &Instance, i: usize, j: usize) -> Cost {
+    let n = solution.path.len();
+    let mut edges = [i - 1, i, j - 1, j];
+    edges.sort_unstable();
+
+    let city = |k: usize, swapped: bool| {
+        let k = k % n;
+        match (swapped, k) {
+            (true, k) if k == i => solution.path[j],
+            (true, k) if k == j => solution.path[i],
+            _ => solution.path[k],
+        }
+    };
+    let cost = |swapped: bool| -> Cost {
+        edges
+            .iter()
+            .enumerate()
+            .filter(|&(idx, k)| idx == 0 || edges[idx - 1] != *k)
+            .map(|(_, &k)| {
+                let (from, to) = (city(k, swapped), city(k + 1, swapped));
+                if k + 1 == n {
+                    instance.closing_distance(from, to)
+                } else {
+                    instance.dist(from, to)
+                }
+            })
+            .sum()
+    };
+
+    cost(true) - cost(false)
+}
@@ -1,16 +1,107 @@
-use crate::{instance::Instance, local_search::local_search_2opt};
+use crate::{
+    error::{Error, ErrorKind},
+    grasp::symmetric_difference,
+    instance::{Cost, Instance},
+    local_search::LocalSearch,
+    objective::Objective,
+};
+
+use clap::ValueEnum;
+use rand::seq::SliceRandom;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use tracing::warn;
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Solution {
     pub path: Vec<usize>,
-    pub total_distance: i32,
+    pub total_distance: Cost,
+    /// Longest edge of the tour, only tracked by `eval` for the bottleneck objective.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub longest_edge: Cost,
+    /// Risk term of the tour length, only tracked by `eval` for the robust objective.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub risk: Cost,
+}
+
+/// When path relinking runs the local search on its intermediate solutions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PrLocalSearch {
+    /// Never: the intermediate solutions are only evaluated.
+    None,
+    /// Once, on the best intermediate solution at the end of the walk.
+    BestOnly,
+    /// On every `k`-th intermediate solution.
+    Every(usize),
+}
+
+/// When path relinking stops walking towards the guiding solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PrStop {
+    /// At the first intermediate solution better than the initial one.
+    FirstImprove,
+    /// Only once the walk is over, keeping the best intermediate solution.
+    FullWalk,
+}
+
+/// How path relinking picks the next position to restore from the guiding solution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PrSelection {
+    /// The next differing position, from the start of the tour.
+    Sequential,
+    /// The restoring swap giving the shortest intermediate solution.
+    Greedy,
+    /// A random restoring swap among those whose cost is within `alpha` (a fraction of the
+    /// range between the best and the worst swap) of the best one.
+    GreedyRandomized(f64),
+}
+
+/// Reason why a solution is not a consistent tour of an instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolutionError {
+    /// The path does not visit as many cities as the instance has.
+    WrongLength { expected: usize, found: usize },
+    /// A city index outside of `0..n`.
+    UnknownCity(usize),
+    /// A city visited more than once.
+    DuplicateCity(usize),
+    /// `total_distance` differs from the length of the path.
+    DistanceMismatch { stored: Cost, actual: Cost },
+}
+
+impl fmt::Display for SolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolutionError::WrongLength { expected, found } => {
+                write!(f, "path visits {} cities, instance has {}", found, expected)
+            }
+            SolutionError::UnknownCity(city) => write!(f, "city {} does not exist", city),
+            SolutionError::DuplicateCity(city) => {
+                write!(f, "city {} is visited more than once", city)
+            }
+            SolutionError::DistanceMismatch { stored, actual } => write!(
+                f,
+                "total distance is {} but the path has length {}",
+                stored, actual
+            ),
+        }
+    }
 }
 
+impl std::error::Error for SolutionError {}
+
 impl Solution {
     pub fn new(num_cities: usize) -> Self {
         Solution {
             path: Vec::with_capacity(num_cities),
             total_distance: 0,
+            longest_edge: 0,
+            risk: 0,
         }
     }
 
@@ -18,50 +109,465 @@ impl Solution {
         if self.path.len() != instance.num_cities {
             panic!("Path length does not match the number of cities in the instance");
         }
-        self.total_distance = 0;
-
-        for i in 0..self.path.len() - 1 {
-            let from = self.path[i];
-            let to = self.path[i + 1];
-            self.total_distance += instance.distances[from][to]
+        self.total_distance = self.length(instance);
+        match instance.objective {
+            Objective::Total => {}
+            Objective::Bottleneck => self.longest_edge = self.longest_edge(instance),
+            Objective::Robust => self.risk = instance.risk(instance.tour_variance(&self.path)),
         }
+    }
 
+    /// Length of the path on `instance`, including the closing edge of closed tours.
+    fn length(&self, instance: &Instance) -> Cost {
         let last = *self.path.last().unwrap();
         let first = self.path[0];
-        self.total_distance += instance.distances[last][first]
+        instance.distance_offset
+            + instance.path_length(&self.path)
+            + instance.closing_distance(last, first)
+    }
+
+    /// Longest edge of the path on `instance`, including the closing edge of closed tours.
+    fn longest_edge(&self, instance: &Instance) -> Cost {
+        let closing = instance.closing_distance(*self.path.last().unwrap(), self.path[0]);
+        self.path
+            .windows(2)
+            .map(|pair| instance.dist(pair[0], pair[1]))
+            .fold(closing, Cost::max)
+    }
+
+    /// Checks that the path is a permutation of the instance's cities and that
+    /// `total_distance` is its length.
+    pub fn validate(&self, instance: &Instance) -> Result<(), SolutionError> {
+        self.validate_path(instance)?;
+        let actual = self.length(instance);
+        if actual != self.total_distance {
+            return Err(SolutionError::DistanceMismatch {
+                stored: self.total_distance,
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that the path is a permutation of the instance's cities.
+    pub fn validate_path(&self, instance: &Instance) -> Result<(), SolutionError> {
+        let n = instance.num_cities;
+        if self.path.len() != n {
+            return Err(SolutionError::WrongLength {
+                expected: n,
+                found: self.path.len(),
+            });
+        }
+
+        let mut seen = vec![false; n];
+        for &city in &self.path {
+            if city >= n {
+                return Err(SolutionError::UnknownCity(city));
+            }
+            if std::mem::replace(&mut seen[city], true) {
+                return Err(SolutionError::DuplicateCity(city));
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads a tour in TSPLIB `.tour` format (1-based city ids in TOUR_SECTION, ended by -1).
+    pub fn load_tour(filename: &str) -> Result<Self, Error> {
+        let invalid = |message: String| Error::new(ErrorKind::Instance, message);
+        let content = std::fs::read_to_string(filename)
+            .map_err(|error| invalid(format!("Failed to read {}: {}", filename, error)))?;
+        let mut path = Vec::new();
+        let mut in_section = false;
+
+        for line in content.lines() {
+            if line.starts_with("TOUR_SECTION") {
+                in_section = true;
+                continue;
+            }
+            if !in_section {
+                continue;
+            }
+            for value in line.split_whitespace() {
+                let id: i64 = value.parse().map_err(|_| {
+                    invalid(format!("Invalid city in the tour {}: {}", filename, value))
+                })?;
+                if id < 0 {
+                    in_section = false;
+                    break;
+                }
+                if id == 0 {
+                    return Err(invalid(format!("Invalid city in the tour {}: 0", filename)));
+                }
+                path.push(id as usize - 1);
+            }
+        }
+
+        Ok(Solution {
+            path,
+            total_distance: 0,
+            longest_edge: 0,
+            risk: 0,
+        })
+    }
+
+    /// Loads a tour with `load_tour` and evaluates it on `instance`, failing if it is not a
+    /// permutation of its cities.
+    pub fn load_valid_tour(filename: &str, instance: &Instance) -> Result<Self, Error> {
+        let mut tour = Self::load_tour(filename)?;
+        tour.validate_path(instance).map_err(|error| {
+            Error::new(
+                ErrorKind::InvalidTour,
+                format!("Invalid tour {}: {}", filename, error),
+            )
+        })?;
+        tour.eval(instance);
+        Ok(tour)
+    }
+
+    /// Moves the instance's fixed start city to position 0 (rotating the tour) and its fixed
+    /// end city to the last position. Does not re-evaluate the tour.
+    pub fn fix_endpoints(&mut self, instance: &Instance) {
+        if let Some(start) = instance.start_city {
+            let position = self.path.iter().position(|&city| city == start).unwrap();
+            self.path.rotate_left(position);
+        }
+        if let Some(end) = instance.end_city {
+            let position = self.path.iter().position(|&city| city == end).unwrap();
+            self.path[position..].rotate_left(1);
+        }
+    }
+
+    /// Position to restore in a greedy path relinking step: a random one among the swaps whose
+    /// cost is within `alpha` of the best swap, or `n` if `self` already equals `target`.
+    fn greedy_position(
+        &self,
+        target: &Solution,
+        instance: &Instance,
+        position: &[usize],
+        alpha: f64,
+    ) -> usize {
+        let n = self.path.len();
+        let moves: Vec<(Cost, usize)> = (0..n)
+            .filter(|&i| self.path[i] != target.path[i])
+            .map(|i| (self.swap_delta(instance, i, position[target.path[i]]), i))
+            .collect();
+        let Some(&(min, first)) = moves.iter().min() else {
+            return n;
+        };
+        if alpha <= 0.0 {
+            return first;
+        }
+
+        let max = moves.iter().map(|&(delta, _)| delta).max().unwrap();
+        let threshold = min + (alpha * (max - min) as f64) as Cost;
+        let candidates: Vec<usize> = moves
+            .iter()
+            .filter(|&&(delta, _)| delta <= threshold)
+            .map(|&(_, i)| i)
+            .collect();
+        *candidates.choose(&mut crate::rng::thread_rng()).unwrap()
+    }
+
+    /// Change in length caused by swapping the cities at positions `i` and `j`.
+    fn swap_delta(&self, instance: &Instance, i: usize, j: usize) -> Cost {
+        let n = self.path.len();
+        let swapped = |k: usize| match k {
+            k if k == i => self.path[j],
+            k if k == j => self.path[i],
+            k => self.path[k],
+        };
+        // Edge k joins positions k and k + 1, and edge n - 1 closes the tour.
+        let edge = |city: &dyn Fn(usize) -> usize, k: usize| {
+            if k + 1 < n {
+                instance.dist(city(k), city(k + 1))
+            } else {
+                instance.closing_distance(city(k), city(0))
+            }
+        };
+
+        let mut edges = [(i + n - 1) % n, i, (j + n - 1) % n, j];
+        edges.sort_unstable();
+        let mut delta = 0;
+        for (index, &k) in edges.iter().enumerate() {
+            if index == 0 || edges[index - 1] != k {
+                delta += edge(&swapped, k) - edge(&|k| self.path[k], k);
+            }
+        }
+        delta
+    }
+
+    /// Fills `position` so that `position[city]` is the index of `city` in the path.
+    fn index_positions(&self, position: &mut [usize]) {
+        for (index, &city) in self.path.iter().enumerate() {
+            position[city] = index;
+        }
     }
 
     pub fn copy(&self) -> Self {
         Solution {
             path: self.path.clone(),
             total_distance: self.total_distance,
+            longest_edge: self.longest_edge,
+            risk: self.risk,
         }
     }
 
-    pub fn path_relinking(&mut self, target: &Solution, instance: &Instance) {
-        let mut best_distance = self.total_distance;
+    /// Path relinking from `self` towards `target`: every step restores one position of
+    /// `target`, and the walk ends once it reaches `target` or after `depth` (a fraction in
+    /// `(0, 1]`) of as many steps as the tours have differing edges, so that below 1 it may
+    /// stop short of `target`. `ls` decides which intermediate solutions are improved with
+    /// `local_search`, `stop` whether the walk ends at the first improvement and `selection`
+    /// which position is restored at each step. `self` ends as the best solution of the walk.
+    #[allow(clippy::too_many_arguments)]
+    pub fn path_relinking(
+        &mut self,
+        target: &Solution,
+        instance: &Instance,
+        local_search: &dyn LocalSearch,
+        depth: f64,
+        ls: PrLocalSearch,
+        stop: PrStop,
+        selection: PrSelection,
+    ) {
+        let objective = instance.objective;
+        let initial_key = objective.key(self);
+        let mut best_key = initial_key;
         let mut best_path = self.path.clone();
 
-        for i in 0..self.path.len() {
-            if self.path[i] != target.path[i] {
-                let target_index = self.path.iter().position(|&x| x == target.path[i]).unwrap();
-                self.path.swap(i, target_index);
-                self.eval(instance);
+        let difference = symmetric_difference(self, target);
+        let max_steps = (difference as f64 * depth).ceil() as usize;
+        let mut steps = 0;
 
-                local_search_2opt(self, instance);
+        // position[city] is the index of `city` in `self.path`.
+        let mut position = vec![0; self.path.len()];
+        self.index_positions(&mut position);
 
-                if self.total_distance < best_distance {
-                    best_distance = self.total_distance;
-                    best_path = self.path.clone();
+        let n = self.path.len();
+        let mut next = 0;
+        while steps < max_steps {
+            let i = match selection {
+                PrSelection::Sequential => {
+                    while next < n && self.path[next] == target.path[next] {
+                        next += 1;
+                    }
+                    next
+                }
+                PrSelection::Greedy => self.greedy_position(target, instance, &position, 0.0),
+                PrSelection::GreedyRandomized(alpha) => {
+                    self.greedy_position(target, instance, &position, alpha)
                 }
+            };
+            if i == n {
+                break;
+            }
 
-                if best_distance == self.total_distance {
-                    break;
+            let target_index = position[target.path[i]];
+            self.path.swap(i, target_index);
+            position[self.path[i]] = i;
+            position[self.path[target_index]] = target_index;
+            self.eval(instance);
+            steps += 1;
+
+            if let PrLocalSearch::Every(k) = ls {
+                if steps.is_multiple_of(k.max(1)) {
+                    local_search.improve(self, instance);
+                    self.eval(instance);
+                    self.index_positions(&mut position);
                 }
             }
+
+            if objective.key(self) < best_key {
+                best_key = objective.key(self);
+                best_path = self.path.clone();
+            }
+
+            if stop == PrStop::FirstImprove && best_key < initial_key {
+                break;
+            }
         }
 
         self.path = best_path;
-        self.total_distance = best_distance;
+        self.eval(instance);
+
+        if ls == PrLocalSearch::BestOnly {
+            local_search.improve(self, instance);
+            self.eval(instance);
+        }
+        debug_assert_eq!(self.validate(instance), Ok(()));
+    }
+}
+
+/// Writes `solutions` as one message: a `<distance> <city>...` line per solution followed by an
+/// empty line.
+pub fn write_solutions(writer: &mut impl Write, solutions: &[Solution]) -> io::Result<()> {
+    for solution in solutions {
+        let path: Vec<String> = solution.path.iter().map(|c| c.to_string()).collect();
+        writeln!(writer, "{} {}", solution.total_distance, path.join(" "))?;
+    }
+    writeln!(writer)?;
+    writer.flush()
+}
+
+/// Reads one message written by `write_solutions`, or `None` at the end of the input. Tours are
+/// dropped if they are not permutations of the cities of `instance`, and otherwise have its
+/// fixed endpoints moved into place and are re-evaluated on it.
+pub fn read_solutions(reader: &mut impl BufRead, instance: &Instance) -> Option<Vec<Solution>> {
+    let mut solutions = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return (!solutions.is_empty()).then_some(solutions);
+        }
+        if line.trim().is_empty() {
+            return Some(solutions);
+        }
+
+        let path: Vec<usize> = line
+            .split_whitespace()
+            .skip(1)
+            .filter_map(|city| city.parse().ok())
+            .collect();
+        let mut solution = Solution {
+            path,
+            total_distance: 0,
+            longest_edge: 0,
+            risk: 0,
+        };
+        if solution.validate_path(instance).is_err() {
+            warn!("Dropping a tour that is not valid for this instance");
+            continue;
+        }
+        solution.fix_endpoints(instance);
+        solution.eval(instance);
+        solutions.push(solution);
+    }
+}
+
+/// Saves `solutions` (e.g. an elite set) to `filename` in the format of `write_solutions`.
+pub fn save_solutions(filename: &str, solutions: &[Solution]) -> Result<(), Error> {
+    File::create(filename)
+        .and_then(|file| write_solutions(&mut BufWriter::new(file), solutions))
+        .map_err(|error| Error::output(filename, error))
+}
+
+/// Saves an elite set to `filename` as JSON: the distance and tour of every solution, and the
+/// matrix of the edge differences between each pair of solutions.
+#[cfg(feature = "json")]
+pub fn save_elite_json(
+    filename: &str,
+    elite_set: &[Solution],
+    instance: &Instance,
+) -> Result<(), Error> {
+    #[derive(serde::Serialize)]
+    struct EliteSolution<'a> {
+        /// Formatted with the precision of the instance.
+        distance: Option<serde_json::Number>,
+        path: &'a [usize],
+    }
+
+    #[derive(serde::Serialize)]
+    struct EliteSet<'a> {
+        solutions: Vec<EliteSolution<'a>>,
+        differences: Vec<Vec<usize>>,
+    }
+
+    let elite = EliteSet {
+        solutions: elite_set
+            .iter()
+            .map(|solution| EliteSolution {
+                distance: instance.format_cost(solution.total_distance).parse().ok(),
+                path: &solution.path,
+            })
+            .collect(),
+        differences: elite_set
+            .iter()
+            .map(|a| {
+                elite_set
+                    .iter()
+                    .map(|b| symmetric_difference(a, b))
+                    .collect()
+            })
+            .collect(),
+    };
+
+    let file = File::create(filename).map_err(|error| Error::output(filename, error))?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer(&mut writer, &elite).map_err(|error| Error::output(filename, error))?;
+    writer
+        .flush()
+        .map_err(|error| Error::output(filename, error))
+}
+
+/// Loads the solutions saved by `save_solutions` that are valid tours of `instance`.
+pub fn load_solutions(filename: &str, instance: &Instance) -> Result<Vec<Solution>, Error> {
+    let file = File::open(filename).map_err(|error| {
+        Error::new(
+            ErrorKind::Instance,
+            format!("Failed to read {}: {}", filename, error),
+        )
+    })?;
+    Ok(read_solutions(&mut BufReader::new(file), instance).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 3-4-5 rectangle, whose perimeter is 14.
+    fn rectangle() -> Instance {
+        Instance::from_coords(vec![(0.0, 0.0), (0.0, 3.0), (4.0, 3.0), (4.0, 0.0)], 0)
+    }
+
+    fn tour(path: Vec<usize>, total_distance: Cost) -> Solution {
+        Solution {
+            path,
+            total_distance,
+            longest_edge: 0,
+            risk: 0,
+        }
+    }
+
+    #[test]
+    fn validate_accepts_a_consistent_tour() {
+        assert_eq!(tour(vec![0, 1, 2, 3], 14).validate(&rectangle()), Ok(()));
+    }
+
+    #[test]
+    fn validate_rejects_a_path_of_the_wrong_length() {
+        assert_eq!(
+            tour(vec![0, 1, 2], 14).validate(&rectangle()),
+            Err(SolutionError::WrongLength {
+                expected: 4,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn validate_rejects_an_unknown_city() {
+        assert_eq!(
+            tour(vec![0, 1, 2, 4], 14).validate(&rectangle()),
+            Err(SolutionError::UnknownCity(4))
+        );
+    }
+
+    #[test]
+    fn validate_rejects_a_duplicate_city() {
+        assert_eq!(
+            tour(vec![0, 1, 1, 3], 14).validate(&rectangle()),
+            Err(SolutionError::DuplicateCity(1))
+        );
+    }
+
+    #[test]
+    fn validate_rejects_a_wrong_total_distance() {
+        assert_eq!(
+            tour(vec![0, 2, 1, 3], 14).validate(&rectangle()),
+            Err(SolutionError::DistanceMismatch {
+                stored: 14,
+                actual: 18
+            })
+        );
     }
 }
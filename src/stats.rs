@@ -0,0 +1,240 @@
+//! Descriptive statistics of experiment results.
+
+/// Summary statistics of a sample.
+#[derive(Debug, Clone, Copy)]
+pub struct Summary {
+    pub mean: f64,
+    pub median: f64,
+    /// Sample standard deviation, 0 for a single value.
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Summary {
+    /// Summary of `values`, which must not be empty.
+    pub fn of(values: &[f64]) -> Self {
+        assert!(!values.is_empty(), "Cannot summarize an empty sample");
+        let n = values.len();
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let mean = values.iter().sum::<f64>() / n as f64;
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+        } else {
+            sorted[n / 2]
+        };
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>()
+            / n.saturating_sub(1).max(1) as f64;
+
+        Summary {
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+            min: sorted[0],
+            max: sorted[n - 1],
+        }
+    }
+}
+
+/// Largest number of nonzero differences for which the signed-rank test computes the exact
+/// null distribution; larger samples use the normal approximation.
+const EXACT_MAX_PAIRS: usize = 100;
+
+/// Result of a Wilcoxon signed-rank test.
+#[derive(Debug, Clone, Copy)]
+pub struct SignedRankTest {
+    /// Pairs with a nonzero difference, the only ones the test uses.
+    pub pairs: usize,
+    /// Sum of the ranks of the positive differences `a - b`.
+    pub w_plus: f64,
+    /// Two-sided p-value of the hypothesis that the differences are symmetric around zero.
+    pub p_value: f64,
+}
+
+/// Paired Wilcoxon signed-rank test of `a` against `b`. Zero differences are dropped and tied
+/// absolute differences get their average rank. The p-value is exact (conditional on the ties)
+/// up to `EXACT_MAX_PAIRS` pairs and uses the normal approximation with tie and continuity
+/// corrections beyond.
+pub fn wilcoxon_signed_rank(a: &[f64], b: &[f64]) -> SignedRankTest {
+    assert_eq!(a.len(), b.len(), "The samples must be paired");
+    let mut differences: Vec<f64> = a
+        .iter()
+        .zip(b)
+        .map(|(x, y)| x - y)
+        .filter(|d| *d != 0.0)
+        .collect();
+    differences.sort_by(|x, y| x.abs().total_cmp(&y.abs()));
+    let n = differences.len();
+    if n == 0 {
+        return SignedRankTest {
+            pairs: 0,
+            w_plus: 0.0,
+            p_value: 1.0,
+        };
+    }
+
+    // Doubled ranks are integers even when ties average them.
+    let mut doubled_ranks = vec![0; n];
+    let mut tie_correction = 0.0;
+    let mut start = 0;
+    while start < n {
+        let mut end = start + 1;
+        while end < n && differences[end].abs() == differences[start].abs() {
+            end += 1;
+        }
+        let ties = (end - start) as f64;
+        tie_correction += ties.powi(3) - ties;
+        doubled_ranks[start..end].fill(start + end + 1);
+        start = end;
+    }
+    let doubled_w_plus: usize = differences
+        .iter()
+        .zip(&doubled_ranks)
+        .filter(|(d, _)| **d > 0.0)
+        .map(|(_, rank)| rank)
+        .sum();
+
+    let p_value = if n <= EXACT_MAX_PAIRS {
+        // Null distribution of the doubled W+: every rank is positive with probability 1/2.
+        let total: usize = doubled_ranks.iter().sum();
+        let mut probability = vec![0.0; total + 1];
+        probability[0] = 1.0;
+        for &rank in &doubled_ranks {
+            for sum in (0..=total).rev() {
+                let with_rank = if sum >= rank {
+                    probability[sum - rank]
+                } else {
+                    0.0
+                };
+                probability[sum] = (probability[sum] + with_rank) / 2.0;
+            }
+        }
+        let lower: f64 = probability[..=doubled_w_plus].iter().sum();
+        let upper: f64 = probability[doubled_w_plus..].iter().sum();
+        (2.0 * lower.min(upper)).min(1.0)
+    } else {
+        let n = n as f64;
+        let w_plus = doubled_w_plus as f64 / 2.0;
+        let mean = n * (n + 1.0) / 4.0;
+        let variance = n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - tie_correction / 48.0;
+        let z = ((w_plus - mean).abs() - 0.5).max(0.0) / variance.sqrt();
+        (2.0 * (1.0 - standard_normal_cdf(z))).min(1.0)
+    };
+
+    SignedRankTest {
+        pairs: n,
+        w_plus: doubled_w_plus as f64 / 2.0,
+        p_value,
+    }
+}
+
+/// Standard normal cumulative distribution, from the Abramowitz and Stegun approximation of
+/// the error function (absolute error below 1.5e-7).
+fn standard_normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 {
+        (1.0 + erf) / 2.0
+    } else {
+        (1.0 - erf) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} is not within {} of {}",
+            actual,
+            tolerance,
+            expected
+        );
+    }
+
+    #[test]
+    fn summary_of_a_sample() {
+        let summary = Summary::of(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert_eq!(summary.mean, 5.0);
+        assert_eq!(summary.median, 4.5);
+        assert_close(summary.std_dev, (32.0f64 / 7.0).sqrt(), 1e-12);
+        assert_eq!((summary.min, summary.max), (2.0, 9.0));
+    }
+
+    #[test]
+    fn summary_of_a_single_value() {
+        let summary = Summary::of(&[3.0]);
+        assert_eq!(
+            (summary.mean, summary.median, summary.std_dev),
+            (3.0, 3.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn signed_rank_exact_all_positive() {
+        // Smallest attainable two-sided p-value for 5 pairs: 2 / 2^5.
+        let test = wilcoxon_signed_rank(&[1.0, 2.0, 3.0, 4.0, 5.0], &[0.0; 5]);
+        assert_eq!(test.pairs, 5);
+        assert_eq!(test.w_plus, 15.0);
+        assert_close(test.p_value, 0.0625, 1e-12);
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)] // The 3.14 is a score, not pi.
+    fn signed_rank_exact_hollander_wolfe() {
+        // Depression scores of Hollander & Wolfe (1973), p. 29: V = 40, p = 0.039.
+        let x = [1.83, 0.50, 1.62, 2.48, 1.68, 1.88, 1.55, 3.06, 1.30];
+        let y = [0.878, 0.647, 0.598, 2.05, 1.06, 1.29, 1.06, 3.14, 1.29];
+        let test = wilcoxon_signed_rank(&x, &y);
+        assert_eq!(test.pairs, 9);
+        assert_eq!(test.w_plus, 40.0);
+        assert_close(test.p_value, 20.0 / 512.0, 1e-12);
+    }
+
+    #[test]
+    fn signed_rank_exact_with_ties_and_zeros() {
+        // Absolute differences 1, 1, 2, 2, 3, 4, 5 rank 1.5, 1.5, 3.5, 3.5, 5, 6, 7, and 22 of
+        // the 128 sign assignments are at least as extreme as W+ = 23.
+        let a = [3.0, 1.0, -2.0, 4.0, -1.0, 2.0, 0.0, 5.0];
+        let test = wilcoxon_signed_rank(&a, &[0.0; 8]);
+        assert_eq!(test.pairs, 7);
+        assert_eq!(test.w_plus, 23.0);
+        assert_close(test.p_value, 22.0 / 128.0, 1e-12);
+    }
+
+    #[test]
+    fn signed_rank_without_differences() {
+        let test = wilcoxon_signed_rank(&[1.0, 2.0], &[1.0, 2.0]);
+        assert_eq!(test.pairs, 0);
+        assert_eq!(test.p_value, 1.0);
+    }
+
+    #[test]
+    fn signed_rank_normal_approximation() {
+        // Differences 1..=120, negative at every multiple of 3: W+ = 4800, z = 3.0628.
+        let a: Vec<f64> = (1..=120)
+            .map(|i| if i % 3 == 0 { -i as f64 } else { i as f64 })
+            .collect();
+        let test = wilcoxon_signed_rank(&a, &[0.0; 120]);
+        assert_eq!(test.pairs, 120);
+        assert_eq!(test.w_plus, 4800.0);
+        assert_close(test.p_value, 0.002_193_004, 1e-6);
+    }
+
+    #[test]
+    fn normal_cdf_table_values() {
+        assert_close(standard_normal_cdf(0.0), 0.5, 1e-7);
+        assert_close(standard_normal_cdf(1.0), 0.841_344_7, 1e-6);
+        assert_close(standard_normal_cdf(1.96), 0.975_002_1, 1e-6);
+        assert_close(standard_normal_cdf(-1.645), 0.049_984_9, 1e-6);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::error::{Error, ErrorKind};
+use crate::instance::Instance;
+use crate::solution::Solution;
+
+use std::fmt::Write;
+
+const SIZE: f64 = 800.0;
+const MARGIN: f64 = 20.0;
+
+/// Renders `solution` as an SVG with nodes and tour edges, optionally overlaying a reference
+/// tour (e.g. the best-known one) as a dashed line. Requires node coordinates.
+pub fn write_svg(
+    filename: &str,
+    instance: &Instance,
+    solution: &Solution,
+    reference: Option<&Solution>,
+) -> Result<(), Error> {
+    let svg = render_svg(instance, solution, reference).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidArguments,
+            "SVG plotting requires node coordinates",
+        )
+    })?;
+    std::fs::write(filename, svg).map_err(|error| Error::output(filename, error))
+}
+
+/// The SVG document of `write_svg`, or `None` when the instance has no coordinates.
+pub fn render_svg(
+    instance: &Instance,
+    solution: &Solution,
+    reference: Option<&Solution>,
+) -> Option<String> {
+    let coords = instance.coords()?;
+
+    let (min_x, max_x) = coords
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &(x, _)| {
+            (lo.min(x), hi.max(x))
+        });
+    let (min_y, max_y) = coords
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &(_, y)| {
+            (lo.min(y), hi.max(y))
+        });
+    let scale = (SIZE - 2.0 * MARGIN) / (max_x - min_x).max(max_y - min_y).max(f64::EPSILON);
+
+    let project = |city: usize| {
+        let (x, y) = coords[city];
+        (
+            MARGIN + (x - min_x) * scale,
+            SIZE - MARGIN - (y - min_y) * scale,
+        )
+    };
+
+    let polygon = |tour: &Solution| {
+        tour.path
+            .iter()
+            .map(|&city| {
+                let (x, y) = project(city);
+                format!("{:.2},{:.2}", x, y)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let shape = if instance.open_tour {
+        "polyline"
+    } else {
+        "polygon"
+    };
+
+    let mut writer = String::new();
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}">"#,
+        SIZE
+    )
+    .expect("Failed to write SVG");
+    writeln!(writer, r#"<rect width="100%" height="100%" fill="white"/>"#)
+        .expect("Failed to write SVG");
+
+    if let Some(reference) = reference {
+        writeln!(
+            writer,
+            r#"<{} points="{}" fill="none" stroke="green" stroke-width="2" stroke-dasharray="6,4" opacity="0.7"/>"#,
+            shape,
+            polygon(reference)
+        )
+        .expect("Failed to write SVG");
+    }
+
+    writeln!(
+        writer,
+        r#"<{} points="{}" fill="none" stroke="steelblue" stroke-width="1.5"/>"#,
+        shape,
+        polygon(solution)
+    )
+    .expect("Failed to write SVG");
+
+    for city in 0..instance.num_cities {
+        let (x, y) = project(city);
+        writeln!(
+            writer,
+            r#"<circle cx="{:.2}" cy="{:.2}" r="3" fill="crimson"><title>{}</title></circle>"#,
+            x,
+            y,
+            city + 1
+        )
+        .expect("Failed to write SVG");
+    }
+
+    writeln!(
+        writer,
+        r#"<text x="{}" y="{}" font-family="monospace" font-size="14">distance = {}</text>"#,
+        MARGIN, MARGIN, solution.total_distance
+    )
+    .expect("Failed to write SVG");
+    writeln!(writer, "</svg>").expect("Failed to write SVG");
+    Some(writer)
+}
@@ -0,0 +1,166 @@
+use crate::driver;
+use crate::grasp::constructive_phase;
+use crate::instance::{Cost, Instance};
+use crate::local_search::LocalSearch;
+use crate::solution::Solution;
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+/// Tabu search driver: each worker repeatedly builds a tour and improves it with tabu search
+/// until the time limit.
+pub fn tabu_search(
+    instance: &Instance,
+    termination: &Termination,
+    tenure: usize,
+    max_iterations: usize,
+    trace: &Trace,
+) -> Solution {
+    let mut best: Option<Solution> = None;
+
+    let mut iteration = 0;
+    while !termination.should_stop() {
+        let batch = driver::batch(termination, trace, |_| {
+            let mut solution = constructive_phase(instance);
+            solution.eval(instance);
+            if local_search_tabu(&mut solution, instance, tenure, max_iterations, &|| {
+                termination.should_stop()
+            }) {
+                trace.count_truncated();
+            }
+            solution
+        });
+
+        for (thread, solution) in batch {
+            iteration += 1;
+            if best
+                .as_ref()
+                .is_none_or(|best| solution.total_distance < best.total_distance)
+            {
+                termination.report(solution.total_distance);
+                trace.record_improvement(iteration, thread, &solution, instance);
+                best = Some(solution);
+            }
+        }
+    }
+
+    best.expect("There should be at least one solution")
+}
+
+/// Tabu search used as the improvement phase of another metaheuristic.
+pub struct Tabu {
+    pub tenure: usize,
+    pub max_iterations: usize,
+}
+
+impl LocalSearch for Tabu {
+    fn improve(&self, solution: &mut Solution, instance: &Instance) {
+        local_search_tabu(
+            solution,
+            instance,
+            self.tenure,
+            self.max_iterations,
+            &|| false,
+        );
+    }
+
+    fn improve_until(
+        &self,
+        solution: &mut Solution,
+        instance: &Instance,
+        should_stop: &dyn Fn() -> bool,
+    ) -> bool {
+        local_search_tabu(
+            solution,
+            instance,
+            self.tenure,
+            self.max_iterations,
+            should_stop,
+        )
+    }
+
+    fn name(&self) -> &str {
+        "Tabu"
+    }
+}
+
+/// Tabu search over the 2-opt neighborhood. Edges removed by a move may not be added back for
+/// `tenure` iterations unless the move yields a new best tour (aspiration). Stops after
+/// `max_iterations` iterations without improving the best tour, leaving it in `solution`.
+/// Returns whether `should_stop` ended the search before that.
+pub fn local_search_tabu(
+    solution: &mut Solution,
+    instance: &Instance,
+    tenure: usize,
+    max_iterations: usize,
+    should_stop: &dyn Fn() -> bool,
+) -> bool {
+    let n = solution.path.len();
+    if n < 4 {
+        return false;
+    }
+
+    solution.eval(instance);
+    let mut best = solution.copy();
+    let mut tabu_until = vec![0usize; n * n];
+    let mut iteration = 0;
+    let mut iterations_without_improvement = 0;
+
+    let mut truncated = false;
+    while iterations_without_improvement < max_iterations {
+        if should_stop() {
+            truncated = true;
+            break;
+        }
+        iteration += 1;
+        iterations_without_improvement += 1;
+
+        let mut best_move = None;
+        let mut best_delta = Cost::MAX;
+
+        for i in 1..n - 1 {
+            for j in i + 2..n {
+                let a = solution.path[i - 1];
+                let b = solution.path[i];
+                let c = solution.path[j - 1];
+                let d = solution.path[j];
+
+                let delta = instance.dist(a, c) + instance.dist(b, d)
+                    - instance.dist(a, b)
+                    - instance.dist(c, d);
+
+                let is_tabu =
+                    tabu_until[a * n + c] > iteration || tabu_until[b * n + d] > iteration;
+                let aspiration = solution.total_distance + delta < best.total_distance;
+
+                if (!is_tabu || aspiration) && delta < best_delta {
+                    best_delta = delta;
+                    best_move = Some((i, j));
+                }
+            }
+        }
+
+        let Some((i, j)) = best_move else {
+            break;
+        };
+
+        let a = solution.path[i - 1];
+        let b = solution.path[i];
+        let c = solution.path[j - 1];
+        let d = solution.path[j];
+        for (x, y) in [(a, b), (c, d)] {
+            tabu_until[x * n + y] = iteration + tenure;
+            tabu_until[y * n + x] = iteration + tenure;
+        }
+
+        solution.path[i..j].reverse();
+        solution.total_distance += best_delta;
+
+        if solution.total_distance < best.total_distance {
+            best = solution.copy();
+            iterations_without_improvement = 0;
+        }
+    }
+
+    *solution = best;
+    truncated
+}
@@ -0,0 +1,113 @@
+use crate::instance::Cost;
+use crate::time::Instant;
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Stop criteria shared by all workers of a run: a time limit, an optional target distance and
+/// an optional number of iterations.
+pub struct Termination {
+    start_time: Instant,
+    time_limit: Duration,
+    target: Option<Cost>,
+    max_iterations: Option<usize>,
+    iterations: AtomicUsize,
+    stop_flag: AtomicBool,
+    interrupted: AtomicBool,
+}
+
+impl Termination {
+    pub fn new(time_limit: Duration) -> Self {
+        Termination {
+            start_time: Instant::now(),
+            time_limit,
+            target: None,
+            max_iterations: None,
+            iterations: AtomicUsize::new(0),
+            stop_flag: AtomicBool::new(false),
+            interrupted: AtomicBool::new(false),
+        }
+    }
+
+    /// Also stops the run as soon as a solution at least as good as `target` is reported.
+    pub fn with_target(mut self, target: Option<Cost>) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Also stops the run once `max_iterations` iterations have been counted.
+    pub fn with_max_iterations(mut self, max_iterations: Option<usize>) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Stop criteria for one part of the run: `fraction` of the time and of the iterations it
+    /// has left, at least one, without the target. The iterations of the part are counted in
+    /// this run by `absorb`.
+    pub fn share(&self, fraction: f64) -> Termination {
+        let fraction = fraction.clamp(0.0, 1.0);
+        let time_limit = if self.time_limit == Duration::MAX {
+            Duration::MAX
+        } else {
+            let remaining = self.time_limit.saturating_sub(self.start_time.elapsed());
+            remaining.mul_f64(fraction)
+        };
+        let max_iterations = self.max_iterations.map(|max| {
+            let remaining = max.saturating_sub(self.iterations.load(Ordering::Relaxed));
+            ((remaining as f64 * fraction).ceil() as usize).max(1)
+        });
+        Termination::new(time_limit).with_max_iterations(max_iterations)
+    }
+
+    /// Counts the iterations of `part`, a share of this run, once it is done.
+    pub fn absorb(&self, part: &Termination) {
+        self.count_iterations(part.iterations.load(Ordering::Relaxed));
+    }
+
+    /// Counts an iteration of any worker, stopping the run once the iteration limit is reached.
+    pub fn count_iteration(&self) {
+        self.count_iterations(1);
+    }
+
+    fn count_iterations(&self, count: usize) {
+        let iterations = self.iterations.fetch_add(count, Ordering::Relaxed) + count;
+        if self.max_iterations.is_some_and(|max| iterations >= max) {
+            self.stop_flag.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns true once the time limit has elapsed or the target has been reached.
+    pub fn should_stop(&self) -> bool {
+        if self.stop_flag.load(Ordering::Relaxed) {
+            return true;
+        }
+        if self.start_time.elapsed() >= self.time_limit {
+            self.stop_flag.store(true, Ordering::Relaxed);
+            return true;
+        }
+        false
+    }
+
+    /// Stops the run at the workers' next check.
+    pub fn stop(&self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Stops the run at the workers' next check on behalf of the user.
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Relaxed);
+        self.stop();
+    }
+
+    /// Whether the run was stopped by `interrupt`.
+    pub fn interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::Relaxed)
+    }
+
+    /// Reports a new best distance, stopping the run if it reaches the target.
+    pub fn report(&self, distance: Cost) {
+        if self.target.is_some_and(|target| distance <= target) {
+            self.stop_flag.store(true, Ordering::Relaxed);
+        }
+    }
+}
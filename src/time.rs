@@ -0,0 +1,7 @@
+//! Clock used for time limits and traces. `std::time::Instant` panics on
+//! `wasm32-unknown-unknown`, so the `wasm` feature reads the browser clock instead.
+
+#[cfg(not(feature = "wasm"))]
+pub use std::time::Instant;
+#[cfg(feature = "wasm")]
+pub use web_time::Instant;
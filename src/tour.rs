@@ -0,0 +1,132 @@
+use crate::instance::Instance;
+use crate::solution::Solution;
+
+/// Cyclic tour supporting the 2-opt move without rebuilding the path. A tour has no fixed
+/// direction: a move may mirror the order in which `next` visits the cities.
+pub trait Tour {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// City after `city` in the current direction.
+    fn next(&self, city: usize) -> usize;
+
+    /// City before `city` in the current direction.
+    fn prev(&self, city: usize) -> usize;
+
+    /// Whether walking forward from `a` reaches `b` before (or at) `c`.
+    fn between(&self, a: usize, b: usize, c: usize) -> bool;
+
+    /// 2-opt move replacing the edges `(a, next(a))` and `(b, next(b))` with `(a, b)` and
+    /// `(next(a), next(b))`.
+    fn two_opt_move(&mut self, a: usize, b: usize);
+
+    /// Cities in tour order starting at `start`.
+    fn path_from(&self, start: usize) -> Vec<usize> {
+        let mut path = Vec::with_capacity(self.len());
+        let mut city = start;
+        for _ in 0..self.len() {
+            path.push(city);
+            city = self.next(city);
+        }
+        path
+    }
+}
+
+/// Tour stored as an array of cities and the position of each city. A 2-opt move reverses the
+/// shorter of the two paths it reconnects, so it costs at most `n / 2` swaps instead of up to
+/// `n`.
+#[derive(Debug, Clone)]
+pub struct ArrayTour {
+    order: Vec<usize>,
+    position: Vec<usize>,
+}
+
+impl ArrayTour {
+    pub fn new(path: &[usize]) -> Self {
+        let mut position = vec![0; path.len()];
+        for (index, &city) in path.iter().enumerate() {
+            position[city] = index;
+        }
+        ArrayTour {
+            order: path.to_vec(),
+            position,
+        }
+    }
+
+    /// Reverses the cyclic range of positions from `i` to `j`, both included.
+    fn reverse(&mut self, mut i: usize, mut j: usize) {
+        let n = self.order.len();
+        let length = (j + n - i) % n + 1;
+        for _ in 0..length / 2 {
+            self.order.swap(i, j);
+            self.position[self.order[i]] = i;
+            self.position[self.order[j]] = j;
+            i = (i + 1) % n;
+            j = (j + n - 1) % n;
+        }
+    }
+
+    /// Evaluated solution with the tour's cities, starting at the instance's start city. When
+    /// the end city is fixed too, the tour is walked in the direction that ends there, so moves
+    /// must keep the edge between the two.
+    pub fn to_solution(&self, instance: &Instance) -> Solution {
+        let start = instance.start_city.unwrap_or(self.order[0]);
+        let mut path = self.path_from(start);
+        if instance.end_city.is_some_and(|end| self.next(start) == end) {
+            path[1..].reverse();
+        }
+        let mut solution = Solution {
+            path,
+            total_distance: 0,
+            longest_edge: 0,
+            risk: 0,
+        };
+        solution.eval(instance);
+        solution
+    }
+}
+
+impl From<&Solution> for ArrayTour {
+    fn from(solution: &Solution) -> Self {
+        ArrayTour::new(&solution.path)
+    }
+}
+
+impl Tour for ArrayTour {
+    fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    fn next(&self, city: usize) -> usize {
+        self.order[(self.position[city] + 1) % self.order.len()]
+    }
+
+    fn prev(&self, city: usize) -> usize {
+        let n = self.order.len();
+        self.order[(self.position[city] + n - 1) % n]
+    }
+
+    fn between(&self, a: usize, b: usize, c: usize) -> bool {
+        let (a, b, c) = (self.position[a], self.position[b], self.position[c]);
+        if a <= c {
+            a <= b && b <= c
+        } else {
+            b >= a || b <= c
+        }
+    }
+
+    fn two_opt_move(&mut self, a: usize, b: usize) {
+        let n = self.order.len();
+        let i = (self.position[a] + 1) % n;
+        let j = self.position[b];
+        // Reversing next(a)..=b or its complement next(b)..=a gives the same cycle.
+        if ((j + n - i) % n + 1) * 2 <= n {
+            self.reverse(i, j);
+        } else {
+            self.reverse((j + 1) % n, self.position[a]);
+        }
+    }
+}
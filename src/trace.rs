@@ -0,0 +1,347 @@
+use crate::error::Error;
+use crate::grasp::symmetric_difference;
+use crate::instance::{Cost, Instance};
+use crate::objective::Objective;
+use crate::solution::Solution;
+use crate::time::Instant;
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::info;
+
+/// Improvement of the best known distance reported by a worker.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImprovementEvent {
+    pub elapsed: Duration,
+    pub iteration: usize,
+    pub thread: usize,
+    pub distance: Cost,
+}
+
+/// Cost of a GRASP iteration's constructed solution and of the same solution after local search.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IterationRecord {
+    pub elapsed: Duration,
+    pub iteration: usize,
+    pub thread: usize,
+    pub constructed: Cost,
+    pub improved: Cost,
+    /// Whether the local search was stopped by the time limit before reaching a local optimum.
+    pub truncated: bool,
+}
+
+/// Current state of a run, published by the solvers for live displays.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub best_path: Vec<usize>,
+    pub elite_distances: Vec<Cost>,
+    /// Pairwise symmetric differences between the elite solutions.
+    pub elite_diversity: Vec<Vec<usize>>,
+    /// Iterations completed by each thread.
+    pub iterations: Vec<usize>,
+}
+
+/// Thread-safe log of the improvement events of a run, in time order and with decreasing
+/// distances.
+pub struct Trace {
+    start_time: Instant,
+    events: Mutex<Vec<ImprovementEvent>>,
+    /// Whether the solvers should also keep `snapshot` up to date.
+    live: bool,
+    snapshot: Mutex<Snapshot>,
+    /// One in this many GRASP iterations is recorded in `iterations`, or none if 0.
+    iteration_sampling: usize,
+    iteration_count: AtomicUsize,
+    iterations: Mutex<Vec<IterationRecord>>,
+    /// Local searches stopped by the time limit before reaching a local optimum.
+    truncated_searches: AtomicUsize,
+    /// Whether `record_improvement` also keeps a copy of each improved solution in `tours`.
+    keep_tours: bool,
+    tours: Mutex<Vec<Solution>>,
+}
+
+impl Trace {
+    pub fn new() -> Self {
+        Trace {
+            start_time: Instant::now(),
+            events: Mutex::new(Vec::new()),
+            live: false,
+            snapshot: Mutex::new(Snapshot::default()),
+            iteration_sampling: 0,
+            iteration_count: AtomicUsize::new(0),
+            iterations: Mutex::new(Vec::new()),
+            truncated_searches: AtomicUsize::new(0),
+            keep_tours: false,
+            tours: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Also records the construction and local search costs of one in `every` GRASP
+    /// iterations.
+    pub fn with_iteration_records(self, every: usize) -> Self {
+        Trace {
+            iteration_sampling: every,
+            ..self
+        }
+    }
+
+    /// Also keeps every improved solution recorded with `record_improvement`, if `keep` is set.
+    pub fn with_tours(self, keep: bool) -> Self {
+        Trace {
+            keep_tours: keep,
+            ..self
+        }
+    }
+
+    /// Trace that also keeps a snapshot of the run for live displays.
+    pub fn live() -> Self {
+        Trace {
+            live: true,
+            ..Self::new()
+        }
+    }
+
+    /// Time since the trace was created.
+    pub fn elapsed(&self) -> Duration {
+        self.start_time.elapsed()
+    }
+
+    /// Improvement events recorded so far, in time order.
+    pub fn events(&self) -> Vec<ImprovementEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        self.snapshot.lock().unwrap().clone()
+    }
+
+    /// Counts an iteration completed by `thread`.
+    pub fn count_iteration(&self, thread: usize) {
+        if !self.live {
+            return;
+        }
+        let iterations = &mut self.snapshot.lock().unwrap().iterations;
+        if iterations.len() <= thread {
+            iterations.resize(thread + 1, 0);
+        }
+        iterations[thread] += 1;
+    }
+
+    /// Publishes the tour of a new best solution.
+    pub fn publish_best(&self, solution: &Solution) {
+        if self.live {
+            self.snapshot.lock().unwrap().best_path = solution.path.clone();
+        }
+    }
+
+    /// Publishes the current elite set (or population). Its distances are kept even when the
+    /// trace is not live, for the plot data export.
+    pub fn publish_elite(&self, elite_set: &[Solution]) {
+        let distances = elite_set.iter().map(|s| s.total_distance).collect();
+        if !self.live {
+            self.snapshot.lock().unwrap().elite_distances = distances;
+            return;
+        }
+        let diversity = elite_set
+            .iter()
+            .map(|a| {
+                elite_set
+                    .iter()
+                    .map(|b| symmetric_difference(a, b))
+                    .collect()
+            })
+            .collect();
+
+        let mut snapshot = self.snapshot.lock().unwrap();
+        snapshot.elite_distances = distances;
+        snapshot.elite_diversity = diversity;
+    }
+
+    /// Records that the best distance improved to `distance`, returning whether it did. Events
+    /// are timestamped and compared under the same lock, so an event that does not beat the
+    /// last one recorded, such as a late report from another thread, is dropped: the recorded
+    /// distances always decrease as time goes on.
+    pub fn record(&self, iteration: usize, thread: usize, distance: Cost) -> bool {
+        let mut events = self.events.lock().unwrap();
+        self.push_event(&mut events, iteration, thread, distance)
+    }
+
+    /// Records an improvement of the best solution to `solution` like `record`, with its value
+    /// under the instance's objective, and if it is recorded also publishes it and logs it while
+    /// still holding the lock, so the logged values decrease too.
+    pub fn record_improvement(
+        &self,
+        iteration: usize,
+        thread: usize,
+        solution: &Solution,
+        instance: &Instance,
+    ) -> bool {
+        let mut events = self.events.lock().unwrap();
+        let value = instance.objective.value(solution);
+        if !self.push_event(&mut events, iteration, thread, value) {
+            return false;
+        }
+        self.publish_best(solution);
+        if self.keep_tours {
+            self.tours.lock().unwrap().push(solution.clone());
+        }
+        match instance.objective {
+            Objective::Total => info!("Improved distance = {}", instance.format_cost(value)),
+            Objective::Bottleneck => {
+                info!("Improved longest edge = {}", instance.format_cost(value))
+            }
+            Objective::Robust => info!("Improved robust cost = {}", instance.format_cost(value)),
+        }
+        true
+    }
+
+    fn push_event(
+        &self,
+        events: &mut Vec<ImprovementEvent>,
+        iteration: usize,
+        thread: usize,
+        distance: Cost,
+    ) -> bool {
+        if events.last().is_some_and(|last| last.distance <= distance) {
+            return false;
+        }
+        events.push(ImprovementEvent {
+            elapsed: self.start_time.elapsed(),
+            iteration,
+            thread,
+            distance,
+        });
+        true
+    }
+
+    /// Improved solutions kept so far (see `with_tours`), in the order they were recorded.
+    pub fn tours(&self) -> Vec<Solution> {
+        self.tours.lock().unwrap().clone()
+    }
+
+    /// Counts a local search that was cut short by the time limit.
+    pub fn count_truncated(&self) {
+        self.truncated_searches.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn truncated_searches(&self) -> usize {
+        self.truncated_searches.load(Ordering::Relaxed)
+    }
+
+    /// Numbers the GRASP iteration about to run, returning its number if it is to be recorded
+    /// with `record_iteration`.
+    pub fn sample_iteration(&self) -> Option<usize> {
+        if self.iteration_sampling == 0 {
+            return None;
+        }
+        let iteration = self.iteration_count.fetch_add(1, Ordering::Relaxed) + 1;
+        iteration
+            .is_multiple_of(self.iteration_sampling)
+            .then_some(iteration)
+    }
+
+    pub fn record_iteration(
+        &self,
+        iteration: usize,
+        thread: usize,
+        constructed: Cost,
+        improved: Cost,
+        truncated: bool,
+    ) {
+        let record = IterationRecord {
+            elapsed: self.start_time.elapsed(),
+            iteration,
+            thread,
+            constructed,
+            improved,
+            truncated,
+        };
+        self.iterations.lock().unwrap().push(record);
+    }
+
+    /// Iteration records so far, in the order they were recorded.
+    pub fn iterations(&self) -> Vec<IterationRecord> {
+        self.iterations.lock().unwrap().clone()
+    }
+
+    /// Elapsed time of the first event reaching `target`, if any.
+    pub fn first_hit(&self, target: Cost) -> Option<Duration> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.distance <= target)
+            .map(|event| event.elapsed)
+            .min()
+    }
+
+    /// Elapsed time of the first event reaching the best distance of the run, if any.
+    pub fn time_to_best(&self) -> Option<Duration> {
+        let events = self.events.lock().unwrap();
+        let best = events.iter().map(|event| event.distance).min()?;
+        events
+            .iter()
+            .filter(|event| event.distance == best)
+            .map(|event| event.elapsed)
+            .min()
+    }
+
+    /// Writes the events, ordered by time, to a CSV file.
+    pub fn write_csv(&self, filename: &str) -> Result<(), Error> {
+        let events = self.events();
+        let write = || -> io::Result<()> {
+            let mut writer = BufWriter::new(File::create(filename)?);
+            writeln!(writer, "elapsed_ms,iteration,thread,distance")?;
+            for event in events {
+                writeln!(
+                    writer,
+                    "{:.3},{},{},{}",
+                    event.elapsed.as_secs_f64() * 1000.0,
+                    event.iteration,
+                    event.thread,
+                    event.distance
+                )?;
+            }
+            writer.flush()
+        };
+        write().map_err(|error| Error::output(filename, error))
+    }
+
+    /// Writes the iteration records, ordered by iteration, to a CSV file.
+    pub fn write_iterations_csv(&self, filename: &str) -> Result<(), Error> {
+        let mut records = self.iterations();
+        records.sort_by_key(|record| record.iteration);
+        let write = || -> io::Result<()> {
+            let mut writer = BufWriter::new(File::create(filename)?);
+            writeln!(
+                writer,
+                "elapsed_ms,iteration,thread,constructed,improved,truncated"
+            )?;
+            for record in records {
+                writeln!(
+                    writer,
+                    "{:.3},{},{},{},{},{}",
+                    record.elapsed.as_secs_f64() * 1000.0,
+                    record.iteration,
+                    record.thread,
+                    record.constructed,
+                    record.improved,
+                    record.truncated
+                )?;
+            }
+            writer.flush()
+        };
+        write().map_err(|error| Error::output(filename, error))
+    }
+}
+
+impl Default for Trace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
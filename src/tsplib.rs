@@ -0,0 +1,281 @@
+use crate::error::{Error, ErrorKind};
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Contents of the instance file `filename`, decompressed when its name ends in `.gz`.
+pub fn read_file(filename: &str) -> Result<String, Error> {
+    if filename.ends_with(".gz") {
+        return read_gzip(filename);
+    }
+    std::fs::read_to_string(filename).map_err(|error| read_error(filename, error))
+}
+
+fn read_error(filename: &str, error: std::io::Error) -> Error {
+    Error::new(
+        ErrorKind::Instance,
+        format!("Failed to read {}: {}", filename, error),
+    )
+}
+
+#[cfg(feature = "gzip")]
+fn read_gzip(filename: &str) -> Result<String, Error> {
+    use std::io::Read;
+
+    let file = std::fs::File::open(filename).map_err(|error| read_error(filename, error))?;
+    let mut content = String::new();
+    flate2::read::GzDecoder::new(file)
+        .read_to_string(&mut content)
+        .map_err(|error| read_error(filename, error))?;
+    Ok(content)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn read_gzip(filename: &str) -> Result<String, Error> {
+    Err(Error::new(
+        ErrorKind::InvalidArguments,
+        format!("Loading {} requires the gzip feature", filename),
+    ))
+}
+
+/// A TSPLIB file split into its specification entries and data sections. Entries are
+/// `KEY: VALUE` lines, with any whitespace around the colon; a section starts at a line whose
+/// first word ends in `_SECTION` and holds every token up to the next entry, section or `EOF`.
+/// Neither entries nor sections have to come in any particular order.
+pub struct Tsplib<'a> {
+    entries: HashMap<&'a str, &'a str>,
+    comments: Vec<&'a str>,
+    sections: HashMap<&'a str, Vec<&'a str>>,
+}
+
+impl<'a> Tsplib<'a> {
+    pub fn tokenize(content: &'a str) -> Self {
+        let mut entries = HashMap::new();
+        let mut comments = Vec::new();
+        let mut sections: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut section = None;
+
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() {
+                continue;
+            }
+            if line == "EOF" {
+                break;
+            }
+
+            let mut words = line.split_whitespace();
+            let first = words.next().unwrap();
+            let keyword = first.split(':').next().unwrap();
+            if keyword.ends_with("_SECTION") {
+                section = Some(keyword);
+                // A section keyword may be followed by its first tokens on the same line.
+                let rest = first[keyword.len()..].trim_start_matches(':');
+                let tokens = sections.entry(keyword).or_default();
+                tokens.extend(rest.split_whitespace().chain(words));
+                continue;
+            }
+
+            match line.split_once(':') {
+                Some((key, value)) if is_keyword(key.trim()) => {
+                    let (key, value) = (key.trim(), value.trim());
+                    if key == "COMMENT" {
+                        comments.push(value);
+                    } else {
+                        entries.insert(key, value);
+                    }
+                    section = None;
+                }
+                _ => {
+                    // Stray lines outside any section are ignored, like unknown entries.
+                    if let Some(section) = section {
+                        sections
+                            .get_mut(section)
+                            .unwrap()
+                            .extend(line.split_whitespace());
+                    }
+                }
+            }
+        }
+
+        Tsplib {
+            entries,
+            comments,
+            sections,
+        }
+    }
+
+    /// Value of the specification entry `key`.
+    pub fn entry(&self, key: &str) -> Option<&'a str> {
+        self.entries.get(key).copied()
+    }
+
+    /// Value of the entry `key` parsed as a `T`, failing if it is not one.
+    pub fn parse_entry<T: FromStr>(&self, key: &str) -> Result<Option<T>, Error> {
+        self.entry(key)
+            .map(|value| {
+                value.parse().map_err(|_| {
+                    Error::new(
+                        ErrorKind::Instance,
+                        format!("Invalid {} in the instance file: {}", key, value),
+                    )
+                })
+            })
+            .transpose()
+    }
+
+    /// The COMMENT entries, in file order.
+    pub fn comments(&self) -> &[&'a str] {
+        &self.comments
+    }
+
+    /// Tokens of the section `name`, if the file has it.
+    pub fn section(&self, name: &str) -> Option<&[&'a str]> {
+        self.sections.get(name).map(Vec::as_slice)
+    }
+
+    /// Tokens of the section `name` parsed as numbers, failing on any other token.
+    pub fn numbers<T: FromStr>(&self, name: &str) -> Result<Option<Vec<T>>, Error> {
+        self.section(name)
+            .map(|tokens| {
+                tokens
+                    .iter()
+                    .map(|token| {
+                        token.parse().map_err(|_| {
+                            Error::new(
+                                ErrorKind::Instance,
+                                format!("Invalid value in {}: {}", name, token),
+                            )
+                        })
+                    })
+                    .collect()
+            })
+            .transpose()
+    }
+}
+
+/// Whether `key` looks like a TSPLIB keyword: upper-case letters, digits and underscores.
+fn is_keyword(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL_MATRIX: &str = "\
+NAME: full4
+TYPE: TSP
+COMMENT: Four cities, the distances given in full
+DIMENSION : 4
+EDGE_WEIGHT_TYPE: EXPLICIT
+EDGE_WEIGHT_FORMAT:FULL_MATRIX
+EDGE_WEIGHT_SECTION
+0 3 4 5
+3 0 6 7
+4 6 0 8
+5 7 8 0
+EOF
+";
+
+    const UPPER_ROW: &str = "\
+NAME: upper4
+COMMENT: Four cities
+COMMENT: only the upper triangle: no diagonal
+TYPE: TSP
+DIMENSION: 4
+EDGE_WEIGHT_TYPE: EXPLICIT
+EDGE_WEIGHT_FORMAT: UPPER_ROW
+EDGE_WEIGHT_SECTION 3 4 5
+  6 7
+  8
+EOF
+";
+
+    #[test]
+    fn full_matrix_entries_and_section() {
+        let tsplib = Tsplib::tokenize(FULL_MATRIX);
+        assert_eq!(tsplib.entry("NAME"), Some("full4"));
+        assert_eq!(tsplib.entry("DIMENSION"), Some("4"));
+        assert_eq!(tsplib.entry("EDGE_WEIGHT_FORMAT"), Some("FULL_MATRIX"));
+        assert_eq!(tsplib.parse_entry::<usize>("DIMENSION").unwrap(), Some(4));
+        assert_eq!(tsplib.entry("COMMENT"), None);
+        assert_eq!(
+            tsplib.comments(),
+            ["Four cities, the distances given in full"]
+        );
+        let weights: Vec<i64> = tsplib.numbers("EDGE_WEIGHT_SECTION").unwrap().unwrap();
+        assert_eq!(weights, [0, 3, 4, 5, 3, 0, 6, 7, 4, 6, 0, 8, 5, 7, 8, 0]);
+    }
+
+    #[test]
+    fn upper_row_with_tokens_on_the_section_line() {
+        let tsplib = Tsplib::tokenize(UPPER_ROW);
+        assert_eq!(tsplib.entry("EDGE_WEIGHT_FORMAT"), Some("UPPER_ROW"));
+        assert_eq!(
+            tsplib.comments(),
+            ["Four cities", "only the upper triangle: no diagonal"]
+        );
+        assert_eq!(
+            tsplib.section("EDGE_WEIGHT_SECTION").unwrap(),
+            ["3", "4", "5", "6", "7", "8"]
+        );
+    }
+
+    #[test]
+    fn formats_give_the_same_distances() {
+        let full = crate::instance::Instance::parse(FULL_MATRIX, 0).unwrap();
+        let upper = crate::instance::Instance::parse(UPPER_ROW, 0).unwrap();
+        for a in 0..4 {
+            for b in 0..4 {
+                assert_eq!(full.dist(a, b), upper.dist(a, b), "{a} {b}");
+            }
+        }
+        assert_eq!(full.dist(0, 3), 5);
+        assert_eq!(upper.dist(3, 2), 8);
+    }
+
+    #[test]
+    fn section_keyword_with_colon_and_tokens() {
+        let tsplib = Tsplib::tokenize("DIMENSION: 2\nNODE_COORD_SECTION: 1 0 0\n2 3 4\nEOF\n");
+        assert_eq!(
+            tsplib.section("NODE_COORD_SECTION").unwrap(),
+            ["1", "0", "0", "2", "3", "4"]
+        );
+    }
+
+    #[test]
+    fn sections_end_at_the_next_entry_or_eof() {
+        let content = "\
+DISPLAY_DATA_SECTION
+1 5 5
+DIMENSION: 1
+stray tokens
+NODE_COORD_SECTION
+1 0 0
+EOF
+2 1 1
+";
+        let tsplib = Tsplib::tokenize(content);
+        assert_eq!(
+            tsplib.section("DISPLAY_DATA_SECTION").unwrap(),
+            ["1", "5", "5"]
+        );
+        assert_eq!(
+            tsplib.section("NODE_COORD_SECTION").unwrap(),
+            ["1", "0", "0"]
+        );
+        assert_eq!(tsplib.entry("DIMENSION"), Some("1"));
+        assert!(tsplib.section("EDGE_WEIGHT_SECTION").is_none());
+    }
+
+    #[test]
+    fn invalid_values_are_errors() {
+        let tsplib = Tsplib::tokenize("DIMENSION: four\nEDGE_WEIGHT_SECTION\n1 x 3\n");
+        assert!(tsplib.parse_entry::<usize>("DIMENSION").is_err());
+        assert!(tsplib.numbers::<i64>("EDGE_WEIGHT_SECTION").is_err());
+        assert_eq!(tsplib.numbers::<i64>("NODE_COORD_SECTION").unwrap(), None);
+    }
+}
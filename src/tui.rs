@@ -0,0 +1,190 @@
+use crate::instance::{Cost, Instance};
+use crate::termination::Termination;
+use crate::trace::{Snapshot, Trace};
+
+use ratatui::crossterm::event::{self, Event, KeyCode};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::Color;
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::canvas::{Canvas, Line as Segment};
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Paragraph, Row, Sparkline, Table};
+use ratatui::Frame;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Interval between redraws of the dashboard.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Draws a live dashboard of the run traced by `trace` until `done` is set: the elite set with
+/// its pairwise diversity, the improvement history, the iteration throughput of each thread and,
+/// for instances with coordinates, the current best tour. Pressing `q` stops the run.
+pub fn dashboard(instance: &Instance, termination: &Termination, trace: &Trace, done: &AtomicBool) {
+    let mut terminal = ratatui::init();
+    while !done.load(Ordering::Relaxed) {
+        terminal
+            .draw(|frame| draw(frame, instance, trace))
+            .expect("Failed to draw the dashboard");
+
+        if event::poll(REFRESH_INTERVAL).expect("Failed to read terminal events") {
+            if let Event::Key(key) = event::read().expect("Failed to read terminal events") {
+                if key.code == KeyCode::Char('q') {
+                    termination.interrupt();
+                }
+            }
+        }
+    }
+    ratatui::restore();
+}
+
+fn draw(frame: &mut Frame, instance: &Instance, trace: &Trace) {
+    let snapshot = trace.snapshot();
+    let elapsed = trace.elapsed();
+    let history: Vec<u64> = trace
+        .events()
+        .iter()
+        .map(|event| event.distance as u64)
+        .collect();
+
+    let [header, body] =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(frame.area());
+    let [left, tour] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(body);
+    let [elite, improvements, throughput] = Layout::vertical([
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
+        Constraint::Percentage(30),
+    ])
+    .areas(left);
+
+    let best = history
+        .iter()
+        .min()
+        .map_or("-".to_string(), |&best| instance.format_cost(best as Cost));
+    frame.render_widget(
+        Paragraph::new(format!(
+            "Elapsed: {:.1?}   Best distance: {}   Press q to stop",
+            elapsed, best
+        )),
+        header,
+    );
+
+    draw_elite(frame, elite, instance, &snapshot);
+    draw_improvements(frame, improvements, &history);
+    draw_throughput(frame, throughput, &snapshot, elapsed);
+    draw_tour(frame, tour, instance, &snapshot);
+}
+
+fn draw_elite(frame: &mut Frame, area: Rect, instance: &Instance, snapshot: &Snapshot) {
+    let size = snapshot.elite_distances.len();
+    let header = Row::new(
+        ["#".to_string(), "Distance".to_string()]
+            .into_iter()
+            .chain((1..=size).map(|i| i.to_string())),
+    );
+    let rows = snapshot
+        .elite_distances
+        .iter()
+        .zip(&snapshot.elite_diversity)
+        .enumerate()
+        .map(|(i, (&distance, diversity))| {
+            Row::new(
+                [(i + 1).to_string(), instance.format_cost(distance)]
+                    .into_iter()
+                    .chain(diversity.iter().map(|d| d.to_string())),
+            )
+        });
+    let widths = [Constraint::Length(3), Constraint::Length(10)]
+        .into_iter()
+        .chain(std::iter::repeat_n(Constraint::Length(4), size));
+
+    frame.render_widget(
+        Table::new(rows, widths)
+            .header(header)
+            .block(Block::bordered().title("Elite set (distance, pairwise diversity)")),
+        area,
+    );
+}
+
+fn draw_improvements(frame: &mut Frame, area: Rect, history: &[u64]) {
+    // Heights are relative to the best distance so that the improvements stay visible.
+    let best = history.iter().copied().min().unwrap_or(0);
+    let width = area.width.saturating_sub(2) as usize;
+    let data: Vec<u64> = history[history.len().saturating_sub(width)..]
+        .iter()
+        .map(|&distance| distance - best + 1)
+        .collect();
+
+    frame.render_widget(
+        Sparkline::default()
+            .data(&data)
+            .block(Block::bordered().title("Improvement history")),
+        area,
+    );
+}
+
+fn draw_throughput(frame: &mut Frame, area: Rect, snapshot: &Snapshot, elapsed: Duration) {
+    let seconds = elapsed.as_secs_f64().max(1e-3);
+    let bars: Vec<Bar> = snapshot
+        .iterations
+        .iter()
+        .enumerate()
+        .map(|(thread, &iterations)| {
+            Bar::default()
+                .value((iterations as f64 / seconds).round() as u64)
+                .label(Line::from(thread.to_string()))
+        })
+        .collect();
+
+    frame.render_widget(
+        BarChart::default()
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(5)
+            .block(Block::bordered().title("Iterations per second by thread")),
+        area,
+    );
+}
+
+fn draw_tour(frame: &mut Frame, area: Rect, instance: &Instance, snapshot: &Snapshot) {
+    let block = Block::bordered().title("Best tour");
+    let Some(coords) = instance.coords() else {
+        frame.render_widget(
+            Paragraph::new("The instance has no coordinates").block(block),
+            area,
+        );
+        return;
+    };
+
+    let (min_x, max_x) = coords
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &(x, _)| {
+            (lo.min(x), hi.max(x))
+        });
+    let (min_y, max_y) = coords
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &(_, y)| {
+            (lo.min(y), hi.max(y))
+        });
+
+    let path = &snapshot.best_path;
+    let edges = if instance.open_tour {
+        path.len().saturating_sub(1)
+    } else {
+        path.len()
+    };
+    frame.render_widget(
+        Canvas::default()
+            .block(block)
+            .marker(Marker::Braille)
+            .x_bounds([min_x, max_x])
+            .y_bounds([min_y, max_y])
+            .paint(|ctx| {
+                for i in 0..edges {
+                    let (x1, y1) = coords[path[i]];
+                    let (x2, y2) = coords[path[(i + 1) % path.len()]];
+                    ctx.draw(&Segment::new(x1, y1, x2, y2, Color::Cyan));
+                }
+            }),
+        area,
+    );
+}
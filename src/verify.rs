@@ -0,0 +1,23 @@
+use crate::error::Error;
+use crate::instance::{Cost, Instance};
+use crate::optimum::{gap, known_optimum};
+use crate::solution::Solution;
+
+/// Checks that the tour in `tour_file` is a permutation of the cities of `instance_file`,
+/// evaluates it and reports the gap to the best-known tour length. Fails with the first problem
+/// of the tour if it is invalid.
+pub fn verify(instance_file: &str, tour_file: &str, optimum: Option<Cost>) -> Result<(), Error> {
+    let instance = Instance::load(instance_file)?;
+    let tour = Solution::load_valid_tour(tour_file, &instance)?;
+
+    println!("Valid tour {}", tour_file);
+    println!("Total distance: {}", tour.total_distance);
+    if let Some(optimum) = optimum.or_else(|| instance.name.as_deref().and_then(known_optimum)) {
+        println!(
+            "Best known: {} (gap = {:.2}%)",
+            optimum,
+            gap(tour.total_distance, optimum)
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::construction::NearestNeighbor;
+use crate::grasp::grasp;
+use crate::instance::Instance;
+use crate::local_search::TwoOpt;
+use crate::termination::Termination;
+use crate::trace::Trace;
+
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+
+/// Best tour found by `solve`.
+#[wasm_bindgen]
+pub struct Tour {
+    path: Vec<u32>,
+    distance: f64,
+}
+
+#[wasm_bindgen]
+impl Tour {
+    /// Cities in visiting order, 0-based.
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> Vec<u32> {
+        self.path.clone()
+    }
+
+    /// Length of the tour.
+    #[wasm_bindgen(getter)]
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+}
+
+/// Solves the TSPLIB instance given as text with the basic GRASP for `time_limit_ms`
+/// milliseconds. Runs on the calling thread, and throws if the instance cannot be parsed.
+#[wasm_bindgen]
+pub fn solve(instance: &str, time_limit_ms: u32) -> Result<Tour, JsError> {
+    let instance = Instance::parse(instance, 0)?;
+    let termination = Termination::new(Duration::from_millis(time_limit_ms as u64));
+    let solution = grasp(
+        &instance,
+        &termination,
+        &NearestNeighbor::default(),
+        &TwoOpt,
+        &Trace::new(),
+    );
+
+    Ok(Tour {
+        path: solution.path.iter().map(|&city| city as u32).collect(),
+        distance: solution.total_distance as f64,
+    })
+}